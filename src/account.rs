@@ -0,0 +1,151 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, AccountError, Error, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    person::{ChangePassword, PasswordChangeAfterReset, PasswordReset, VerifyEmail},
+    sensitive::Sensitive,
+};
+use url::Url;
+
+/// The lengths Lemmy accepts for passwords, in characters.
+const PASSWORD_LENGTH: std::ops::RangeInclusive<usize> = 10..=60;
+
+/// Fails with [`AccountError::InvalidPassword`] before sending a password the instance would reject.
+fn check_password(password: &str) -> Result<(), AccountError> {
+    if PASSWORD_LENGTH.contains(&password.chars().count()) {
+        Ok(())
+    } else {
+        Err(AccountError::InvalidPassword)
+    }
+}
+
+/// Takes the token from a link Lemmy emails, such as `https://lemmy.ml/password_change/<token>`, where `page` is the path segment before the token. Anything that is not such a link is taken to be the token itself.
+fn token_from_link(link: &str, page: &str) -> String {
+    let link = link.trim();
+    let token = Url::parse(link).ok().and_then(|url| {
+        let mut segments = url.path_segments()?;
+        (segments.next()? == page)
+            .then(|| segments.next())
+            .flatten()
+            .map(str::to_owned)
+    });
+
+    token.unwrap_or_else(|| link.to_owned())
+}
+
+#[must_use = "the password is only changed once the reset is completed"]
+/// A password reset that was requested with [`LemmyClient::request_password_reset`], waiting for the token the instance emailed.
+pub struct PasswordResetRequest<'a> {
+    client: &'a LemmyClient,
+    email: String,
+}
+
+impl PasswordResetRequest<'_> {
+    /// The email address the reset link was sent to.
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    /// Sets the new password with the emailed reset link, or just its token. Every session of the account is logged out.
+    ///
+    /// Fails with [`AccountError::InvalidPassword`] if the password is too short or too long, and with [`AccountError::TokenExpired`] if the link expired or was already used.
+    pub async fn complete(
+        self,
+        link_or_token: &str,
+        new_password: &str,
+    ) -> Result<(), AccountError> {
+        self.client
+            .complete_password_reset(link_or_token, new_password)
+            .await
+    }
+}
+
+impl LemmyClient {
+    /// Asks the instance to email a link to reset the password of the account with `email`, for users who forgot their password. Finish with [`PasswordResetRequest::complete`] once the user has the link.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # async fn example(client: LemmyClient) -> Result<(), lemmy_client::Error> {
+    /// let reset = client.request_password_reset("alice@example.com").await?;
+    /// // The user pastes the link from the email.
+    /// reset
+    ///     .complete("https://lemmy.ml/password_change/abc123", "a new password")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request_password_reset(
+        &self,
+        email: &str,
+    ) -> Result<PasswordResetRequest<'_>, AccountError> {
+        let email = email.trim().to_owned();
+        let form = PasswordReset {
+            email: Sensitive::new(email.clone()),
+        };
+        self.client
+            .reset_password(LemmyRequest::from(form), &self.headers)
+            .await?;
+
+        Ok(PasswordResetRequest {
+            client: self,
+            email,
+        })
+    }
+
+    /// Sets a new password with a reset link, or just its token, e.g. when the link was opened in the app without requesting the reset in it first. See [`PasswordResetRequest::complete`].
+    pub async fn complete_password_reset(
+        &self,
+        link_or_token: &str,
+        new_password: &str,
+    ) -> Result<(), AccountError> {
+        check_password(new_password)?;
+        let form = PasswordChangeAfterReset {
+            token: Sensitive::new(token_from_link(link_or_token, "password_change")),
+            password: Sensitive::new(new_password.to_owned()),
+            password_verify: Sensitive::new(new_password.to_owned()),
+        };
+        self.client
+            .change_password_after_reset(LemmyRequest::from(form), &self.headers)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Verifies the email address of an account with the link the instance emailed after signing up or changing the address, or just its token. Fails with [`AccountError::TokenExpired`] if the link expired or was already used.
+    pub async fn verify_email_link(&self, link_or_token: &str) -> Result<(), AccountError> {
+        let form = VerifyEmail {
+            token: token_from_link(link_or_token, "verify_email"),
+        };
+        self.client
+            .verify_email(LemmyRequest::from(form), &self.headers)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Changes the password of the account that is logged in, returning the new JWT. Every other session of the account is logged out, and so is `jwt`.
+    ///
+    /// Fails with [`AccountError::InvalidPassword`] if the new password is too short or too long, and with [`AccountError::WrongPassword`] if `old_password` is wrong.
+    pub async fn update_password(
+        &self,
+        old_password: &str,
+        new_password: &str,
+        jwt: String,
+    ) -> Result<String, AccountError> {
+        check_password(new_password)?;
+        let form = ChangePassword {
+            new_password: Sensitive::new(new_password.to_owned()),
+            new_password_verify: Sensitive::new(new_password.to_owned()),
+            old_password: Sensitive::new(old_password.to_owned()),
+        };
+        let response = self
+            .client
+            .change_password(LemmyRequest::with_jwt(form, Some(jwt)), &self.headers)
+            .await?;
+
+        response
+            .jwt
+            .map(Sensitive::into_inner)
+            .ok_or_else(|| AccountError::Other(Error::new("not_logged_in")))
+    }
+}
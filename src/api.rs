@@ -0,0 +1,179 @@
+use crate::{
+    endpoints::with_endpoints,
+    form::LemmyForm,
+    lemmy_client_trait::LemmyClientInternal,
+    newer_api::*,
+    registry::{endpoint, ApiVersion, AuthRequirement, EndpointInfo},
+    Error, LemmyClient, LemmyRequest, LemmyResult,
+};
+use http::Method;
+use lemmy_api_common::{
+    comment::*, community::*, custom_emoji::*, lemmy_db_schema::source::login_token::LoginToken,
+    person::*, post::*, private_message::*, site::*, SuccessResponse,
+};
+use std::{future::Future, pin::Pin};
+
+#[cfg(not(target_family = "wasm"))]
+/// The future returned by the methods of [`LemmyApi`].
+pub type ApiFuture<'a, T> = Pin<Box<dyn Future<Output = LemmyResult<T>> + Send + 'a>>;
+
+#[cfg(target_family = "wasm")]
+/// The future returned by the methods of [`LemmyApi`].
+pub type ApiFuture<'a, T> = Pin<Box<dyn Future<Output = LemmyResult<T>> + 'a>>;
+
+macro_rules! lemmy_api {
+    ($(
+        $(#[$doc:meta])*
+        $name:ident($variant:ident):
+        $method:ident $path:literal, $auth:ident, ($major:literal, $minor:literal, $patch:literal):
+        $form:tt => $response:ty;
+    )+) => {
+        /// Every endpoint supported by this crate, in the order they are declared.
+        ///
+        /// ```
+        /// use lemmy_client::{endpoint, AuthRequirement, ENDPOINTS};
+        ///
+        /// let get_site = endpoint("get_site").unwrap();
+        /// assert_eq!(get_site.path, "site");
+        /// assert_eq!(get_site.auth, AuthRequirement::Optional);
+        /// assert!(ENDPOINTS.iter().any(|endpoint| endpoint.name == "hide_post"));
+        /// ```
+        pub static ENDPOINTS: &[EndpointInfo] = &[
+            $(
+                EndpointInfo {
+                    name: stringify!($name),
+                    method: Method::$method,
+                    path: $path,
+                    auth: AuthRequirement::$auth,
+                    min_version: ApiVersion::new($major, $minor, $patch),
+                },
+            )+
+        ];
+
+        /// An object safe version of the endpoint methods of [`LemmyClient`], for storing a client as `Arc<dyn LemmyApi>` and swapping it for a mock or caching implementation.
+        ///
+        /// Every method has a default implementation that fails with a `not_implemented` error, so implementations only need to provide the endpoints they use.
+        ///
+        /// ```
+        /// use lemmy_client::{
+        ///     lemmy_api_common::site::GetSiteResponse, ApiFuture, LemmyApi, LemmyClient,
+        ///     LemmyRequest,
+        /// };
+        /// use std::sync::Arc;
+        ///
+        /// struct MockApi;
+        ///
+        /// impl LemmyApi for MockApi {
+        ///     fn get_site(&self, _request: LemmyRequest<()>) -> ApiFuture<'_, GetSiteResponse> {
+        ///         Box::pin(async { Err(lemmy_client::Error::new("site_not_found")) })
+        ///     }
+        /// }
+        ///
+        /// let api: Arc<dyn LemmyApi> = Arc::new(MockApi);
+        /// ```
+        pub trait LemmyApi {
+            /// Sends a request to whichever endpoint it is for. See [`EndpointRequest`].
+            fn call(&self, request: EndpointRequest) -> ApiFuture<'_, EndpointResponse> {
+                match request {
+                    $(
+                        EndpointRequest::$variant(request) => {
+                            let response = self.$name(request);
+                            Box::pin(async move { response.await.map(EndpointResponse::$variant) })
+                        }
+                    )+
+                }
+            }
+
+            $(
+                #[doc = concat!("See [`LemmyClient::", stringify!($name), "`].")]
+                fn $name(&self, request: LemmyRequest<$form>) -> ApiFuture<'_, $response> {
+                    let _ = request;
+                    Box::pin(async { Err(Error::new("not_implemented")) })
+                }
+            )+
+        }
+
+        #[derive(Debug, Clone)]
+        #[allow(clippy::large_enum_variant)]
+        /// A request to any of the endpoints of [`LemmyApi`], for middleware, queues, and batch executors that handle every endpoint the same way. Send it with [`LemmyClient::call`].
+        ///
+        /// Each variant is named after the method of the same endpoint.
+        pub enum EndpointRequest {
+            $(
+                #[doc = concat!("A request for [`LemmyClient::", stringify!($name), "`].")]
+                $variant(LemmyRequest<$form>),
+            )+
+        }
+
+        impl EndpointRequest {
+            /// The name of the method for this endpoint, such as `get_site`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $( Self::$variant(_) => stringify!($name), )+
+                }
+            }
+
+            /// Information about the endpoint the request is for.
+            pub fn info(&self) -> &'static EndpointInfo {
+                endpoint(self.name()).expect("Every variant has an entry in ENDPOINTS")
+            }
+
+            /// The JWT sent with the request.
+            pub fn jwt(&self) -> Option<&str> {
+                match self {
+                    $( Self::$variant(request) => request.jwt.as_deref(), )+
+                }
+            }
+        }
+
+        #[derive(Debug)]
+        #[allow(clippy::large_enum_variant)]
+        /// The response to an [`EndpointRequest`]. Has the same variant as the request it answers.
+        pub enum EndpointResponse {
+            $(
+                #[doc = concat!("A response from [`LemmyClient::", stringify!($name), "`].")]
+                $variant($response),
+            )+
+        }
+
+        impl LemmyApi for LemmyClient {
+            $(
+                fn $name(&self, request: LemmyRequest<$form>) -> ApiFuture<'_, $response> {
+                    Box::pin(async move {
+                        if self.validate_forms {
+                            LemmyForm::validate(&request.body)?;
+                        }
+
+                        let response = self.client.$name(request, &self.headers).await?;
+                        self.filter_response($path, response)
+                    })
+                }
+            )+
+        }
+    };
+}
+
+with_endpoints!(lemmy_api);
+
+impl LemmyClient {
+    /// Sends a request to whichever endpoint it is for. See [`EndpointRequest`].
+    ///
+    /// ```no_run
+    /// use lemmy_client::{ClientOptions, EndpointRequest, EndpointResponse, LemmyClient, LemmyRequest};
+    ///
+    /// # async fn example() -> Result<(), lemmy_client::Error> {
+    /// let client = LemmyClient::new(ClientOptions {
+    ///     domain: String::from("lemmy.ml"),
+    ///     secure: true,
+    /// });
+    ///
+    /// if let EndpointResponse::GetSite(site) = client.call(EndpointRequest::GetSite(LemmyRequest::empty())).await? {
+    ///     println!("{}", site.site_view.site.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call(&self, request: EndpointRequest) -> LemmyResult<EndpointResponse> {
+        LemmyApi::call(self, request).await
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal,
+    response::LemmyResult,
+    utils::{paged_stream, sleep},
+    CommunityHandle, Error, LemmyClient, LemmyRequest,
+};
+use futures::TryStreamExt;
+use lemmy_api_common::{
+    comment::GetComments,
+    community::GetCommunity,
+    lemmy_db_schema::{
+        newtypes::{PersonId, PostId},
+        CommentSortType, ListingType, SortType,
+    },
+    lemmy_db_views::structs::CommentView,
+    person::GetPersonDetails,
+    post::GetPosts,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const COMMUNITY_FILE: &str = "community.json";
+const POSTS_FILE: &str = "posts.jsonl";
+const COMMENTS_FILE: &str = "comments.jsonl";
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+const PERSON_FILE: &str = "person.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [`LemmyClient::archive_community`].
+pub struct ArchiveOptions {
+    /// How long to wait before each request, to avoid hitting the instance's rate limits.
+    pub request_delay: Duration,
+    /// How many posts or comments to fetch per request. Lemmy allows at most 50.
+    pub page_size: i64,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            request_delay: Duration::from_millis(500),
+            page_size: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// How far an archive has progressed. Written to `checkpoint.json` after every page of posts.
+pub struct ArchiveCheckpoint {
+    /// The next page of posts to fetch.
+    pub next_page: i64,
+    /// Number of posts written so far.
+    pub posts: usize,
+    /// Number of comments written so far.
+    pub comments: usize,
+    /// Length of `posts.jsonl` when the checkpoint was written.
+    posts_len: u64,
+    /// Length of `comments.jsonl` when the checkpoint was written.
+    comments_len: u64,
+    /// True once every post has been archived.
+    pub complete: bool,
+}
+
+impl ArchiveCheckpoint {
+    /// Reads the checkpoint of the archive in `dir`, if there is one.
+    pub fn read(dir: impl AsRef<Path>) -> LemmyResult<Option<Self>> {
+        match fs::read(dir.as_ref().join(CHECKPOINT_FILE)) {
+            Ok(checkpoint) => serde_json::from_slice(&checkpoint)
+                .map(Some)
+                .map_err(archive_error),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(archive_error(e)),
+        }
+    }
+
+    fn write(&self, dir: &Path) -> LemmyResult<()> {
+        let checkpoint = serde_json::to_vec_pretty(self).map_err(archive_error)?;
+        let temp_path = dir.join(format!("{CHECKPOINT_FILE}.tmp"));
+        fs::write(&temp_path, checkpoint).map_err(archive_error)?;
+        fs::rename(temp_path, dir.join(CHECKPOINT_FILE)).map_err(archive_error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Progress of [`LemmyClient::export_user_history`], reported after every page.
+pub struct HistoryProgress {
+    /// The last page that was fetched.
+    pub page: i64,
+    /// Number of posts written so far.
+    pub posts: usize,
+    /// Number of comments written so far.
+    pub comments: usize,
+}
+
+fn archive_error(e: impl std::fmt::Display) -> Error {
+    Error::new(format!("archive_failed: {e}"))
+}
+
+/// Opens a JSONL file for appending, dropping anything written after the last checkpoint.
+fn open_jsonl(path: PathBuf, checkpoint_len: u64) -> LemmyResult<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(archive_error)?;
+    file.set_len(checkpoint_len).map_err(archive_error)?;
+
+    Ok((BufWriter::new(file), checkpoint_len))
+}
+
+fn write_line(
+    (writer, len): &mut (BufWriter<File>, u64),
+    item: &impl Serialize,
+) -> LemmyResult<()> {
+    let mut line = serde_json::to_vec(item).map_err(archive_error)?;
+    line.push(b'\n');
+    writer.write_all(&line).map_err(archive_error)?;
+    *len += line.len() as u64;
+
+    Ok(())
+}
+
+impl LemmyClient {
+    /// Archives every post and comment of a community into `dir` as newline delimited JSON.
+    ///
+    /// The directory ends up with `community.json`, `posts.jsonl` with one [`PostView`](lemmy_api_common::lemmy_db_views::structs::PostView) per line, and `comments.jsonl` with one [`CommentView`] per line. Progress is saved to `checkpoint.json` after every page of posts, so calling this again with the same directory resumes where an interrupted archive stopped. Calling it on a complete archive does nothing.
+    pub async fn archive_community(
+        &self,
+        community: &CommunityHandle,
+        dir: impl AsRef<Path>,
+        options: &ArchiveOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<ArchiveCheckpoint> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(archive_error)?;
+        let mut checkpoint = ArchiveCheckpoint::read(dir)?.unwrap_or(ArchiveCheckpoint {
+            next_page: 1,
+            ..Default::default()
+        });
+        if checkpoint.complete {
+            return Ok(checkpoint);
+        }
+
+        let community_id = self.resolve_community_id(community, jwt.clone()).await?;
+        let form = GetCommunity {
+            id: Some(community_id),
+            name: None,
+        };
+        let community = self
+            .client
+            .get_community(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+            .await?;
+        let community = serde_json::to_vec_pretty(&community).map_err(archive_error)?;
+        fs::write(dir.join(COMMUNITY_FILE), community).map_err(archive_error)?;
+
+        let mut posts_file = open_jsonl(dir.join(POSTS_FILE), checkpoint.posts_len)?;
+        let mut comments_file = open_jsonl(dir.join(COMMENTS_FILE), checkpoint.comments_len)?;
+        loop {
+            sleep(options.request_delay).await;
+            let form = GetPosts {
+                type_: Some(ListingType::All),
+                sort: Some(SortType::Old),
+                page: Some(checkpoint.next_page),
+                limit: Some(options.page_size),
+                community_id: Some(community_id),
+                ..Default::default()
+            };
+            let posts = self
+                .client
+                .list_posts(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                .await?
+                .posts;
+            if posts.is_empty() {
+                checkpoint.complete = true;
+                checkpoint.write(dir)?;
+                return Ok(checkpoint);
+            }
+
+            for post_view in &posts {
+                write_line(&mut posts_file, post_view)?;
+                let comments = self
+                    .archive_comments(post_view.post.id, options, jwt.clone())
+                    .await?;
+                for comment_view in &comments {
+                    write_line(&mut comments_file, comment_view)?;
+                }
+                checkpoint.comments += comments.len();
+            }
+
+            posts_file.0.flush().map_err(archive_error)?;
+            comments_file.0.flush().map_err(archive_error)?;
+            checkpoint.posts += posts.len();
+            checkpoint.next_page += 1;
+            checkpoint.posts_len = posts_file.1;
+            checkpoint.comments_len = comments_file.1;
+            checkpoint.write(dir)?;
+        }
+    }
+
+    /// Exports every post and comment of a user into `dir` as newline delimited JSON.
+    ///
+    /// The directory ends up with `person.json`, `posts.jsonl` with one [`PostView`](lemmy_api_common::lemmy_db_views::structs::PostView) per line, and `comments.jsonl` with one [`CommentView`] per line. Anyone can export the public history of a user, while admins also get content that was removed. `progress` is called after every page.
+    pub async fn export_user_history(
+        &self,
+        person_id: PersonId,
+        dir: impl AsRef<Path>,
+        options: &ArchiveOptions,
+        mut progress: impl FnMut(HistoryProgress),
+        jwt: Option<String>,
+    ) -> LemmyResult<HistoryProgress> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(archive_error)?;
+        let mut posts_file = open_jsonl(dir.join(POSTS_FILE), 0)?;
+        let mut comments_file = open_jsonl(dir.join(COMMENTS_FILE), 0)?;
+        let mut current = HistoryProgress::default();
+        loop {
+            sleep(options.request_delay).await;
+            current.page += 1;
+            let form = GetPersonDetails {
+                person_id: Some(person_id),
+                sort: Some(SortType::Old),
+                page: Some(current.page),
+                limit: Some(options.page_size),
+                ..Default::default()
+            };
+            let details = self
+                .client
+                .get_person(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                .await?;
+            if current.page == 1 {
+                let person =
+                    serde_json::to_vec_pretty(&details.person_view).map_err(archive_error)?;
+                fs::write(dir.join(PERSON_FILE), person).map_err(archive_error)?;
+            }
+            if details.posts.is_empty() && details.comments.is_empty() {
+                posts_file.0.flush().map_err(archive_error)?;
+                comments_file.0.flush().map_err(archive_error)?;
+                return Ok(current);
+            }
+
+            for post_view in &details.posts {
+                write_line(&mut posts_file, post_view)?;
+            }
+            for comment_view in &details.comments {
+                write_line(&mut comments_file, comment_view)?;
+            }
+            current.posts += details.posts.len();
+            current.comments += details.comments.len();
+            progress(current);
+        }
+    }
+
+    async fn archive_comments(
+        &self,
+        post_id: PostId,
+        options: &ArchiveOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<Vec<CommentView>> {
+        paged_stream(1, |page| {
+            let form = GetComments {
+                type_: Some(ListingType::All),
+                sort: Some(CommentSortType::Old),
+                page: Some(page),
+                limit: Some(options.page_size),
+                post_id: Some(post_id),
+                ..Default::default()
+            };
+            let jwt = jwt.clone();
+            async move {
+                sleep(options.request_delay).await;
+                self.client
+                    .list_comments(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                    .await
+                    .map(|response| response.comments)
+            }
+        })
+        .try_collect()
+        .await
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    dry_run::is_mutating, hooks::RequestHooks, registry::endpoint_name, response::LemmyResult,
+    Error,
+};
+use chrono::{DateTime, Utc};
+use http::Method;
+use lemmy_api_common::lemmy_db_schema::newtypes::LocalUserId;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone)]
+/// A mutating request made by a client, as passed to an [`AuditSink`].
+pub struct AuditEntry {
+    /// The name of the endpoint, such as `remove_post`, or the path for endpoints this crate does not wrap.
+    pub endpoint: String,
+    /// The HTTP method used.
+    pub method: Method,
+    /// The ids in the form, keyed by their field, such as `post_id` or `person_id`.
+    pub targets: BTreeMap<String, i64>,
+    /// The local user whose JWT the request was sent with, if it was authenticated.
+    pub actor: Option<LocalUserId>,
+    /// When the request finished.
+    pub timestamp: DateTime<Utc>,
+    /// Whether the request succeeded, and why not if it failed.
+    pub outcome: Result<(), Error>,
+}
+
+impl AuditEntry {
+    /// The entry as a JSON object, with the timestamp in milliseconds since the Unix epoch and the error code of failed requests in `error`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "endpoint": self.endpoint,
+            "method": self.method.as_str(),
+            "targets": self.targets,
+            "actor": self.actor.map(|actor| actor.0),
+            "timestamp": self.timestamp.timestamp_millis(),
+            "error": self.outcome.as_ref().err().map(Error::message),
+        })
+    }
+}
+
+/// Receives every mutating request made by a [`LemmyClient`](crate::LemmyClient), for reviewing what a bot or moderation tool did. Set it with [`LemmyClient::set_audit_sink`](crate::LemmyClient::set_audit_sink).
+///
+/// Closures taking an [`AuditEntry`] are sinks, e.g. to forward entries to a logging crate.
+///
+/// ```
+/// use lemmy_client::{AuditEntry, ClientOptions, LemmyClient};
+/// use std::sync::Arc;
+///
+/// let mut client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// client.set_audit_sink(Arc::new(|entry: &AuditEntry| {
+///     println!("{} {:?}: {:?}", entry.endpoint, entry.targets, entry.outcome);
+/// }));
+/// ```
+pub trait AuditSink: Send + Sync {
+    /// Called after every mutating request, whether it succeeded or not.
+    fn record(&self, entry: &AuditEntry);
+}
+
+impl<F> AuditSink for F
+where
+    F: Fn(&AuditEntry) + Send + Sync,
+{
+    fn record(&self, entry: &AuditEntry) {
+        self(entry)
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+/// An [`AuditSink`] appending each entry to a file as a line of JSON, as returned by [`AuditEntry::to_json`].
+///
+/// Only available on native targets.
+pub struct FileAuditSink(std::sync::Mutex<std::fs::File>);
+
+#[cfg(not(target_family = "wasm"))]
+impl FileAuditSink {
+    /// Opens the file to append entries to, creating it if it does not exist.
+    pub fn open(path: impl AsRef<std::path::Path>) -> LemmyResult<Self> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(|file| Self(std::sync::Mutex::new(file)))
+            .map_err(|e| Error::new(format!("audit_failed: {e}")))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl AuditSink for FileAuditSink {
+    fn record(&self, entry: &AuditEntry) {
+        use std::io::Write;
+
+        let mut line = entry.to_json().to_string();
+        line.push('\n');
+        let mut file = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Failing to audit must not fail the request, which was already sent.
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+#[cfg(all(feature = "audit-sqlite", not(target_family = "wasm")))]
+/// An [`AuditSink`] inserting each entry into the `audit_log` table of a SQLite database, which is created if it does not exist.
+///
+/// Only available on native targets with the `audit-sqlite` feature.
+pub struct SqliteAuditSink(std::sync::Mutex<rusqlite::Connection>);
+
+#[cfg(all(feature = "audit-sqlite", not(target_family = "wasm")))]
+impl SqliteAuditSink {
+    /// Opens the database at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<std::path::Path>) -> LemmyResult<Self> {
+        let connection = rusqlite::Connection::open(path)
+            .map_err(|e| Error::new(format!("audit_failed: {e}")))?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS audit_log (
+                    id INTEGER PRIMARY KEY,
+                    endpoint TEXT NOT NULL,
+                    method TEXT NOT NULL,
+                    targets TEXT NOT NULL,
+                    actor INTEGER,
+                    timestamp INTEGER NOT NULL,
+                    error TEXT
+                );",
+            )
+            .map_err(|e| Error::new(format!("audit_failed: {e}")))?;
+
+        Ok(Self(std::sync::Mutex::new(connection)))
+    }
+}
+
+#[cfg(all(feature = "audit-sqlite", not(target_family = "wasm")))]
+impl AuditSink for SqliteAuditSink {
+    fn record(&self, entry: &AuditEntry) {
+        let connection = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Failing to audit must not fail the request, which was already sent.
+        let _ = connection.execute(
+            "INSERT INTO audit_log (endpoint, method, targets, actor, timestamp, error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                entry.endpoint,
+                entry.method.as_str(),
+                serde_json::to_string(&entry.targets).unwrap_or_default(),
+                entry.actor.map(|actor| actor.0),
+                entry.timestamp.timestamp_millis(),
+                entry.outcome.as_ref().err().map(Error::message),
+            ],
+        );
+    }
+}
+
+/// Decodes unpadded base64url, as used in the parts of a JWT.
+fn decode_base64_url(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(bytes)
+}
+
+/// The local user a JWT was issued to, from its `sub` claim. The signature is not checked, since only the instance can.
+fn jwt_local_user(jwt: &str) -> Option<LocalUserId> {
+    #[derive(serde::Deserialize)]
+    struct Claims {
+        sub: String,
+    }
+
+    let payload = decode_base64_url(jwt.split('.').nth(1)?)?;
+    let claims = serde_json::from_slice::<Claims>(&payload).ok()?;
+    claims.sub.parse().ok().map(LocalUserId)
+}
+
+/// Passes a finished request to the audit sink of the hooks, if there is one and the request is mutating. Requests held back by a dry run are skipped.
+///
+/// The JWT may be passed directly, in the `auth` field of forms for Lemmy 0.18, or in the `Authorization` header set on the client.
+pub(crate) fn audit<T>(
+    hooks: &RequestHooks,
+    method: &Method,
+    path: &str,
+    body: &impl Serialize,
+    jwt: Option<&str>,
+    headers: &HashMap<String, String>,
+    result: &LemmyResult<T>,
+) {
+    let Some(sink) = &hooks.audit else {
+        return;
+    };
+    if !is_mutating(method, path) || result.as_ref().is_err_and(Error::is_dry_run) {
+        return;
+    }
+
+    let form = match serde_json::to_value(body) {
+        Ok(serde_json::Value::Object(form)) => form,
+        _ => serde_json::Map::new(),
+    };
+    let targets = form
+        .iter()
+        .filter(|(field, _)| field.as_str() == "id" || field.ends_with("_id"))
+        .filter_map(|(field, value)| Some((field.clone(), value.as_i64()?)))
+        .collect();
+    let header_jwt = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.strip_prefix("Bearer "));
+    let actor = jwt
+        .or(form.get("auth").and_then(serde_json::Value::as_str))
+        .or(header_jwt)
+        .and_then(jwt_local_user);
+
+    sink.record(&AuditEntry {
+        endpoint: endpoint_name(method, path).to_owned(),
+        method: method.clone(),
+        targets,
+        actor,
+        timestamp: crate::now(),
+        outcome: result.as_ref().map(|_| ()).map_err(Clone::clone),
+    });
+}
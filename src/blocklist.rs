@@ -0,0 +1,198 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use lemmy_api_common::{
+    community::BlockCommunity,
+    person::BlockPerson,
+    site::{BlockInstance, ResolveObject},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// The people, communities, and instances an account has blocked.
+///
+/// People and communities are identified by their ActivityPub ID so the list can be applied to accounts on other instances.
+pub struct Blocklist {
+    /// ActivityPub IDs of blocked people.
+    pub people: Vec<String>,
+    /// ActivityPub IDs of blocked communities.
+    pub communities: Vec<String>,
+    /// Domains of blocked instances.
+    pub instances: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The outcome of [`LemmyClient::apply_blocklist`].
+pub struct BlocklistReport {
+    /// Entries that were blocked.
+    pub blocked: Vec<String>,
+    /// Entries that the account had already blocked.
+    pub already_blocked: Vec<String>,
+    /// Entries that could not be resolved or blocked.
+    pub failed: Vec<(String, Error)>,
+}
+
+impl LemmyClient {
+    /// Exports the blocks of the logged in user.
+    pub async fn export_blocklist(&self, jwt: Option<String>) -> LemmyResult<Blocklist> {
+        let my_user = self.my_user(jwt).await?;
+
+        Ok(Blocklist {
+            people: my_user
+                .person_blocks
+                .into_iter()
+                .map(|block| block.target.actor_id.to_string())
+                .collect(),
+            communities: my_user
+                .community_blocks
+                .into_iter()
+                .map(|block| block.community.actor_id.to_string())
+                .collect(),
+            instances: my_user
+                .instance_blocks
+                .into_iter()
+                .map(|block| block.instance.domain)
+                .collect(),
+        })
+    }
+
+    /// Blocks everything in `blocklist` that the logged in user has not blocked yet.
+    ///
+    /// People and communities from other instances are fetched with [`LemmyClient::resolve_object`] first, so this can be used to copy the blocks of an account on one instance to an account on another:
+    /// ```no_run
+    /// # use lemmy_client::{LemmyClient, ClientOptions};
+    /// # async fn example(main: LemmyClient, alt: LemmyClient) -> Result<(), lemmy_client::Error> {
+    /// let blocklist = main.export_blocklist(None).await?;
+    /// let report = alt.apply_blocklist(&blocklist, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn apply_blocklist(
+        &self,
+        blocklist: &Blocklist,
+        jwt: Option<String>,
+    ) -> LemmyResult<BlocklistReport> {
+        let current = self.export_blocklist(jwt.clone()).await?;
+        let mut report = BlocklistReport::default();
+
+        for actor_id in &blocklist.people {
+            if current.people.contains(actor_id) {
+                report.already_blocked.push(actor_id.clone());
+                continue;
+            }
+            let result = async {
+                let person_id = self
+                    .resolve(actor_id, jwt.clone())
+                    .await?
+                    .person
+                    .ok_or_else(|| Error::new("couldnt_find_person"))?
+                    .person
+                    .id;
+                let form = BlockPerson {
+                    person_id,
+                    block: true,
+                };
+                self.client
+                    .block_person(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                    .await
+            }
+            .await;
+            report.record(actor_id, result);
+        }
+
+        for actor_id in &blocklist.communities {
+            if current.communities.contains(actor_id) {
+                report.already_blocked.push(actor_id.clone());
+                continue;
+            }
+            let result = async {
+                let community_id = self
+                    .resolve(actor_id, jwt.clone())
+                    .await?
+                    .community
+                    .ok_or_else(|| Error::new("couldnt_find_community"))?
+                    .community
+                    .id;
+                let form = BlockCommunity {
+                    community_id,
+                    block: true,
+                };
+                self.client
+                    .block_community(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                    .await
+            }
+            .await;
+            report.record(actor_id, result);
+        }
+
+        let missing_instances = blocklist
+            .instances
+            .iter()
+            .filter(|domain| !current.instances.contains(domain))
+            .collect::<Vec<_>>();
+        report.already_blocked.extend(
+            blocklist
+                .instances
+                .iter()
+                .filter(|domain| current.instances.contains(domain))
+                .cloned(),
+        );
+        if !missing_instances.is_empty() {
+            let known_instances = self
+                .client
+                .get_federated_instances(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+                .await?
+                .federated_instances
+                .map(|instances| {
+                    [instances.linked, instances.allowed, instances.blocked]
+                        .into_iter()
+                        .flatten()
+                        .map(|instance| instance.instance)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            for domain in missing_instances {
+                let result = async {
+                    let instance_id = known_instances
+                        .iter()
+                        .find(|instance| &instance.domain == domain)
+                        .ok_or_else(|| Error::new("couldnt_find_instance"))?
+                        .id;
+                    let form = BlockInstance {
+                        instance_id,
+                        block: true,
+                    };
+                    self.client
+                        .block_instance(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                        .await
+                }
+                .await;
+                report.record(domain, result);
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn resolve(
+        &self,
+        q: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<lemmy_api_common::site::ResolveObjectResponse> {
+        let form = ResolveObject { q: q.to_owned() };
+        self.client
+            .resolve_object(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+}
+
+impl BlocklistReport {
+    fn record<T>(&mut self, entry: &str, result: LemmyResult<T>) {
+        match result {
+            Ok(_) => self.blocked.push(entry.to_owned()),
+            Err(e) => self.failed.push((entry.to_owned(), e)),
+        }
+    }
+}
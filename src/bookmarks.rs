@@ -0,0 +1,160 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::paged_stream,
+    LemmyClient, LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use lemmy_api_common::{
+    comment::GetComments,
+    lemmy_db_schema::{CommentSortType, ListingType, SortType},
+    lemmy_db_views::structs::{CommentView, PostView},
+    person::GetPersonDetails,
+    post::GetPosts,
+};
+
+#[derive(Debug, Clone)]
+/// A post or a comment.
+pub enum ContentItem {
+    /// A post.
+    Post(PostView),
+    /// A comment.
+    Comment(CommentView),
+}
+
+impl ContentItem {
+    /// When the post or comment was published.
+    pub fn published(&self) -> DateTime<Utc> {
+        match self {
+            Self::Post(post_view) => post_view.post.published,
+            Self::Comment(comment_view) => comment_view.comment.published,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Which votes [`LemmyClient::liked_content_stream`] returns content for.
+pub enum LikeFilter {
+    /// Content the user upvoted.
+    Liked,
+    /// Content the user downvoted.
+    Disliked,
+}
+
+/// Merges posts and comments into one list, newest first.
+fn merge_content(posts: Vec<PostView>, comments: Vec<CommentView>) -> Vec<ContentItem> {
+    let mut items = posts
+        .into_iter()
+        .map(ContentItem::Post)
+        .chain(comments.into_iter().map(ContentItem::Comment))
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| std::cmp::Reverse(item.published()));
+
+    items
+}
+
+/// Merges two streams that are each newest first into one stream, newest first. Errors are streamed as soon as they are reached.
+fn merge_newest<'a>(
+    posts: impl Stream<Item = LemmyResult<ContentItem>> + 'a,
+    comments: impl Stream<Item = LemmyResult<ContentItem>> + 'a,
+) -> impl Stream<Item = LemmyResult<ContentItem>> + 'a {
+    stream::unfold(
+        (Box::pin(posts.peekable()), Box::pin(comments.peekable())),
+        |(mut posts, mut comments)| async move {
+            let published =
+                |item: &LemmyResult<ContentItem>| item.as_ref().ok().map(ContentItem::published);
+            let next_post = posts.as_mut().peek().await.map(published);
+            let next_comment = comments.as_mut().peek().await.map(published);
+            let take_post = match (next_post, next_comment) {
+                (None, None) => return None,
+                (Some(None), _) | (Some(Some(_)), None) => true,
+                (_, Some(None)) | (None, Some(Some(_))) => false,
+                (Some(Some(post)), Some(Some(comment))) => post >= comment,
+            };
+            let item = if take_post {
+                posts.next().await
+            } else {
+                comments.next().await
+            }?;
+
+            Some((item, (posts, comments)))
+        },
+    )
+}
+
+impl LemmyClient {
+    /// Streams the posts and comments the logged in user saved, fetching pages as needed. Each page of the instance holds both posts and comments, so items are newest first within each page, but not across pages.
+    pub fn saved_content_stream(
+        &self,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<ContentItem>> + '_ {
+        stream::once(self.my_user(jwt.clone()))
+            .map_ok(move |my_user| {
+                let person_id = my_user.local_user_view.person.id;
+                let jwt = jwt.clone();
+                paged_stream(1, move |page| {
+                    let form = GetPersonDetails {
+                        person_id: Some(person_id),
+                        sort: Some(SortType::New),
+                        page: Some(page),
+                        saved_only: Some(true),
+                        ..Default::default()
+                    };
+                    let request = LemmyRequest::with_jwt(form, jwt.clone());
+                    async move {
+                        let response = self.client.get_person(request, &self.headers).await?;
+                        Ok(merge_content(response.posts, response.comments))
+                    }
+                })
+            })
+            .try_flatten()
+    }
+
+    /// Streams the posts and comments the logged in user voted on, from every instance, newest first. Posts and comments are fetched page by page as needed, from two listings merged by publish time.
+    pub fn liked_content_stream(
+        &self,
+        filter: LikeFilter,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<ContentItem>> + '_ {
+        let liked_only = Some(filter == LikeFilter::Liked);
+        let disliked_only = Some(filter == LikeFilter::Disliked);
+
+        let posts_jwt = jwt.clone();
+        let posts = paged_stream(1, move |page| {
+            let form = GetPosts {
+                type_: Some(ListingType::All),
+                sort: Some(SortType::New),
+                page: Some(page),
+                liked_only,
+                disliked_only,
+                ..Default::default()
+            };
+            let request = LemmyRequest::with_jwt(form, posts_jwt.clone());
+            async move {
+                let response = self.client.list_posts(request, &self.headers).await?;
+                Ok(response.posts.into_iter().map(ContentItem::Post).collect())
+            }
+        });
+        let comments = paged_stream(1, move |page| {
+            let form = GetComments {
+                type_: Some(ListingType::All),
+                sort: Some(CommentSortType::New),
+                page: Some(page),
+                liked_only,
+                disliked_only,
+                ..Default::default()
+            };
+            let request = LemmyRequest::with_jwt(form, jwt.clone());
+            async move {
+                let response = self.client.list_comments(request, &self.headers).await?;
+                Ok(response
+                    .comments
+                    .into_iter()
+                    .map(ContentItem::Comment)
+                    .collect())
+            }
+        });
+
+        merge_newest(posts, comments)
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    lemmy_client_trait::private_trait::LemmyClientInternal, response::LemmyResult, timestamps::now,
+    LemmyClient, LemmyRequest,
+};
+use chrono::DateTime;
+use http::Method;
+use lemmy_api_common::post::{GetSiteMetadata, GetSiteMetadataResponse};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use web_time::Instant;
+
+/// How long link metadata is cached for by default.
+pub(crate) const DEFAULT_SITE_METADATA_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How long a response may be cached for, according to its `Cache-Control` and `Expires` headers, e.g. when the instance is behind a CDN.
+pub(crate) enum CachePolicy {
+    /// The response has no caching headers, so only the client's own TTL applies.
+    #[default]
+    Unspecified,
+    /// The response must not be reused.
+    NoStore,
+    /// The response may be reused for at most this long.
+    MaxAge(Duration),
+}
+
+impl CachePolicy {
+    /// Reads the policy from the values of the `Cache-Control`, `Expires`, `Date`, and `Age` headers. `Cache-Control` takes precedence over `Expires`, as in HTTP.
+    pub(crate) fn from_headers(
+        cache_control: Option<&str>,
+        expires: Option<&str>,
+        date: Option<&str>,
+        age: Option<&str>,
+    ) -> Self {
+        let age = age
+            .and_then(|age| age.trim().parse().ok())
+            .map_or(Duration::ZERO, Duration::from_secs);
+        let mut max_age = None;
+        for directive in cache_control.into_iter().flat_map(|value| value.split(',')) {
+            let directive = directive.trim().to_ascii_lowercase();
+            // Responses that have to be revalidated cannot be reused either, since the client does not revalidate.
+            if directive == "no-store" || directive == "no-cache" {
+                return Self::NoStore;
+            }
+            if let Some(seconds) = directive.strip_prefix("max-age=") {
+                max_age = seconds
+                    .trim_matches('"')
+                    .parse()
+                    .ok()
+                    .map(Duration::from_secs);
+            }
+        }
+
+        if let Some(max_age) = max_age {
+            return Self::fresh_for(max_age.checked_sub(age));
+        }
+        let Some(expires) = expires else {
+            return Self::Unspecified;
+        };
+        // Invalid dates, such as `0`, mean the response has already expired.
+        let Ok(expires) = DateTime::parse_from_rfc2822(expires.trim()) else {
+            return Self::NoStore;
+        };
+        let date = date
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .map_or_else(now, |date| date.to_utc());
+        Self::fresh_for(
+            (expires.to_utc() - date)
+                .to_std()
+                .ok()
+                .and_then(|lifetime| lifetime.checked_sub(age)),
+        )
+    }
+
+    fn fresh_for(lifetime: Option<Duration>) -> Self {
+        match lifetime {
+            Some(lifetime) if !lifetime.is_zero() => Self::MaxAge(lifetime),
+            _ => Self::NoStore,
+        }
+    }
+}
+
+struct CacheEntry<V> {
+    inserted: Instant,
+    /// How long the server allows the entry to be reused for, if it said so.
+    max_age: Option<Duration>,
+    value: V,
+}
+
+/// A map whose entries expire a fixed amount of time after they are inserted, or earlier if the server says so.
+pub(crate) struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, CacheEntry<V>>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry<V>) -> bool {
+        let ttl = entry
+            .max_age
+            .map_or(self.ttl, |max_age| max_age.min(self.ttl));
+        entry.inserted.elapsed() < ttl
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        match entries.get(key) {
+            Some(entry) if self.is_fresh(entry) => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Inserts an entry, unless `policy` forbids storing it. Entries whose policy allows less time than the TTL expire earlier.
+    pub(crate) fn insert(&self, key: K, value: V, policy: CachePolicy) {
+        let max_age = match policy {
+            CachePolicy::Unspecified => None,
+            CachePolicy::NoStore => return,
+            CachePolicy::MaxAge(max_age) => Some(max_age),
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|_, entry| self.is_fresh(entry));
+        entries.insert(
+            key,
+            CacheEntry {
+                inserted: Instant::now(),
+                max_age,
+                value,
+            },
+        );
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    pub(crate) fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub(crate) fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+}
+
+impl LemmyClient {
+    /// Like [`LemmyClient::get_post_url_metadata`], but reuses responses for URLs that were fetched recently.
+    ///
+    /// Responses are never reused for longer than the `Cache-Control` or `Expires` headers of the response allow, and not at all if they forbid caching, e.g. with `no-store`.
+    ///
+    /// Useful for post editors that fetch link previews while the user is typing, since instances rate limit this endpoint heavily.
+    pub async fn get_post_url_metadata_cached<Request>(
+        &self,
+        request: Request,
+    ) -> LemmyResult<GetSiteMetadataResponse>
+    where
+        Request: Into<LemmyRequest<GetSiteMetadata>>,
+    {
+        let request = request.into();
+        let key = request.body.url.to_string();
+        if let Some(response) = self.site_metadata_cache.get(&key) {
+            return Ok(response);
+        }
+
+        let (response, policy): (GetSiteMetadataResponse, _) = self
+            .client
+            .make_request_with_cache_policy(
+                Method::GET,
+                "post/site_metadata",
+                request,
+                &self.headers,
+            )
+            .await?;
+        self.site_metadata_cache
+            .insert(key, response.clone(), policy);
+
+        Ok(response)
+    }
+
+    /// Sets how long responses of [`LemmyClient::get_post_url_metadata_cached`] are reused for at most. Defaults to 5 minutes.
+    ///
+    /// If the cache is shared with [derived clients](LemmyClient::derive), this client gets an empty cache of its own.
+    pub fn set_site_metadata_cache_ttl(&mut self, ttl: Duration) {
+        match Arc::get_mut(&mut self.site_metadata_cache) {
+            Some(cache) => cache.set_ttl(ttl),
+            None => self.site_metadata_cache = Arc::new(TtlCache::new(ttl)),
+        }
+    }
+
+    /// Discards all responses cached by [`LemmyClient::get_post_url_metadata_cached`].
+    pub fn clear_site_metadata_cache(&self) {
+        self.site_metadata_cache.clear();
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, LemmyClient, LemmyRequest,
+};
+use futures::future::{try_join, try_join_all};
+use lemmy_api_common::{
+    comment::GetComments,
+    lemmy_db_schema::newtypes::{CommentId, PostId},
+    lemmy_db_views::structs::CommentView,
+    post::{GetPost, GetPostResponse},
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+/// A comment and its replies.
+pub struct CommentNode {
+    /// The comment.
+    pub comment_view: CommentView,
+    /// Replies to the comment, in the order they were returned by the API.
+    pub children: Vec<CommentNode>,
+}
+
+/// Gets the ID of the comment a comment replies to from its path, e.g. `0.24.27`.
+fn parent_id(comment_view: &CommentView) -> Option<CommentId> {
+    let mut ids = comment_view.comment.path.split('.').rev().skip(1);
+    ids.next()
+        .filter(|id| *id != "0")
+        .and_then(|id| id.parse().ok())
+        .map(CommentId)
+}
+
+/// Arranges a flat list of comments into trees.
+///
+/// Comments whose parent is not in the list are treated as top level comments, so this also works on a single page of comments.
+pub fn build_comment_tree(comments: Vec<CommentView>) -> Vec<CommentNode> {
+    let ids = comments
+        .iter()
+        .map(|comment_view| comment_view.comment.id)
+        .collect::<HashSet<_>>();
+    let mut roots = Vec::new();
+    let mut children = HashMap::<CommentId, Vec<CommentView>>::new();
+    for comment_view in comments {
+        match parent_id(&comment_view).filter(|parent_id| ids.contains(parent_id)) {
+            Some(parent_id) => children.entry(parent_id).or_default().push(comment_view),
+            None => roots.push(comment_view),
+        }
+    }
+
+    fn attach(
+        comment_view: CommentView,
+        children: &mut HashMap<CommentId, Vec<CommentView>>,
+    ) -> CommentNode {
+        let replies = children
+            .remove(&comment_view.comment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|reply| attach(reply, children))
+            .collect();
+        CommentNode {
+            comment_view,
+            children: replies,
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|comment_view| attach(comment_view, &mut children))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+/// A post along with its comments, returned by [`LemmyClient::get_post_with_comments`].
+pub struct PostWithComments {
+    /// The post.
+    pub post: GetPostResponse,
+    /// The comments of the post, without duplicates.
+    pub comments: Vec<CommentView>,
+}
+
+impl PostWithComments {
+    /// Arranges the comments into trees. See [`build_comment_tree`].
+    pub fn comment_tree(&self) -> Vec<CommentNode> {
+        build_comment_tree(self.comments.clone())
+    }
+}
+
+impl LemmyClient {
+    /// Gets a post and the first `pages` pages of its comments at the same time.
+    ///
+    /// `comments` sets the sort, page size, and other filters of the comment requests. Its `post_id` and `page` are overwritten.
+    pub async fn get_post_with_comments(
+        &self,
+        post_id: PostId,
+        comments: GetComments,
+        pages: u32,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostWithComments> {
+        let post_form = GetPost {
+            id: Some(post_id),
+            comment_id: None,
+        };
+        let post = self.client.get_post(
+            LemmyRequest::with_jwt(post_form, jwt.clone()),
+            &self.headers,
+        );
+        let comment_pages = try_join_all((1..=i64::from(pages)).map(|page| {
+            let form = GetComments {
+                post_id: Some(post_id),
+                page: Some(page),
+                ..comments.clone()
+            };
+            self.client
+                .list_comments(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+        }));
+
+        let (post, comment_pages) = try_join(post, comment_pages).await?;
+        let mut seen = HashSet::new();
+        let comments = comment_pages
+            .into_iter()
+            .flat_map(|page| page.comments)
+            .filter(|comment_view| seen.insert(comment_view.comment.id))
+            .collect();
+
+        Ok(PostWithComments { post, comments })
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{ApiVersion, Error, LemmyClient, LemmyResult, NodeInfo};
+use std::sync::Arc;
+
+/// The first version of Lemmy that takes the JWT from the `Authorization` header.
+const HEADER_AUTH_VERSION: ApiVersion = ApiVersion::new(0, 19, 0);
+
+impl LemmyClient {
+    /// Sets whether JWTs are sent in the `auth` field of forms and queries, as Lemmy 0.18 and older expect, instead of the `Authorization` header. Disabled by default.
+    ///
+    /// The types of this crate match Lemmy 0.19, so only requests whose forms and responses did not change since 0.18 work. Prefer [`LemmyClient::detect_legacy_auth`], which only enables this for instances that need it.
+    pub fn set_legacy_auth(&mut self, enabled: bool) {
+        self.client.hooks.legacy_auth = enabled;
+    }
+
+    /// Sets whether `PUT` requests are sent as `POST` requests with an `X-HTTP-Method-Override: PUT` header, for networks such as proxies or serverless fetch shims that block `PUT`. Disabled by default.
+    ///
+    /// Lemmy itself ignores the header, so whatever sits between the network and the instance has to turn these requests back into `PUT` requests. The endpoints and their types stay the same.
+    pub fn set_method_override(&mut self, enabled: bool) {
+        self.client.hooks.method_override = enabled;
+    }
+
+    /// Detects the version of Lemmy the instance runs, and sends JWTs the way that version expects. See [`LemmyClient::set_legacy_auth`].
+    ///
+    /// Returns the detected version. Instances whose version cannot be determined keep the current setting.
+    pub async fn detect_legacy_auth(&mut self) -> LemmyResult<Option<ApiVersion>> {
+        let version = self.get_node_info().await?.version();
+        if let Some(version) = version {
+            self.set_legacy_auth(version < HEADER_AUTH_VERSION);
+        }
+
+        Ok(version)
+    }
+}
+
+/// Adapts requests and responses for servers whose API is compatible with Lemmy's, apart from some quirks. Set it with [`LemmyClient::set_server_adapter`], or let [`LemmyClient::detect_server_adapter`] pick one.
+///
+/// Both methods default to behaving like Lemmy, so an adapter only needs to implement what differs:
+///
+/// ```
+/// use lemmy_client::ServerAdapter;
+/// use serde_json::Value;
+///
+/// struct MissingCounts;
+///
+/// impl ServerAdapter for MissingCounts {
+///     fn patch_response(&self, path: &str, response: &mut Value) {
+///         // This server leaves out a field that Lemmy always sends.
+///         if path == "post" {
+///             if let Some(post_view) = response.get_mut("post_view").and_then(Value::as_object_mut) {
+///                 post_view.entry("unread_comments").or_insert(0.into());
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub trait ServerAdapter: Send + Sync {
+    /// The path of an endpoint relative to the domain, given its path relative to `/api/v3`.
+    fn api_path(&self, path: &str) -> String {
+        format!("api/v3/{path}")
+    }
+
+    /// Changes a successful response from the endpoint at `path` before it is parsed, e.g. to add fields the server leaves out.
+    fn patch_response(&self, path: &str, response: &mut serde_json::Value) {
+        let _ = (path, response);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// Adapter for [PieFed](https://join.piefed.social/), which serves a Lemmy compatible API under `/api/alpha`.
+///
+/// Besides moving requests to that path, it fills in the flags and counts PieFed leaves out of posts, comments, communities, users and their views: flags default to `false`, counts to `0` and `subscribed` to `NotSubscribed`. Other missing fields, such as IDs and dates, still fail with [`ErrorKind::Decode`](crate::ErrorKind::Decode).
+///
+/// ```
+/// use lemmy_client::{PieFedAdapter, ServerAdapter};
+/// use serde_json::json;
+///
+/// let mut response = json!({ "post_view": { "counts": { "score": 3 }, "saved": true } });
+/// PieFedAdapter.patch_response("post", &mut response);
+/// assert_eq!(response["post_view"]["read"], false);
+/// assert_eq!(response["post_view"]["saved"], true);
+/// assert_eq!(response["post_view"]["counts"]["comments"], 0);
+/// ```
+pub struct PieFedAdapter;
+
+/// Flags of views, which are the objects with `counts`.
+const VIEW_FLAGS: &[&str] = &[
+    "blocked",
+    "creator_banned_from_community",
+    "creator_blocked",
+    "creator_is_admin",
+    "creator_is_moderator",
+    "is_admin",
+    "read",
+    "saved",
+];
+
+/// Flags of posts and comments, which are the objects with an `ap_id`.
+const CONTENT_FLAGS: &[&str] = &[
+    "deleted",
+    "distinguished",
+    "featured_community",
+    "featured_local",
+    "local",
+    "locked",
+    "nsfw",
+    "removed",
+];
+
+/// Flags of communities and users, which are the objects with an `actor_id`.
+const ACTOR_FLAGS: &[&str] = &[
+    "banned",
+    "bot_account",
+    "deleted",
+    "hidden",
+    "local",
+    "nsfw",
+    "posting_restricted_to_mods",
+    "removed",
+];
+
+/// Counts in the `counts` of views.
+const COUNTS: &[&str] = &[
+    "child_count",
+    "comment_count",
+    "comments",
+    "downvotes",
+    "post_count",
+    "posts",
+    "score",
+    "subscribers",
+    "upvotes",
+    "users_active_day",
+    "users_active_half_year",
+    "users_active_month",
+    "users_active_week",
+];
+
+/// Fills in the fields PieFed leaves out of `value` and everything in it. Serde ignores the fields that do not belong to the type being parsed, so every view gets the flags of all views.
+fn fill_missing_fields(value: &mut serde_json::Value) {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(values) => values.iter_mut().for_each(fill_missing_fields),
+        Value::Object(object) => {
+            let fill =
+                |object: &mut serde_json::Map<String, Value>, fields: &[&str], default: Value| {
+                    for field in fields {
+                        object.entry(*field).or_insert_with(|| default.clone());
+                    }
+                };
+            if let Some(counts) = object.get_mut("counts").and_then(Value::as_object_mut) {
+                fill(counts, COUNTS, 0.into());
+                fill(object, VIEW_FLAGS, false.into());
+                fill(object, &["unread_comments"], 0.into());
+                fill(object, &["subscribed"], "NotSubscribed".into());
+            } else if object.contains_key("ap_id") {
+                fill(object, CONTENT_FLAGS, false.into());
+            } else if object.contains_key("actor_id") {
+                fill(object, ACTOR_FLAGS, false.into());
+            }
+            object.values_mut().for_each(fill_missing_fields);
+        }
+        _ => {}
+    }
+}
+
+impl ServerAdapter for PieFedAdapter {
+    fn api_path(&self, path: &str) -> String {
+        format!("api/alpha/{path}")
+    }
+
+    fn patch_response(&self, _path: &str, response: &mut serde_json::Value) {
+        fill_missing_fields(response);
+    }
+}
+
+impl LemmyClient {
+    /// Sets the adapter used to talk to servers that are not quite Lemmy. [`None`] talks to the server as if it runs Lemmy, which is the default. See [`ServerAdapter`].
+    pub fn set_server_adapter(&mut self, adapter: Option<Arc<dyn ServerAdapter>>) {
+        self.client.hooks.adapter = adapter;
+    }
+
+    /// Detects the software the instance runs from its [`NodeInfo`], and sets the matching adapter.
+    ///
+    /// Fails with `unsupported_software` for software without a Lemmy compatible API, such as Mbin. Returns the detected NodeInfo otherwise.
+    pub async fn detect_server_adapter(&mut self) -> LemmyResult<NodeInfo> {
+        let node_info = self.get_node_info().await?;
+        let adapter: Option<Arc<dyn ServerAdapter>> =
+            match node_info.software.name.to_ascii_lowercase().as_str() {
+                "lemmy" => None,
+                "piefed" => Some(Arc::new(PieFedAdapter)),
+                software => {
+                    return Err(Error::new(format!("unsupported_software: {software}")));
+                }
+            };
+        self.set_server_adapter(adapter);
+
+        Ok(node_info)
+    }
+}
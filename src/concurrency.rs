@@ -0,0 +1,133 @@
+use async_lock::{Semaphore, SemaphoreGuardArc};
+use event_listener::Event;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock, PoisonError,
+    },
+};
+
+/// Limits shared by every client sending requests to the same host.
+static HOST_LIMITS: OnceLock<Mutex<HashMap<String, ConcurrencyLimit>>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+/// A limit on how many requests can be in flight at once. Requests over the limit wait until another request finishes.
+pub(crate) struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max.max(1))),
+            max,
+        }
+    }
+
+    /// Gets the limit shared by every client sending requests to `host`, creating it if needed.
+    ///
+    /// If the existing limit allows a different number of requests, it is replaced. Clients that were using the old limit keep using it until it is set again.
+    pub(crate) fn for_host(host: &str, max: usize) -> Self {
+        let mut limits = HOST_LIMITS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match limits.get(host) {
+            Some(limit) if limit.max == max => limit.clone(),
+            _ => {
+                let limit = Self::new(max);
+                limits.insert(host.to_owned(), limit.clone());
+                limit
+            }
+        }
+    }
+
+    pub(crate) async fn acquire(&self) -> SemaphoreGuardArc {
+        self.semaphore.acquire_arc().await
+    }
+
+    /// How many requests can be in flight at once.
+    pub(crate) fn max(&self) -> usize {
+        self.max
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// How urgent the requests of a client are, for clients sharing a [`DispatchQueue`].
+pub enum Priority {
+    /// Requests that can wait, such as crawls and archival. They are only sent when no interactive request is waiting.
+    Background,
+    /// Requests a user is waiting for. They are sent before any waiting background request.
+    #[default]
+    Interactive,
+}
+
+#[derive(Debug)]
+struct QueueState {
+    slots: Arc<Semaphore>,
+    interactive_waiting: AtomicUsize,
+    interactive_dispatched: Event,
+}
+
+#[derive(Debug, Clone)]
+/// Limits how many requests clients sharing the queue can have in flight at once, sending interactive requests before background ones. Set it with [`LemmyClient::set_dispatch_queue`](crate::LemmyClient::set_dispatch_queue).
+///
+/// Background requests yield to interactive requests that arrive while they are waiting, so a client crawling an instance in the background does not delay a client replying to users. The queue is waited on before the client's [concurrency limits](crate::LemmyClient::set_host_concurrency_limit), so background requests never hold a slot of those limits while interactive requests wait.
+///
+/// ```
+/// use lemmy_client::{ClientOptions, DispatchQueue, LemmyClient, Priority};
+///
+/// let options = ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// };
+/// let queue = DispatchQueue::new(4);
+///
+/// let mut replies = LemmyClient::new(options.clone());
+/// replies.set_dispatch_queue(Some((queue.clone(), Priority::Interactive)));
+///
+/// let mut crawler = LemmyClient::new(options);
+/// crawler.set_dispatch_queue(Some((queue, Priority::Background)));
+/// ```
+pub struct DispatchQueue(Arc<QueueState>);
+
+impl DispatchQueue {
+    /// Creates a queue allowing up to `max_in_flight` requests at once.
+    pub fn new(max_in_flight: usize) -> Self {
+        Self(Arc::new(QueueState {
+            slots: Arc::new(Semaphore::new(max_in_flight.max(1))),
+            interactive_waiting: AtomicUsize::new(0),
+            interactive_dispatched: Event::new(),
+        }))
+    }
+
+    pub(crate) async fn acquire(&self, priority: Priority) -> SemaphoreGuardArc {
+        let state = &self.0;
+        match priority {
+            Priority::Interactive => {
+                state.interactive_waiting.fetch_add(1, Ordering::SeqCst);
+                let permit = state.slots.acquire_arc().await;
+                state.interactive_waiting.fetch_sub(1, Ordering::SeqCst);
+                state.interactive_dispatched.notify(usize::MAX);
+                permit
+            }
+            Priority::Background => loop {
+                while state.interactive_waiting.load(Ordering::SeqCst) > 0 {
+                    let listener = state.interactive_dispatched.listen();
+                    if state.interactive_waiting.load(Ordering::SeqCst) > 0 {
+                        listener.await;
+                    }
+                }
+
+                let permit = state.slots.acquire_arc().await;
+                // An interactive request may have arrived while waiting, in which case it goes first.
+                if state.interactive_waiting.load(Ordering::SeqCst) == 0 {
+                    break permit;
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::{
+    form::LemmyForm,
+    lemmy_client_trait::private_trait::LemmyClientInternal,
+    response::{LemmyResponse, LemmyResult},
+    worker::RawBody,
+    Error, ErrorKind, LemmyClient, LemmyRequest, Method,
+};
+use lemmy_api_common::{
+    lemmy_db_schema::newtypes::DbUrl,
+    lemmy_db_views::structs::{CommentView, PostView},
+    lemmy_db_views_actor::structs::{CommunityView, PersonView},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+#[derive(Debug, Clone, Default)]
+/// Rules that hide posts, comments, communities, and people from list responses, set with [`LemmyClient::set_content_filter`]. Items have to pass every rule that is set.
+///
+/// ```
+/// use lemmy_client::{ClientOptions, ContentFilter, LemmyClient};
+///
+/// let mut client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// client.set_content_filter(Some(ContentFilter {
+///     keywords: vec![String::from("spoiler")],
+///     hide_bots: true,
+///     muted_instances: vec![String::from("spam.example")],
+///     min_score: Some(-5),
+///     ..Default::default()
+/// }));
+/// ```
+pub struct ContentFilter {
+    /// Hides items whose text contains any of these words, ignoring case. The text is the title and body of posts, the content of comments, and the name and title of communities and people.
+    pub keywords: Vec<String>,
+    #[cfg(feature = "regex")]
+    /// Hides items whose text matches any of these patterns. See [`ContentFilter::keywords`] for what the text is.
+    ///
+    /// Only available with the `regex` feature.
+    pub patterns: Vec<regex::Regex>,
+    /// Hides NSFW posts, and posts, comments, and communities in NSFW communities.
+    pub hide_nsfw: bool,
+    /// Hides bot accounts and what they post.
+    pub hide_bots: bool,
+    /// Hides communities and people from these instances, and what is posted by or in them. Matches subdomains too.
+    pub muted_instances: Vec<String>,
+    /// Hides posts and comments with a lower score.
+    pub min_score: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The rule of a [`ContentFilter`] that hid an item.
+pub enum FilterReason {
+    /// [`ContentFilter::keywords`].
+    Keyword,
+    #[cfg(feature = "regex")]
+    /// [`ContentFilter::patterns`].
+    Pattern,
+    /// [`ContentFilter::hide_nsfw`].
+    Nsfw,
+    /// [`ContentFilter::hide_bots`].
+    Bot,
+    /// [`ContentFilter::muted_instances`].
+    Instance,
+    /// [`ContentFilter::min_score`].
+    Score,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How many items each rule of a [`ContentFilter`] hid, as returned by [`LemmyClient::content_filter_stats`]. Items are counted for the first rule that hid them.
+pub struct FilterStats {
+    /// Items hidden by [`ContentFilter::keywords`].
+    pub keywords: u64,
+    #[cfg(feature = "regex")]
+    /// Items hidden by [`ContentFilter::patterns`].
+    pub patterns: u64,
+    /// Items hidden by [`ContentFilter::hide_nsfw`].
+    pub nsfw: u64,
+    /// Items hidden by [`ContentFilter::hide_bots`].
+    pub bots: u64,
+    /// Items hidden by [`ContentFilter::muted_instances`].
+    pub instances: u64,
+    /// Items hidden by [`ContentFilter::min_score`].
+    pub score: u64,
+}
+
+impl FilterStats {
+    /// How many items were hidden in total.
+    pub fn total(&self) -> u64 {
+        let total = self.keywords + self.nsfw + self.bots + self.instances + self.score;
+        #[cfg(feature = "regex")]
+        let total = total + self.patterns;
+        total
+    }
+}
+
+impl ContentFilter {
+    /// The first rule that hides text, if any.
+    fn check_text(&self, texts: &[&str]) -> Option<FilterReason> {
+        let lowercase = texts
+            .iter()
+            .map(|text| text.to_lowercase())
+            .collect::<Vec<_>>();
+        if self.keywords.iter().any(|keyword| {
+            let keyword = keyword.to_lowercase();
+            lowercase.iter().any(|text| text.contains(&keyword))
+        }) {
+            return Some(FilterReason::Keyword);
+        }
+        #[cfg(feature = "regex")]
+        if self
+            .patterns
+            .iter()
+            .any(|pattern| texts.iter().any(|text| pattern.is_match(text)))
+        {
+            return Some(FilterReason::Pattern);
+        }
+
+        None
+    }
+
+    /// Whether an actor is from a muted instance.
+    fn is_muted(&self, actor_id: &DbUrl) -> bool {
+        let Some(host) = actor_id.host_str() else {
+            return false;
+        };
+        self.muted_instances.iter().any(|muted| {
+            host.eq_ignore_ascii_case(muted)
+                || host
+                    .to_ascii_lowercase()
+                    .ends_with(&format!(".{}", muted.to_ascii_lowercase()))
+        })
+    }
+
+    /// The first rule that hides a post, if any.
+    pub fn check_post(&self, post_view: &PostView) -> Option<FilterReason> {
+        let post = &post_view.post;
+        if self.hide_nsfw && (post.nsfw || post_view.community.nsfw) {
+            Some(FilterReason::Nsfw)
+        } else if self.hide_bots && post_view.creator.bot_account {
+            Some(FilterReason::Bot)
+        } else if self.is_muted(&post_view.creator.actor_id)
+            || self.is_muted(&post_view.community.actor_id)
+        {
+            Some(FilterReason::Instance)
+        } else if self
+            .min_score
+            .is_some_and(|min_score| post_view.counts.score < min_score)
+        {
+            Some(FilterReason::Score)
+        } else {
+            self.check_text(&[&post.name, post.body.as_deref().unwrap_or_default()])
+        }
+    }
+
+    /// The first rule that hides a comment, if any.
+    pub fn check_comment(&self, comment_view: &CommentView) -> Option<FilterReason> {
+        if self.hide_nsfw && comment_view.community.nsfw {
+            Some(FilterReason::Nsfw)
+        } else if self.hide_bots && comment_view.creator.bot_account {
+            Some(FilterReason::Bot)
+        } else if self.is_muted(&comment_view.creator.actor_id)
+            || self.is_muted(&comment_view.community.actor_id)
+        {
+            Some(FilterReason::Instance)
+        } else if self
+            .min_score
+            .is_some_and(|min_score| comment_view.counts.score < min_score)
+        {
+            Some(FilterReason::Score)
+        } else {
+            self.check_text(&[&comment_view.comment.content])
+        }
+    }
+
+    /// The first rule that hides a community, if any.
+    pub fn check_community(&self, community_view: &CommunityView) -> Option<FilterReason> {
+        let community = &community_view.community;
+        if self.hide_nsfw && community.nsfw {
+            Some(FilterReason::Nsfw)
+        } else if self.is_muted(&community.actor_id) {
+            Some(FilterReason::Instance)
+        } else {
+            self.check_text(&[&community.name, &community.title])
+        }
+    }
+
+    /// The first rule that hides a person, if any.
+    pub fn check_person(&self, person_view: &PersonView) -> Option<FilterReason> {
+        let person = &person_view.person;
+        if self.hide_bots && person.bot_account {
+            Some(FilterReason::Bot)
+        } else if self.is_muted(&person.actor_id) {
+            Some(FilterReason::Instance)
+        } else {
+            self.check_text(&[
+                &person.name,
+                person.display_name.as_deref().unwrap_or_default(),
+            ])
+        }
+    }
+}
+
+#[derive(Default)]
+/// The counters of [`FilterStats`].
+struct FilterCounters {
+    keywords: AtomicU64,
+    #[cfg(feature = "regex")]
+    patterns: AtomicU64,
+    nsfw: AtomicU64,
+    bots: AtomicU64,
+    instances: AtomicU64,
+    score: AtomicU64,
+}
+
+/// A content filter set on a client, counting what it hid.
+pub(crate) struct ActiveFilter {
+    filter: ContentFilter,
+    counters: FilterCounters,
+}
+
+impl ActiveFilter {
+    fn count(&self, reason: FilterReason) {
+        let counter = match reason {
+            FilterReason::Keyword => &self.counters.keywords,
+            #[cfg(feature = "regex")]
+            FilterReason::Pattern => &self.counters.patterns,
+            FilterReason::Nsfw => &self.counters.nsfw,
+            FilterReason::Bot => &self.counters.bots,
+            FilterReason::Instance => &self.counters.instances,
+            FilterReason::Score => &self.counters.score,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> FilterStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        FilterStats {
+            keywords: load(&self.counters.keywords),
+            #[cfg(feature = "regex")]
+            patterns: load(&self.counters.patterns),
+            nsfw: load(&self.counters.nsfw),
+            bots: load(&self.counters.bots),
+            instances: load(&self.counters.instances),
+            score: load(&self.counters.score),
+        }
+    }
+
+    /// Whether the filter lets `item` through, counting it if not.
+    pub(crate) fn allows<T>(
+        &self,
+        item: &T,
+        check: fn(&ContentFilter, &T) -> Option<FilterReason>,
+    ) -> bool {
+        match check(&self.filter, item) {
+            Some(reason) => {
+                self.count(reason);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Removes the items of the list in `field` that the filter hides. Items that do not parse as `T` are kept.
+    fn retain<T: DeserializeOwned>(
+        &self,
+        response: &mut serde_json::Value,
+        field: &str,
+        check: fn(&ContentFilter, &T) -> Option<FilterReason>,
+    ) {
+        let Some(items) = response
+            .get_mut(field)
+            .and_then(serde_json::Value::as_array_mut)
+        else {
+            return;
+        };
+        items.retain(|item| T::deserialize(item).map_or(true, |item| self.allows(&item, check)));
+    }
+
+    /// Whether the response of an endpoint has lists the filter applies to.
+    fn applies_to(path: &str) -> bool {
+        matches!(path, "post/list" | "comment/list" | "search")
+    }
+
+    /// Removes the items the filter hides from the response of an endpoint.
+    fn apply(&self, path: &str, response: &mut serde_json::Value) {
+        match path {
+            "post/list" => self.retain(response, "posts", ContentFilter::check_post),
+            "comment/list" => self.retain(response, "comments", ContentFilter::check_comment),
+            "search" => {
+                self.retain(response, "posts", ContentFilter::check_post);
+                self.retain(response, "comments", ContentFilter::check_comment);
+                self.retain(response, "communities", ContentFilter::check_community);
+                self.retain(response, "users", ContentFilter::check_person);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<State> LemmyClient<State> {
+    /// The content filter, if it applies to the response of the endpoint at `path`.
+    fn filter_for(&self, path: &str) -> Option<&ActiveFilter> {
+        self.client
+            .hooks
+            .content_filter
+            .as_deref()
+            .filter(|_| ActiveFilter::applies_to(path))
+    }
+
+    /// Removes the items the content filter hides from the response of the endpoint at `path`, before it is returned to the caller.
+    pub(crate) fn filter_response<Response>(
+        &self,
+        path: &str,
+        response: Response,
+    ) -> LemmyResult<Response>
+    where
+        Response: Serialize + DeserializeOwned,
+    {
+        let Some(filter) = self.filter_for(path) else {
+            return Ok(response);
+        };
+        let decode_error =
+            |e: serde_json::Error| Error::with_kind(ErrorKind::Decode, e.to_string());
+        let mut response = serde_json::to_value(response).map_err(decode_error)?;
+        filter.apply(path, &mut response);
+        serde_json::from_value(response).map_err(decode_error)
+    }
+
+    /// Sends a request for the `_as` method of an endpoint, removing the items the content filter hides before the response is parsed into `Response`.
+    pub(crate) async fn make_filtered_request<Response, Form>(
+        &self,
+        method: Method,
+        path: &str,
+        request: LemmyRequest<Form>,
+    ) -> LemmyResult<Response>
+    where
+        Response: LemmyResponse,
+        Form: LemmyForm,
+    {
+        let Some(filter) = self.filter_for(path) else {
+            return self
+                .client
+                .make_request(method, path, request, &self.headers)
+                .await;
+        };
+        let RawBody(mut response) = self
+            .client
+            .make_request(method, path, request, &self.headers)
+            .await?;
+        filter.apply(path, &mut response);
+        serde_json::from_value(response)
+            .map_err(|e| Error::with_kind(ErrorKind::Decode, e.to_string()))
+    }
+}
+
+impl LemmyClient {
+    /// Sets the filter applied to the posts, comments, communities, and people of list responses, before they are returned: [`LemmyClient::list_posts`], [`LemmyClient::list_comments`], and [`LemmyClient::search`], their `_as` methods, the same methods of [anonymous](crate::Anonymous) and [authenticated](crate::Authenticated) clients made from this one, [`LemmyClient::call`], and [`Feed`](crate::Feed)s. [`None`] removes the filter, which is the default.
+    ///
+    /// Setting a filter resets the [statistics](LemmyClient::content_filter_stats). Pages can come back shorter than their limit, or empty, while more pages remain. Helpers that page through results themselves, such as archives, syncs, and polling, see every item, so hidden items never end them early.
+    ///
+    /// ```no_run
+    /// use lemmy_client::{lemmy_api_common::post::GetPosts, ClientOptions, ContentFilter, LemmyClient};
+    ///
+    /// # async fn example() -> Result<(), lemmy_client::Error> {
+    /// let mut client = LemmyClient::new(ClientOptions {
+    ///     domain: String::from("lemmy.ml"),
+    ///     secure: true,
+    /// });
+    /// client.set_content_filter(Some(ContentFilter {
+    ///     hide_bots: true,
+    ///     ..Default::default()
+    /// }));
+    ///
+    /// // Typed clients keep the filter.
+    /// let client = client.into_anonymous();
+    /// let posts = client.list_posts(GetPosts::default()).await?.posts;
+    /// assert!(posts.iter().all(|post_view| !post_view.creator.bot_account));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_content_filter(&mut self, filter: Option<ContentFilter>) {
+        self.client.hooks.content_filter = filter.map(|filter| {
+            Arc::new(ActiveFilter {
+                filter,
+                counters: FilterCounters::default(),
+            })
+        });
+    }
+
+    /// How many items each rule of the content filter hid since it was set. See [`LemmyClient::set_content_filter`].
+    pub fn content_filter_stats(&self) -> FilterStats {
+        self.client
+            .hooks
+            .content_filter
+            .as_ref()
+            .map(|filter| filter.stats())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::paged_stream,
+    LemmyClient, LemmyRequest,
+};
+use futures::TryStreamExt;
+use lemmy_api_common::{
+    lemmy_db_schema::{newtypes::PersonId, source::person::Person},
+    lemmy_db_views::structs::PrivateMessageView,
+    private_message::{CreatePrivateMessage, GetPrivateMessages, MarkPrivateMessageAsRead},
+};
+use std::collections::HashMap;
+
+/// How many private messages are fetched at a time.
+const MESSAGE_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone)]
+/// The private messages between the logged in user and one other user, as grouped by [`group_conversations`].
+pub struct Conversation {
+    me: PersonId,
+    /// The other user.
+    pub counterpart: Person,
+    /// The messages, oldest first.
+    pub messages: Vec<PrivateMessageView>,
+}
+
+/// Groups private messages, such as [`PrivateMessagesResponse::private_messages`](lemmy_api_common::private_message::PrivateMessagesResponse::private_messages), into conversations with each user `me` wrote to or got messages from. Conversations with the most recent message come first.
+///
+/// Messages `me` sent to themselves form a conversation with themselves.
+pub fn group_conversations(messages: Vec<PrivateMessageView>, me: PersonId) -> Vec<Conversation> {
+    let mut conversations = HashMap::<PersonId, Conversation>::new();
+    for message_view in messages {
+        let counterpart = if message_view.creator.id == me {
+            &message_view.recipient
+        } else {
+            &message_view.creator
+        };
+        conversations
+            .entry(counterpart.id)
+            .or_insert_with(|| Conversation {
+                me,
+                counterpart: counterpart.clone(),
+                messages: Vec::new(),
+            })
+            .messages
+            .push(message_view);
+    }
+
+    let mut conversations = conversations.into_values().collect::<Vec<_>>();
+    for conversation in &mut conversations {
+        conversation
+            .messages
+            .sort_by_key(|message_view| message_view.private_message.published);
+    }
+    conversations.sort_by(|a, b| {
+        let latest = |conversation: &Conversation| {
+            conversation
+                .last_message()
+                .map(|message_view| message_view.private_message.published)
+        };
+        latest(b).cmp(&latest(a))
+    });
+
+    conversations
+}
+
+impl Conversation {
+    /// The most recent message.
+    pub fn last_message(&self) -> Option<&PrivateMessageView> {
+        self.messages.last()
+    }
+
+    /// The messages from the other user that the logged in user has not read yet.
+    pub fn unread(&self) -> impl Iterator<Item = &PrivateMessageView> {
+        self.messages.iter().filter(|message_view| {
+            message_view.private_message.recipient_id == self.me
+                && !message_view.private_message.read
+        })
+    }
+
+    /// The number of messages from the other user that the logged in user has not read yet.
+    pub fn unread_count(&self) -> usize {
+        self.unread().count()
+    }
+
+    /// Sends a message to the other user, adding it to the conversation.
+    pub async fn reply(
+        &mut self,
+        client: &LemmyClient,
+        text: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<&PrivateMessageView> {
+        let form = CreatePrivateMessage {
+            content: text.to_owned(),
+            recipient_id: self.counterpart.id,
+        };
+        let message_view = client
+            .client
+            .create_private_message(LemmyRequest::with_jwt(form, jwt), &client.headers)
+            .await?
+            .private_message_view;
+        self.messages.push(message_view);
+
+        Ok(&self.messages[self.messages.len() - 1])
+    }
+
+    /// Marks every unread message from the other user as read, returning how many were marked. Messages stay unread in the conversation if marking them fails.
+    pub async fn mark_read(
+        &mut self,
+        client: &LemmyClient,
+        jwt: Option<String>,
+    ) -> LemmyResult<usize> {
+        let me = self.me;
+        let mut marked = 0;
+        for message_view in &mut self.messages {
+            let message = &mut message_view.private_message;
+            if message.recipient_id != me || message.read {
+                continue;
+            }
+
+            let form = MarkPrivateMessageAsRead {
+                private_message_id: message.id,
+                read: true,
+            };
+            client
+                .client
+                .mark_private_message_as_read(
+                    LemmyRequest::with_jwt(form, jwt.clone()),
+                    &client.headers,
+                )
+                .await?;
+            message.read = true;
+            marked += 1;
+        }
+
+        Ok(marked)
+    }
+}
+
+impl LemmyClient {
+    /// Fetches every private message of the logged in user, grouped into conversations. See [`group_conversations`].
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # async fn example(client: LemmyClient, jwt: String) -> Result<(), lemmy_client::Error> {
+    /// let mut conversations = client.conversations(Some(jwt.clone())).await?;
+    /// if let Some(conversation) = conversations.first_mut() {
+    ///     println!("{} unread from {}", conversation.unread_count(), conversation.counterpart.name);
+    ///     conversation.mark_read(&client, Some(jwt.clone())).await?;
+    ///     conversation.reply(&client, "Thanks!", Some(jwt)).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn conversations(&self, jwt: Option<String>) -> LemmyResult<Vec<Conversation>> {
+        let me = self.my_user(jwt.clone()).await?.local_user_view.person.id;
+        let messages = paged_stream(1, |page| {
+            let form = GetPrivateMessages {
+                page: Some(page),
+                limit: Some(MESSAGE_PAGE_SIZE),
+                ..Default::default()
+            };
+            let request = LemmyRequest::with_jwt(form, jwt.clone());
+            async move {
+                self.client
+                    .list_private_messages(request, &self.headers)
+                    .await
+                    .map(|response| response.private_messages)
+            }
+        })
+        .try_collect()
+        .await?;
+
+        Ok(group_conversations(messages, me))
+    }
+}
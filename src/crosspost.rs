@@ -0,0 +1,104 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, markdown::MarkdownBuilder, response::LemmyResult,
+    CommunityHandle, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    lemmy_db_schema::{newtypes::PostId, SearchType},
+    lemmy_db_views::structs::PostView,
+    post::{CreatePost, GetPost, PostResponse},
+    site::Search,
+};
+
+#[derive(Debug, Clone)]
+/// The outcome of [`LemmyClient::crosspost`].
+pub enum CrosspostOutcome {
+    /// The crosspost was created.
+    Created(PostResponse),
+    /// The post had already been crossposted to the target community, so nothing was created.
+    AlreadyExists(PostView),
+}
+
+/// Formats a post body the way Lemmy's web UI does for crossposts.
+fn crosspost_body(ap_id: &str, body: Option<&str>) -> String {
+    let builder = MarkdownBuilder::new().raw(&format!("cross-posted from: {ap_id}"));
+    match body.filter(|body| !body.trim().is_empty()) {
+        Some(body) => builder.quote(body),
+        None => builder,
+    }
+    .build()
+}
+
+impl LemmyClient {
+    /// Crossposts a post to another community.
+    ///
+    /// The new post keeps the title, URL, NSFW flag, and language of the original, and its body links back to the original with the original's body quoted underneath. The thumbnail is regenerated by the instance from the URL.
+    ///
+    /// If `skip_existing` is true and the post was already crossposted to the target community, the existing crosspost is returned instead of creating a new one.
+    pub async fn crosspost(
+        &self,
+        post_id: PostId,
+        target_community: &CommunityHandle,
+        skip_existing: bool,
+        jwt: Option<String>,
+    ) -> LemmyResult<CrosspostOutcome> {
+        let form = GetPost {
+            id: Some(post_id),
+            comment_id: None,
+        };
+        let original = self
+            .client
+            .get_post(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+            .await?;
+        let community_id = self
+            .resolve_community_id(target_community, jwt.clone())
+            .await?;
+        let post = original.post_view.post;
+        let ap_id = post.ap_id.to_string();
+
+        if skip_existing {
+            if let Some(existing) = original
+                .cross_posts
+                .into_iter()
+                .find(|cross_post| cross_post.community.id == community_id)
+            {
+                return Ok(CrosspostOutcome::AlreadyExists(existing));
+            }
+
+            // Posts without a URL are not listed as crossposts, so fall back to searching for the backlink.
+            let form = Search {
+                q: ap_id.clone(),
+                community_id: Some(community_id),
+                type_: Some(SearchType::Posts),
+                ..Default::default()
+            };
+            let search = self
+                .client
+                .search(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                .await?;
+            if let Some(existing) = search.posts.into_iter().find(|post_view| {
+                post_view
+                    .post
+                    .body
+                    .as_deref()
+                    .is_some_and(|body| body.contains(&ap_id))
+            }) {
+                return Ok(CrosspostOutcome::AlreadyExists(existing));
+            }
+        }
+
+        let form = CreatePost {
+            name: post.name,
+            community_id,
+            url: post.url.map(Into::into),
+            body: Some(crosspost_body(&ap_id, post.body.as_deref())),
+            honeypot: None,
+            nsfw: Some(post.nsfw),
+            language_id: Some(post.language_id),
+        };
+
+        self.client
+            .create_post(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+            .map(CrosspostOutcome::Created)
+    }
+}
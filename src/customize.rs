@@ -0,0 +1,135 @@
+use crate::{
+    cache::TtlCache, concurrency::ConcurrencyLimit, hooks::RequestHooks, ClientOptions, LemmyClient,
+};
+use lemmy_api_common::sensitive::Sensitive;
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(not(target_family = "wasm"))]
+/// The builder of the requests the client sends, which [`LemmyClient::customize`] hands out.
+pub type RequestBuilder = reqwest::RequestBuilder;
+
+#[cfg(target_family = "wasm")]
+/// The builder of the requests the client sends, which [`LemmyClient::customize`] hands out.
+pub type RequestBuilder = gloo_net::http::RequestBuilder;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The settings of a client that [`LemmyClient::derive`] can change.
+pub struct DeriveOptions {
+    /// The instance the derived client sends requests to.
+    pub client_options: ClientOptions,
+    /// The headers sent with every request. See [`LemmyClient::headers_mut`].
+    pub headers: HashMap<String, String>,
+    /// The JWT sent with requests that do not pass one, e.g. to act as another account. Audit entries record the account it belongs to.
+    pub jwt: Option<Sensitive<String>>,
+}
+
+/// Changes a request just before it is sent.
+pub(crate) type RequestCustomizer = Arc<dyn Fn(RequestBuilder) -> RequestBuilder + Send + Sync>;
+
+impl<State: Clone> LemmyClient<State> {
+    /// Returns a copy of the client that passes the builder of each endpoint request through `customize` just before sending it, for needs this crate has no option for, such as a timeout or header for a single call. The copy shares the connections of the client, but not its caches.
+    ///
+    /// Customizing a customized client applies both closures, the earlier one first. The JWT, headers, and body are already set on the builder, so changing them may break the request.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # #[cfg(not(target_family = "wasm"))]
+    /// # async fn example(client: LemmyClient) -> Result<(), lemmy_client::Error> {
+    /// use std::time::Duration;
+    ///
+    /// let site = client
+    ///     .customize(|request| {
+    ///         request
+    ///             .timeout(Duration::from_secs(5))
+    ///             .header("X-Request-Source", "health-check")
+    ///     })
+    ///     .get_site()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn customize<F>(&self, customize: F) -> Self
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+    {
+        self.with_hooks(|hooks| {
+            hooks.customize = Some(match hooks.customize.take() {
+                Some(previous) => Arc::new(move |request| customize(previous(request))),
+                None => Arc::new(customize),
+            });
+        })
+    }
+
+    /// Returns a copy of the client with changed request hooks, sharing its connections but not its caches.
+    pub(crate) fn with_hooks(&self, change: impl FnOnce(&mut RequestHooks)) -> Self {
+        let mut client = self.client.clone();
+        change(&mut client.hooks);
+
+        LemmyClient {
+            state: self.state.clone(),
+            headers: self.headers.clone(),
+            site_metadata_cache: Arc::new(TtlCache::new(self.site_metadata_cache.ttl())),
+            validate_forms: self.validate_forms,
+            image_proxy: self.image_proxy,
+            client,
+        }
+    }
+
+    /// Returns a client with the options changed by `change`, such as another account or instance, that shares the connection pool, concurrency and bandwidth limits, hooks, and caches of this one. Apps with many accounts or instances can use one derived client per account or instance without opening more connections.
+    ///
+    /// A client for another instance uses the [host concurrency limit](LemmyClient::set_host_concurrency_limit) of that instance, with the same maximum. Settings that depend on the software of the instance are reset to their defaults: the [server adapter](LemmyClient::set_server_adapter), [legacy authentication](LemmyClient::set_legacy_auth), and the [method override](LemmyClient::set_method_override). Run detection again for the new instance, e.g. with [`LemmyClient::detect_server_adapter`].
+    ///
+    /// ```
+    /// use lemmy_client::{lemmy_api_common::sensitive::Sensitive, ClientOptions, LemmyClient};
+    ///
+    /// let client = LemmyClient::new(ClientOptions {
+    ///     domain: String::from("lemmy.ml"),
+    ///     secure: true,
+    /// });
+    /// let alt_account = client.derive(|options| options.jwt = Some(Sensitive::new(String::from("alt-jwt"))));
+    /// let other_instance = client.derive(|options| {
+    ///     options.client_options.domain = String::from("lemmy.world");
+    /// });
+    /// assert_eq!(other_instance.client_options().domain, "lemmy.world");
+    /// ```
+    pub fn derive(&self, change: impl FnOnce(&mut DeriveOptions)) -> Self {
+        let mut options = DeriveOptions {
+            client_options: self.client_options().clone(),
+            headers: self.headers.clone(),
+            jwt: self.client.hooks.jwt.clone().map(Sensitive::new),
+        };
+        change(&mut options);
+
+        let mut client = if options.client_options == *self.client_options() {
+            self.client.clone()
+        } else {
+            let mut client = self.client.with_options(options.client_options);
+            if let Some(limit) = &self.client.hooks.host_limit {
+                client.hooks.host_limit = Some(ConcurrencyLimit::for_host(
+                    &client.client_options().domain,
+                    limit.max(),
+                ));
+            }
+            client.hooks.adapter = None;
+            client.hooks.legacy_auth = false;
+            client.hooks.method_override = false;
+            client.hooks.instance_version = Arc::default();
+            client
+        };
+        client.hooks.jwt = options.jwt.map(Sensitive::into_inner);
+
+        LemmyClient {
+            state: self.state.clone(),
+            headers: options.headers,
+            site_metadata_cache: self.site_metadata_cache.clone(),
+            validate_forms: self.validate_forms,
+            image_proxy: self.image_proxy,
+            client,
+        }
+    }
+
+    /// `jwt`, or the JWT of the client if it was [derived](LemmyClient::derive) with one.
+    pub(crate) fn jwt_or_default(&self, jwt: Option<String>) -> Option<String> {
+        jwt.or_else(|| self.client.hooks.jwt.clone())
+    }
+}
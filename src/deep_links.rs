@@ -0,0 +1,176 @@
+use crate::{
+    lemmy_client_internal::build_url, utils::ClientOptions, ContentReference, LemmyClient,
+};
+use lemmy_api_common::{
+    lemmy_db_schema::newtypes::{CommentId, PostId},
+    site::{GetModlog, Search},
+};
+use serde::Serialize;
+use url::form_urlencoded;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Builds links to pages of an instance's web UI, the way Lemmy's web UI writes them, e.g. for bots to link to content in what they post. [`extract_references`](crate::extract_references) parses the links back into a [`ContentReference`].
+///
+/// ```
+/// use lemmy_client::{ClientOptions, DeepLinks};
+/// use lemmy_client::lemmy_api_common::{lemmy_db_schema::newtypes::PostId, site::Search};
+///
+/// let links = DeepLinks::new(&ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// assert_eq!(links.post(PostId(1)), "https://lemmy.ml/post/1");
+/// assert_eq!(
+///     links.community("rust", Some("programming.dev")),
+///     "https://lemmy.ml/c/rust@programming.dev"
+/// );
+///
+/// let search = Search {
+///     q: String::from("async traits"),
+///     ..Default::default()
+/// };
+/// assert_eq!(links.search(&search), "https://lemmy.ml/search?q=async+traits");
+/// ```
+pub struct DeepLinks {
+    options: ClientOptions,
+}
+
+/// The name of an enum variant as it is sent to the API, e.g. `Comments` for [`SearchType::Comments`](lemmy_api_common::lemmy_db_schema::SearchType::Comments).
+fn query_value<T: Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value).ok()? {
+        serde_json::Value::String(value) => Some(value),
+        value => Some(value.to_string()),
+    }
+}
+
+impl DeepLinks {
+    /// Creates a builder for links to the instance of `options`.
+    pub fn new(options: &ClientOptions) -> Self {
+        Self {
+            options: options.clone(),
+        }
+    }
+
+    /// The domain links point at.
+    pub fn domain(&self) -> &str {
+        &self.options.domain
+    }
+
+    fn page(&self, path: &str) -> String {
+        build_url(path, &self.options)
+    }
+
+    /// Adds the parameters that are set as a query string.
+    fn with_query<'a>(
+        mut url: String,
+        params: impl IntoIterator<Item = (&'a str, Option<String>)>,
+    ) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in params {
+            if let Some(value) = value {
+                query.append_pair(key, &value);
+            }
+        }
+        let query = query.finish();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+
+        url
+    }
+
+    /// Names remote users and communities with their home instance, so the instance knows which one is meant.
+    fn actor_path(&self, kind: &str, name: &str, instance: Option<&str>) -> String {
+        match instance {
+            Some(instance) if !instance.eq_ignore_ascii_case(self.domain()) => {
+                self.page(&format!("{kind}/{name}@{instance}"))
+            }
+            _ => self.page(&format!("{kind}/{name}")),
+        }
+    }
+
+    /// The page of a post.
+    pub fn post(&self, post_id: PostId) -> String {
+        self.page(&format!("post/{}", post_id.0))
+    }
+
+    /// The page of a comment, which shows it in its thread.
+    pub fn comment(&self, comment_id: CommentId) -> String {
+        self.page(&format!("comment/{}", comment_id.0))
+    }
+
+    /// The page of a community. `instance` is the home instance of remote communities.
+    pub fn community(&self, name: &str, instance: Option<&str>) -> String {
+        self.actor_path("c", name, instance)
+    }
+
+    /// The profile of a user. `instance` is the home instance of remote users.
+    pub fn person(&self, name: &str, instance: Option<&str>) -> String {
+        self.actor_path("u", name, instance)
+    }
+
+    /// The search page, showing the results of `search`. The limit is left out, since the web UI picks its own.
+    pub fn search(&self, search: &Search) -> String {
+        Self::with_query(
+            self.page("search"),
+            [
+                ("q", Some(search.q.clone())),
+                ("type", search.type_.as_ref().and_then(query_value)),
+                (
+                    "listingType",
+                    search.listing_type.as_ref().and_then(query_value),
+                ),
+                (
+                    "communityId",
+                    search.community_id.map(|id| id.0.to_string()),
+                ),
+                ("creatorId", search.creator_id.map(|id| id.0.to_string())),
+                ("sort", search.sort.as_ref().and_then(query_value)),
+                ("page", search.page.map(|page| page.to_string())),
+            ],
+        )
+    }
+
+    /// The modlog, filtered like `modlog`. The limit is left out, since the web UI picks its own.
+    pub fn modlog(&self, modlog: &GetModlog) -> String {
+        let url = match modlog.community_id {
+            Some(community_id) => self.page(&format!("modlog/{}", community_id.0)),
+            None => self.page("modlog"),
+        };
+        Self::with_query(
+            url,
+            [
+                ("actionType", modlog.type_.as_ref().and_then(query_value)),
+                ("modId", modlog.mod_person_id.map(|id| id.0.to_string())),
+                ("userId", modlog.other_person_id.map(|id| id.0.to_string())),
+                ("page", modlog.page.map(|page| page.to_string())),
+            ],
+        )
+    }
+
+    /// The page a reference found by [`extract_references`](crate::extract_references) points at. Posts and comments are linked on the instance they were found on, since their ids differ between instances.
+    pub fn reference(&self, reference: &ContentReference) -> String {
+        let on_instance = |instance: &str| ClientOptions {
+            domain: instance.to_owned(),
+            secure: self.options.secure,
+        };
+        match reference {
+            ContentReference::User { name, instance } => self.person(name, Some(instance)),
+            ContentReference::Community { name, instance } => self.community(name, Some(instance)),
+            ContentReference::Post { id, instance } => {
+                build_url(&format!("post/{id}"), &on_instance(instance))
+            }
+            ContentReference::Comment { id, instance } => {
+                build_url(&format!("comment/{id}"), &on_instance(instance))
+            }
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Builds links to pages of the client's instance. See [`DeepLinks`].
+    pub fn deep_links(&self) -> DeepLinks {
+        DeepLinks::new(self.client_options())
+    }
+}
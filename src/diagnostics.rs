@@ -0,0 +1,74 @@
+use crate::{Error, ErrorKind};
+use miette::Diagnostic;
+use std::fmt::Display;
+
+/// The version of Lemmy whose API this crate's types match.
+const SUPPORTED_VERSION: &str = "0.19.3";
+
+impl Error {
+    fn help_text(&self) -> Option<String> {
+        let help = match (self.kind(), self.message()) {
+            (ErrorKind::Decode, _) => {
+                return Some(match self.instance_version() {
+                    Some(version) => format!(
+                        "This crate supports Lemmy {SUPPORTED_VERSION}, but the instance runs \
+                         {version}."
+                    ),
+                    None => format!(
+                        "This crate supports Lemmy {SUPPORTED_VERSION}. The instance probably runs \
+                         a different version, which `LemmyClient::get_site` reports in `version`."
+                    ),
+                })
+            }
+            (ErrorKind::Api, "rate_limit_error") => {
+                "The instance is rate limiting this client. Wait a few seconds before retrying, \
+                 or send fewer requests at once with `LemmyClient::set_concurrency_limit`."
+            }
+            (ErrorKind::Api, "incorrect_login") => {
+                "Check the username or email and the password. Accounts with two-factor \
+                 authentication also need a TOTP token."
+            }
+            (ErrorKind::Api | ErrorKind::Other, "not_logged_in") => {
+                "Pass the JWT returned by `LemmyClient::login` with the request."
+            }
+            (ErrorKind::Api | ErrorKind::Other, "not_a_mod_or_admin") => {
+                "The logged in user has to moderate the community or be an admin of the instance."
+            }
+            (ErrorKind::Api, code) if code.starts_with("http_status_") => {
+                "The response did not come from Lemmy. Check that the domain points at a Lemmy \
+                 instance rather than a proxy or its front end."
+            }
+            (ErrorKind::Network, _) => {
+                "Check that the domain of the instance is correct and can be reached, and \
+                 whether `secure` needs to be set in `ClientOptions`."
+            }
+            (ErrorKind::Aborted, _) => {
+                "The request was cancelled before it finished, so it may or may not have taken \
+                 effect."
+            }
+            _ => return None,
+        };
+
+        Some(help.to_owned())
+    }
+}
+
+impl Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let kind = match self.kind() {
+            ErrorKind::Api => return Some(Box::new(format!("lemmy::{}", self.message()))),
+            ErrorKind::Network => "network",
+            ErrorKind::Decode => "decode",
+            ErrorKind::Aborted => "aborted",
+            ErrorKind::Validation => "validation",
+            _ => "other",
+        };
+
+        Some(Box::new(format!("lemmy_client::{kind}")))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.help_text()
+            .map(|help| Box::new(help) as Box<dyn Display + 'a>)
+    }
+}
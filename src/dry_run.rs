@@ -0,0 +1,117 @@
+use crate::{hooks::RequestHooks, response::LemmyResult, Error, LemmyClient};
+use http::Method;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The error code of requests that a dry run client held back.
+pub(crate) const DRY_RUN_CODE: &str = "dry_run";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A request that a dry run client would have sent, as collected in a [`DryRunLog`].
+pub struct DryRunRequest {
+    /// The HTTP method of the request.
+    pub method: Method,
+    /// The path of the endpoint, such as `post/remove`, or the URL for requests to other sites, such as those of an [`EventSink`](crate::EventSink).
+    pub path: String,
+    /// The JSON body of the request, without the JWT. `null` for requests without a JSON body, such as image uploads.
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The requests held back by clients made with [`LemmyClient::dry_run`], oldest first. Clones share the same requests.
+pub struct DryRunLog(Arc<Mutex<Vec<DryRunRequest>>>);
+
+impl DryRunLog {
+    /// A copy of the requests held back so far.
+    pub fn requests(&self) -> Vec<DryRunRequest> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Removes and returns the requests held back so far.
+    pub fn take(&self) -> Vec<DryRunRequest> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// How many requests were held back so far.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Whether no request was held back yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&self, request: DryRunRequest) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request);
+    }
+}
+
+impl Error {
+    /// Whether the request was held back by a dry run client instead of being sent. See [`LemmyClient::dry_run`].
+    pub fn is_dry_run(&self) -> bool {
+        self.message() == DRY_RUN_CODE
+    }
+}
+
+/// Whether a request changes anything on the instance. Logging in only creates a session, so it is sent even in a dry run.
+pub(crate) fn is_mutating(method: &Method, path: &str) -> bool {
+    (method != Method::GET && path != "user/login") || path == "pictrs/image/delete"
+}
+
+/// Records a request in the dry run log of the hooks, if there is one and the request is mutating, returning the error to fail it with instead of sending it.
+pub(crate) fn hold_back(
+    hooks: &RequestHooks,
+    method: &Method,
+    path: &str,
+    body: impl FnOnce() -> serde_json::Value,
+) -> LemmyResult<()> {
+    let Some(log) = &hooks.dry_run else {
+        return Ok(());
+    };
+    if !is_mutating(method, path) {
+        return Ok(());
+    }
+
+    let mut body = body();
+    if let Some(form) = body.as_object_mut() {
+        // Forms carry the JWT in `auth` when talking to Lemmy 0.18.
+        form.remove("auth");
+    }
+    log.push(DryRunRequest {
+        method: method.clone(),
+        path: path.to_owned(),
+        body,
+    });
+
+    Err(Error::new(DRY_RUN_CODE))
+}
+
+impl<State: Clone> LemmyClient<State> {
+    /// Returns a copy of the client that records mutating requests in `log` instead of sending them, failing them with an error for which [`Error::is_dry_run`] is true. Reads, and logging in, are sent as usual, so scripts such as moderation bots can be tried against live data safely.
+    ///
+    /// Requests are mutating if they use a method other than GET, or delete an image. Requests to other sites, such as those of [`LemmyClient::forward_events`], are held back as well. The copy shares the connections of the client, but not its caches.
+    ///
+    /// ```no_run
+    /// use lemmy_client::{lemmy_api_common::post::RemovePost, DryRunLog, LemmyClient, LemmyRequest};
+    ///
+    /// # async fn example(client: LemmyClient, form: RemovePost, jwt: String) {
+    /// let log = DryRunLog::default();
+    /// let dry_run = client.dry_run(log.clone());
+    /// let request = LemmyRequest { body: form, jwt: Some(jwt) };
+    /// let result = dry_run.remove_post(request).await;
+    /// assert!(result.is_err_and(|e| e.is_dry_run()));
+    /// for request in log.take() {
+    ///     println!("Would have sent {} {}: {}", request.method, request.path, request.body);
+    /// }
+    /// # }
+    /// ```
+    pub fn dry_run(&self, log: DryRunLog) -> Self {
+        self.with_hooks(|hooks| hooks.dry_run = Some(log))
+    }
+}
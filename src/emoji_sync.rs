@@ -0,0 +1,238 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use lemmy_api_common::{
+    custom_emoji::{CreateCustomEmoji, DeleteCustomEmoji, EditCustomEmoji},
+    lemmy_db_views::structs::CustomEmojiView,
+};
+use url::Url;
+
+#[cfg(not(target_family = "wasm"))]
+/// The extensions of the image files [`EmojiManifest::from_dir`] reads.
+const IMAGE_EXTENSIONS: &[&str] = &["apng", "avif", "gif", "jpeg", "jpg", "jxl", "png", "webp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The image of an emoji in an [`EmojiManifest`].
+pub enum EmojiImage {
+    /// An image that is already hosted somewhere.
+    Url(Url),
+    /// Image data that will be uploaded to the instance if the emoji does not exist yet.
+    ///
+    /// Since the bytes of an existing emoji's image are not compared, an existing emoji keeps its image.
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A custom emoji as it should exist on the instance.
+pub struct EmojiManifestEntry {
+    /// The shortcode used to insert the emoji, without colons.
+    pub shortcode: String,
+    /// The image of the emoji.
+    pub image: EmojiImage,
+    /// Alt text for the image.
+    pub alt_text: String,
+    /// Category the emoji is listed under in the emoji picker.
+    pub category: String,
+    /// Keywords the emoji can be searched by.
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The complete set of custom emojis an instance should have.
+pub struct EmojiManifest(pub Vec<EmojiManifestEntry>);
+
+impl EmojiManifest {
+    /// Builds a manifest from the images in a directory.
+    ///
+    /// Each image file, by its extension, becomes an emoji whose shortcode and alt text are the file name without the extension. Other files, such as `README` or `.DS_Store`, are skipped.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn from_dir(path: impl AsRef<std::path::Path>, category: &str) -> std::io::Result<Self> {
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(path)? {
+            let path = dir_entry?.path();
+            let is_image = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    IMAGE_EXTENSIONS
+                        .iter()
+                        .any(|image| extension.eq_ignore_ascii_case(image))
+                });
+            if !path.is_file() || !is_image {
+                continue;
+            }
+            let Some(shortcode) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            entries.push(EmojiManifestEntry {
+                shortcode: shortcode.to_owned(),
+                alt_text: shortcode.to_owned(),
+                image: EmojiImage::Bytes(std::fs::read(&path)?),
+                category: category.to_owned(),
+                keywords: Vec::new(),
+            });
+        }
+        entries.sort_by(|a, b| a.shortcode.cmp(&b.shortcode));
+
+        Ok(Self(entries))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The outcome of [`LemmyClient::sync_custom_emojis`]. In a dry run, lists what would have been done.
+pub struct EmojiSyncReport {
+    /// Shortcodes of emojis that were created.
+    pub created: Vec<String>,
+    /// Shortcodes of emojis that were edited.
+    pub edited: Vec<String>,
+    /// Shortcodes of emojis that were deleted.
+    pub deleted: Vec<String>,
+    /// Shortcodes of emojis that already matched the manifest.
+    pub unchanged: Vec<String>,
+    /// Shortcodes of emojis that could not be synced.
+    pub failed: Vec<(String, Error)>,
+}
+
+fn differs(existing: &CustomEmojiView, entry: &EmojiManifestEntry) -> bool {
+    let emoji = &existing.custom_emoji;
+    let mut keywords = existing
+        .keywords
+        .iter()
+        .map(|keyword| keyword.keyword.as_str())
+        .collect::<Vec<_>>();
+    keywords.sort_unstable();
+    let mut wanted_keywords = entry
+        .keywords
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    wanted_keywords.sort_unstable();
+
+    emoji.alt_text != entry.alt_text
+        || emoji.category != entry.category
+        || keywords != wanted_keywords
+        || matches!(&entry.image, EmojiImage::Url(url) if *emoji.image_url != *url)
+}
+
+impl LemmyClient {
+    /// Creates, edits, and deletes custom emojis so the instance's emojis match `manifest` (admin action).
+    ///
+    /// If `dry_run` is true, nothing is changed and the report lists what would have been done.
+    pub async fn sync_custom_emojis(
+        &self,
+        manifest: &EmojiManifest,
+        dry_run: bool,
+        jwt: Option<String>,
+    ) -> LemmyResult<EmojiSyncReport> {
+        let existing = self
+            .client
+            .get_site(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+            .await?
+            .custom_emojis;
+        let mut report = EmojiSyncReport::default();
+
+        for entry in &manifest.0 {
+            let shortcode = entry.shortcode.clone();
+            match existing
+                .iter()
+                .find(|view| view.custom_emoji.shortcode == entry.shortcode)
+            {
+                Some(view) if !differs(view, entry) => report.unchanged.push(shortcode),
+                Some(view) => {
+                    if !dry_run {
+                        let result = async {
+                            let image_url = match &entry.image {
+                                EmojiImage::Url(url) => url.clone(),
+                                EmojiImage::Bytes(_) => (*view.custom_emoji.image_url).clone(),
+                            };
+                            let form = EditCustomEmoji {
+                                id: view.custom_emoji.id,
+                                category: entry.category.clone(),
+                                image_url,
+                                alt_text: entry.alt_text.clone(),
+                                keywords: entry.keywords.clone(),
+                            };
+                            self.client
+                                .edit_custom_emoji(
+                                    LemmyRequest::with_jwt(form, jwt.clone()),
+                                    &self.headers,
+                                )
+                                .await
+                        }
+                        .await;
+                        if let Err(e) = result {
+                            report.failed.push((shortcode, e));
+                            continue;
+                        }
+                    }
+                    report.edited.push(shortcode);
+                }
+                None => {
+                    if !dry_run {
+                        let mut uploaded = None;
+                        let result = async {
+                            let image_url = match &entry.image {
+                                EmojiImage::Url(url) => url.clone(),
+                                EmojiImage::Bytes(bytes) => {
+                                    let image = uploaded.insert(
+                                        self.upload_image(bytes.clone(), jwt.clone()).await?,
+                                    );
+                                    Url::parse(&image.url).map_err(|e| Error::new(e.to_string()))?
+                                }
+                            };
+                            let form = CreateCustomEmoji {
+                                category: entry.category.clone(),
+                                shortcode: entry.shortcode.clone(),
+                                image_url,
+                                alt_text: entry.alt_text.clone(),
+                                keywords: entry.keywords.clone(),
+                            };
+                            self.client
+                                .create_custom_emoji(
+                                    LemmyRequest::with_jwt(form, jwt.clone()),
+                                    &self.headers,
+                                )
+                                .await
+                        }
+                        .await;
+                        if let (Err(_), Some(image)) = (&result, &uploaded) {
+                            // The failed creation is what goes in the report, so a failure to clean up is ignored.
+                            let _ = self.delete_image(image, jwt.clone()).await;
+                        }
+                        if let Err(e) = result {
+                            report.failed.push((shortcode, e));
+                            continue;
+                        }
+                    }
+                    report.created.push(shortcode);
+                }
+            }
+        }
+
+        for view in existing.iter().filter(|view| {
+            !manifest
+                .0
+                .iter()
+                .any(|entry| entry.shortcode == view.custom_emoji.shortcode)
+        }) {
+            let shortcode = view.custom_emoji.shortcode.clone();
+            if !dry_run {
+                let form = DeleteCustomEmoji {
+                    id: view.custom_emoji.id,
+                };
+                if let Err(e) = self
+                    .client
+                    .delete_custom_emoji(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                    .await
+                {
+                    report.failed.push((shortcode, e));
+                    continue;
+                }
+            }
+            report.deleted.push(shortcode);
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,222 @@
+/// Declares every endpoint wrapped by this crate exactly once, and passes the list to `$callback` to generate code from it.
+///
+/// Each entry has the doc comment of its [`LemmyClient`](crate::LemmyClient) method followed by
+/// `name(Variant): METHOD "path", auth, (major, minor, patch): Form => Response;`, where
+/// - `name` is the name of the method, and `Variant` the name of its [`EndpointRequest`](crate::EndpointRequest) and [`EndpointResponse`](crate::EndpointResponse) variants.
+/// - `METHOD` and `path` are the HTTP method and the path relative to `/api/v3`.
+/// - `auth` is the [`AuthRequirement`](crate::AuthRequirement) and `(major, minor, patch)` the first Lemmy version with the endpoint.
+/// - `Form` is `()` for endpoints without a body.
+///
+/// Adding an endpoint only requires adding an entry here.
+macro_rules! with_endpoints {
+    ($callback:ident) => {
+        $callback! {
+        /// Gets the site and, if you pass an authorized JWT, information about the logged in user.
+        get_site(GetSite): GET "site", Optional, (0, 19, 0): () => GetSiteResponse;
+        /// Creates site during initial setup.
+        create_site(CreateSite): POST "site", Admin, (0, 19, 0): CreateSite => SiteResponse;
+        /// Edits your site.
+        edit_site(EditSite): PUT "site", Admin, (0, 19, 0): EditSite => SiteResponse;
+        /// Gets the modlog.
+        get_modlog(GetModlog): GET "modlog", Optional, (0, 19, 0): GetModlog => GetModlogResponse;
+        /// Searches for content.
+        search(Search): GET "search", Optional, (0, 19, 0): Search => SearchResponse;
+        /// Fetches an object from a non-local instance.
+        resolve_object(ResolveObject): GET "resolve_object", Optional, (0, 19, 0): ResolveObject => ResolveObjectResponse;
+        /// Gets a community.
+        get_community(GetCommunity): GET "community", Optional, (0, 19, 0): GetCommunity => GetCommunityResponse;
+        /// Creates a new community.
+        create_community(CreateCommunity): POST "community", User, (0, 19, 0): CreateCommunity => CommunityResponse;
+        /// Edits a community.
+        edit_community(EditCommunity): PUT "community", Moderator, (0, 19, 0): EditCommunity => CommunityResponse;
+        /// Hides a community from public view.
+        hide_community(HideCommunity): PUT "community/hide", Admin, (0, 19, 0): HideCommunity => SuccessResponse;
+        /// Lists communities.
+        list_communities(ListCommunities): GET "community/list", Optional, (0, 19, 0): ListCommunities => ListCommunitiesResponse;
+        /// Subscribes to a community.
+        follow_community(FollowCommunity): POST "community/follow", User, (0, 19, 0): FollowCommunity => CommunityResponse;
+        /// Blocks a community.
+        block_community(BlockCommunity): POST "community/block", User, (0, 19, 0): BlockCommunity => BlockCommunityResponse;
+        /// Deletes a community.
+        delete_community(DeleteCommunity): POST "community/delete", User, (0, 19, 0): DeleteCommunity => CommunityResponse;
+        /// Removes a community (moderation action).
+        remove_community(RemoveCommunity): POST "community/remove", Admin, (0, 19, 0): RemoveCommunity => CommunityResponse;
+        /// Transfers a community you own to another user on that community's moderation team.
+        transfer_community(TransferCommunity): POST "community/transfer", Moderator, (0, 19, 0): TransferCommunity => GetCommunityResponse;
+        /// Bans a user from a community.
+        ban_from_community(BanFromCommunity): POST "community/ban_user", Moderator, (0, 19, 0): BanFromCommunity => BanFromCommunityResponse;
+        /// Adds a moderator to your community.
+        add_mod_to_community(AddModToCommunity): POST "community/mod", Moderator, (0, 19, 0): AddModToCommunity => AddModToCommunityResponse;
+        /// Gets the instances that are federated with your instance.
+        ///
+        /// Takes no form. Older versions of this crate took a [`FederatedInstances`](lemmy_api_common::site::FederatedInstances), which is the list type of the response rather than a form, and could not be sent as a query string.
+        get_federated_instances(GetFederatedInstances): GET "federated_instances", Optional, (0, 19, 0): () => GetFederatedInstancesResponse;
+        /// Blocks an instance, hiding its content from you.
+        block_instance(BlockInstance): POST "site/block", User, (0, 19, 0): BlockInstance => BlockInstanceResponse;
+        /// Gets post.
+        get_post(GetPost): GET "post", Optional, (0, 19, 0): GetPost => GetPostResponse;
+        /// Creates a post.
+        create_post(CreatePost): POST "post", User, (0, 19, 0): CreatePost => PostResponse;
+        /// Edits a post you have already created.
+        edit_post(EditPost): PUT "post", User, (0, 19, 0): EditPost => PostResponse;
+        /// Deletes a post you have made.
+        delete_post(DeletePost): POST "post/delete", User, (0, 19, 0): DeletePost => PostResponse;
+        /// Removes a post (moderator action).
+        remove_post(RemovePost): POST "post/remove", Moderator, (0, 19, 0): RemovePost => PostResponse;
+        /// Marks one or more posts as read. Use `post_ids` to mark several posts at once.
+        mark_post_as_read(MarkPostAsRead): POST "post/mark_as_read", User, (0, 19, 0): MarkPostAsRead => SuccessResponse;
+        /// Prevents users from commenting on the post (moderator action).
+        lock_post(LockPost): POST "post/lock", Moderator, (0, 19, 0): LockPost => PostResponse;
+        /// Pins a post to the top of the community page (moderator action).
+        feature_post(FeaturePost): POST "post/feature", Moderator, (0, 19, 0): FeaturePost => PostResponse;
+        /// Gets posts with a variety of filters.
+        list_posts(ListPosts): GET "post/list", Optional, (0, 19, 0): GetPosts => GetPostsResponse;
+        /// Votes on a post.
+        like_post(LikePost): POST "post/like", User, (0, 19, 0): CreatePostLike => PostResponse;
+        /// Lists the likes for a post.
+        list_post_likes(ListPostLikes): GET "post/like/list", Moderator, (0, 19, 0): ListPostLikes => ListPostLikesResponse;
+        /// Saves a post to your favorites list.
+        save_post(SavePost): PUT "post/save", User, (0, 19, 0): SavePost => PostResponse;
+        /// Hides or unhides posts from your feeds. Requires Lemmy 0.19.4 or newer.
+        hide_post(HidePost): POST "post/hide", User, (0, 19, 4): HidePost => SuccessResponse;
+        /// Gets posts like [`LemmyClient::list_posts`], optionally including posts you hid. Requires Lemmy 0.19.4 or newer.
+        list_posts_with_hidden(ListPostsWithHidden): GET "post/list", Optional, (0, 19, 4): GetPostsWithHidden => GetPostsResponse;
+        /// Reports a post to the moderator team of the community the post is in, the admin team of your instance, and the admin team of the poster's instance.
+        report_post(ReportPost): POST "post/report", User, (0, 19, 0): CreatePostReport => PostReportResponse;
+        /// Resolves a post report (moderator action).
+        resolve_post_report(ResolvePostReport): PUT "post/report/resolve", Moderator, (0, 19, 0): ResolvePostReport => PostReportResponse;
+        /// Gets reports of posts that you are able to moderate.
+        list_post_reports(ListPostReports): GET "post/report/list", Moderator, (0, 19, 0): ListPostReports => ListPostReportsResponse;
+        /// Gets the metadata of a given site.
+        get_post_url_metadata(GetPostUrlMetadata): GET "post/site_metadata", Optional, (0, 19, 0): GetSiteMetadata => GetSiteMetadataResponse;
+        /// Gets a comment.
+        get_comment(GetComment): GET "comment", Optional, (0, 19, 0): GetComment => CommentResponse;
+        /// Creates a new comment.
+        create_comment(CreateComment): POST "comment", User, (0, 19, 0): CreateComment => CommentResponse;
+        /// Edits one of your already-created comments.
+        edit_comment(EditComment): PUT "comment", User, (0, 19, 0): EditComment => CommentResponse;
+        /// Deletes one of your already-existing comments.
+        delete_comment(DeleteComment): POST "comment/delete", User, (0, 19, 0): DeleteComment => CommentResponse;
+        /// Removes a post (moderator action).
+        remove_comment(RemoveComment): POST "comment/remove", Moderator, (0, 19, 0): RemoveComment => CommentResponse;
+        /// Marks a reply to one of your posts or comments as read.
+        mark_reply_as_read(MarkReplyAsRead): POST "comment/mark_as_read", User, (0, 19, 0): MarkCommentReplyAsRead => CommentReplyResponse;
+        /// Pins a comment to the top of a post's comment section (speak as moderator).
+        distinguish_comment(DistinguishComment): POST "comment/distinguish", Moderator, (0, 19, 0): DistinguishComment => CommentResponse;
+        /// Votes on a comment.
+        like_comment(LikeComment): POST "comment/like", User, (0, 19, 0): CreateCommentLike => CommentResponse;
+        /// Gets the votes for a comment.
+        list_comment_likes(ListCommentLikes): GET "comment/like/list", Moderator, (0, 19, 0): ListCommentLikes => ListCommentLikesResponse;
+        /// Saves a comment to your favorites list.
+        save_comment(SaveComment): PUT "comment/save", User, (0, 19, 0): SaveComment => CommentResponse;
+        /// Gets comments with various filters.
+        list_comments(ListComments): GET "comment/list", Optional, (0, 19, 0): GetComments => GetCommentsResponse;
+        /// Reports a comment to the moderator team of the community the comment is in, your instance's admin team, and the commentor's instance's admin team.
+        create_comment_report(CreateCommentReport): POST "comment/report", User, (0, 19, 0): CreateCommentReport => CommentResponse;
+        /// Resolves a report on a comment made in a community you moderate or instance you administrate.
+        resolve_comment_report(ResolveCommentReport): PUT "comment/report/resolve", Moderator, (0, 19, 0): ResolveCommentReport => CommentReportResponse;
+        /// Lists reports for comments in communities you moderate or instances you adminstrate.
+        list_comment_reports(ListCommentReports): GET "comment/report/list", Moderator, (0, 19, 0): ListCommentReports => ListCommentReportsResponse;
+        /// Creates and send a private message to another user.
+        create_private_message(CreatePrivateMessage): POST "private_message", User, (0, 19, 0): CreatePrivateMessage => PrivateMessageResponse;
+        /// Edits a private message you have already sent.
+        edit_private_message(EditPrivateMessage): PUT "private_message", User, (0, 19, 0): EditPrivateMessage => PrivateMessageResponse;
+        /// Lists private messages that have been sent to you.
+        list_private_messages(ListPrivateMessages): GET "private_message/list", User, (0, 19, 0): GetPrivateMessages => PrivateMessagesResponse;
+        /// Deletes a private that you have already sent.
+        delete_private_message(DeletePrivateMessage): POST "private_message/delete", User, (0, 19, 0): DeletePrivateMessage => PrivateMessageResponse;
+        /// Marks a private message that was sent to you as read.
+        mark_private_message_as_read(MarkPrivateMessageAsRead): POST "private_message/mark_as_read", User, (0, 19, 0): MarkPrivateMessageAsRead => PrivateMessageResponse;
+        /// Reports a private message that was sent to you to your instance's admin team and the sender's instance's admin team.
+        create_private_message_report(CreatePrivateMessageReport): POST "private_message/report", User, (0, 19, 0): CreatePrivateMessageReport => PrivateMessageReportResponse;
+        /// Resolves a report of a private message sent to a user on the instance you administrate.
+        resolve_private_message_report(ResolvePrivateMessageReport): PUT "private_message/report/resolve", Admin, (0, 19, 0): ResolvePrivateMessageReport => PrivateMessageReportResponse;
+        /// Lists reports of private messages received on the isntance you administrate.
+        list_private_message_reports(ListPrivateMessageReports): GET "private_message/report/list", Admin, (0, 19, 0): ListPrivateMessageReports => ListPrivateMessageReportsResponse;
+        /// Gets the publicly viewable details of a user's account.
+        get_person(GetPerson): GET "user", Optional, (0, 19, 0): GetPersonDetails => GetPersonDetailsResponse;
+        /// Registers a new account on an instance.
+        register_account(RegisterAccount): POST "user/register", None, (0, 19, 0): Register => RegistrationApplicationResponse;
+        /// Gets a captcha.
+        get_captcha(GetCaptcha): GET "user/get_captcha", None, (0, 19, 0): () => GetCaptchaResponse;
+        /// Exports a backup of your user settings - including your saved content, followed communities, and blocks - as JSON.
+        export_settings(ExportSettings): GET "user/export_settings", User, (0, 19, 0): () => String;
+        /// Imports a backup of your user settings.
+        import_settings(ImportSettings): POST "user/import_settings", User, (0, 19, 0): String => SuccessResponse;
+        /// Gets mentions of the authenticated user.
+        list_mentions(ListMentions): GET "user/mention", User, (0, 19, 0): GetPersonMentions => GetPersonMentionsResponse;
+        /// Marks a mention as read.
+        mark_mention_as_read(MarkMentionAsRead): POST "user/mention/mark_as_read", User, (0, 19, 0): MarkPersonMentionAsRead => PersonMentionResponse;
+        /// Gets replies to your posts and comments.
+        list_replies(ListReplies): GET "user/replies", User, (0, 19, 0): GetReplies => GetRepliesResponse;
+        /// Bans a person from your instance.
+        ban_from_site(BanFromSite): POST "user/ban", Admin, (0, 19, 0): BanPerson => BanPersonResponse;
+        /// Gets users banned who are banned from your isntance.
+        list_banned_users(ListBannedUsers): GET "user/banned", Admin, (0, 19, 0): () => BannedPersonsResponse;
+        /// Blocks a person.
+        block_person(BlockPerson): POST "user/block", User, (0, 19, 0): BlockPerson => BlockPersonResponse;
+        /// Logs into the instance, giving you a JWT to use to make authorized requests.
+        login(Login): POST "user/login", None, (0, 19, 0): Login => LoginResponse;
+        /// Deletes the active session associated with the JWT.
+        logout(Logout): POST "user/logout", User, (0, 19, 0): () => SuccessResponse;
+        /// Deletes your account.
+        delete_account(DeleteAccount): POST "user/delete_account", User, (0, 19, 0): DeleteAccount => SuccessResponse;
+        /// Sends an email to your account (if you have one) with a one time link to change your password. Use this if you forgot your password.
+        reset_password(ResetPassword): POST "user/password_reset", None, (0, 19, 0): PasswordReset => SuccessResponse;
+        /// Follows through with one time link password reset request.
+        change_password_after_reset(ChangePasswordAfterReset): POST "user/password_change", None, (0, 19, 0): PasswordChangeAfterReset => SuccessResponse;
+        /// Marks all notifications (replies, mentions, private messages) as read.
+        mark_all_notifications_as_read(MarkAllNotificationsAsRead): POST "user/mark_all_as_read", User, (0, 19, 0): () => GetRepliesResponse;
+        /// Saves your account settings.
+        save_user_settings(SaveUserSettings): PUT "user/save_user_settings", User, (0, 19, 0): SaveUserSettings => SuccessResponse;
+        /// Changes your password if you are already logged in.
+        change_password(ChangePassword): PUT "user/change_password", User, (0, 19, 0): ChangePassword => LoginResponse;
+        /// Gets number of reports you can resolve.
+        report_count(ReportCount): GET "user/report_count", User, (0, 19, 0): GetReportCount => GetReportCountResponse;
+        /// Gets the number of unread notifications.
+        unread_count(UnreadCount): GET "user/unread_count", User, (0, 19, 0): () => GetUnreadCountResponse;
+        /// Verifies your email. Used when the instance you are registering an account on requires email verification.
+        verify_email(VerifyEmail): POST "user/verify_email", None, (0, 19, 0): VerifyEmail => SuccessResponse;
+        /// Leave your instance's admin team.
+        leave_admin(LeaveAdmin): POST "user/leave_admin", Admin, (0, 19, 0): () => GetSiteResponse;
+        /// Generates a secret to enable time-based one time passwords for two-factor authentication.
+        ///
+        /// After this, you will need to call /user/totp/update with a vaild token to enable it.
+        generate_totp_secret(GenerateTotpSecret): POST "user/totp/generate", User, (0, 19, 0): () => GenerateTotpSecretResponse;
+        /// Enables/disables two-factor authentivation.
+        ///
+        /// To enable, you must first call /user/totp/generate to generate a token to pass to this.
+        ///
+        /// You can only disable this if it is already enabled. Again, you must pass a valid TOTP.
+        update_totp(UpdateTotp): POST "user/totp/update", User, (0, 19, 0): UpdateTotp => UpdateTotpResponse;
+        /// Lists login tokens for your user's active sessions.
+        list_logins(ListLogins): GET "user/list_logins", User, (0, 19, 0): () => Vec<LoginToken>;
+        /// Returns an error message if your auth token is invalid.
+        validate_auth(ValidateAuth): GET "user/validate_auth", User, (0, 19, 0): () => SuccessResponse;
+        /// Adds a user to your instance's admin team.
+        add_admin(AddAdmin): POST "admin/add", Admin, (0, 19, 0): AddAdmin => AddAdminResponse;
+        /// Gets the number of unread registration applications for the instance you administrate.
+        unread_registration_application_count(UnreadRegistrationApplicationCount): GET "admin/registration_application/count", Admin, (0, 19, 0): () => GetUnreadRegistrationApplicationCountResponse;
+        /// Gets applications to register an account on the instance you administer.
+        list_registration_applications(ListRegistrationApplications): GET "admin/registration_application/list", Admin, (0, 19, 0): ListRegistrationApplications => ListRegistrationApplicationsResponse;
+        /// Approves a pending registration application.
+        approve_registration_application(ApproveRegistrationApplication): PUT "admin/registration_application/approve", Admin, (0, 19, 0): ApproveRegistrationApplication => RegistrationApplicationResponse;
+        /// Purges a user from the database.
+        purge_person(PurgePerson): POST "admin/purge/person", Admin, (0, 19, 0): PurgePerson => SuccessResponse;
+        /// Purges a community from the database.
+        purge_community(PurgeCommunity): POST "admin/purge/community", Admin, (0, 19, 0): PurgeCommunity => SuccessResponse;
+        /// Purges a post from the datbase.
+        purge_post(PurgePost): POST "admin/purge/post", Admin, (0, 19, 0): PurgePost => SuccessResponse;
+        /// Purges a comment from the database.
+        purge_comment(PurgeComment): POST "admin/purge/comment", Admin, (0, 19, 0): PurgeComment => SuccessResponse;
+        /// Creates a custom emoji.
+        create_custom_emoji(CreateCustomEmoji): POST "custom_emoji", Admin, (0, 19, 0): CreateCustomEmoji => CustomEmojiResponse;
+        /// Edits an existing custom emoji.
+        edit_custom_emoji(EditCustomEmoji): PUT "custom_emoji", Admin, (0, 19, 0): EditCustomEmoji => CustomEmojiResponse;
+        /// Deletes an existing custom emoji.
+        delete_custom_emoji(DeleteCustomEmoji): POST "custom_emoji/delete", Admin, (0, 19, 0): DeleteCustomEmoji => CustomEmojiResponse;
+        }
+    };
+}
+
+pub(crate) use with_endpoints;
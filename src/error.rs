@@ -1,27 +1,194 @@
+use crate::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, str::FromStr, sync::Arc};
 use thiserror::Error as ThisError;
 
-#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+/// What kind of failure an [`Error`] is.
+pub enum ErrorKind {
+    /// The instance rejected the request. The message is the error code Lemmy returned, such as `couldnt_find_post`.
+    Api,
+    /// The request could not be sent, or no response was received.
+    Network,
+    /// The response could not be parsed. This often means the instance runs a version of Lemmy this crate does not support.
+    Decode,
+    /// The request was cancelled before it finished, e.g. by a Leptos component being unmounted.
+    Aborted,
+    /// The form would be rejected by the instance, so it was not sent. The message starts with `invalid_form`, and [`Error::validation_errors`] lists the problems.
+    Validation,
+    /// Any other error, including those raised by the client itself, such as `not_logged_in`.
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, ThisError, Serialize, Deserialize)]
 /// An error returned from the API.
-#[error("Lemmy Error: {0}")]
-pub struct Error(String);
+///
+/// Serializes its kind, message, and instance version, so it can be sent to another process, e.g. from a Leptos server function to the browser, and turned back into the same error. The source and [validation errors](Error::validation_errors) of the error are not serialized.
+#[error("Lemmy Error: {message}")]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    instance_version: Option<String>,
+    #[serde(skip)]
+    validation_errors: Vec<ValidationError>,
+    #[source]
+    #[serde(skip)]
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
 
 impl Error {
+    /// Creates an error from a message. Used for errors raised by the client itself, and useful for mock implementations of [`LemmyApi`](crate::LemmyApi).
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::Other, message)
+    }
+
+    /// Creates an error of the given kind from a message.
+    pub fn with_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            instance_version: None,
+            validation_errors: Vec::new(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_validation_errors(
+        message: impl Into<String>,
+        validation_errors: Vec<ValidationError>,
+    ) -> Self {
+        Self {
+            validation_errors,
+            ..Self::with_kind(ErrorKind::Validation, message)
+        }
+    }
+
+    fn with_source(
+        kind: ErrorKind,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            kind,
+            message: source.to_string(),
+            instance_version: None,
+            validation_errors: Vec::new(),
+            source: Some(Arc::new(source)),
+        }
+    }
+
+    /// Builds the error for a response with an unsuccessful status, using the error code in the body if there is one.
+    pub(crate) fn from_response(status: u16, body: &[u8]) -> Self {
+        Self::from_error_body(body)
+            .unwrap_or_else(|| Self::with_kind(ErrorKind::Api, format!("http_status_{status}")))
+    }
+
+    /// Reads the error code from the body of a response, if it holds one.
+    pub(crate) fn from_error_body(body: &[u8]) -> Option<Self> {
+        let ApiErrorBody { error } = serde_json::from_slice(body).ok()?;
+        Some(Self::with_kind(ErrorKind::Api, error))
+    }
+
     /// Get the error message;
     pub fn message(&self) -> &str {
-        self.0.as_str()
+        self.message.as_str()
+    }
+
+    /// What kind of failure this is.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The version of Lemmy the instance reported, for [`ErrorKind::Decode`] errors of clients that learned it. Clients learn it from [`LemmyClient::get_site`](crate::LemmyClient::get_site), even when its response fails to parse, and from [`LemmyClient::get_node_info`](crate::LemmyClient::get_node_info).
+    pub fn instance_version(&self) -> Option<&str> {
+        self.instance_version.as_deref()
+    }
+
+    /// The problems found with a form, for [`ErrorKind::Validation`] errors.
+    pub fn validation_errors(&self) -> &[ValidationError] {
+        &self.validation_errors
+    }
+
+    pub(crate) fn with_instance_version(self, instance_version: Option<String>) -> Self {
+        Self {
+            instance_version,
+            ..self
+        }
+    }
+}
+
+#[derive(Deserialize)]
+/// The body Lemmy responds with when a request fails.
+struct ApiErrorBody {
+    error: String,
+}
+
+/// Errors are equal if their kinds and messages are, regardless of their sources, instance versions, and validation errors.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.message == other.message
+    }
+}
+
+impl Eq for Error {}
+
+/// Parses the [`Display`](std::fmt::Display) output of an error back into the error, as Leptos does with the errors of server functions.
+///
+/// Only the message is part of the output, so the round trip is lossy: the parsed error always has the kind [`ErrorKind::Other`], and is only equal to errors of that kind. Serialize errors instead to keep their kind.
+///
+/// ```
+/// use lemmy_client::{Error, ErrorKind};
+///
+/// let error = Error::new("couldnt_find_post");
+/// assert_eq!(error.to_string().parse::<Error>(), Ok(error));
+///
+/// let error = Error::with_kind(ErrorKind::Api, "rate_limit_error");
+/// let parsed = error.to_string().parse::<Error>().unwrap();
+/// assert_eq!(parsed.message(), error.message());
+/// assert_eq!(parsed.kind(), ErrorKind::Other);
+/// assert_ne!(parsed, error);
+/// ```
+impl FromStr for Error {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s.strip_prefix("Lemmy Error: ").unwrap_or(s)))
     }
 }
 
 #[cfg(not(target_family = "wasm"))]
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
-        Self(e.to_string())
+        let kind = if e.is_decode() {
+            ErrorKind::Decode
+        } else if e.is_status() {
+            ErrorKind::Api
+        } else if e.is_builder() {
+            ErrorKind::Other
+        } else {
+            ErrorKind::Network
+        };
+
+        Self::with_source(kind, e)
     }
 }
 
 #[cfg(target_family = "wasm")]
 impl From<gloo_net::Error> for Error {
     fn from(e: gloo_net::Error) -> Self {
-        Self(e.to_string())
+        let kind = match &e {
+            gloo_net::Error::JsError(js_error) if js_error.name == "AbortError" => {
+                ErrorKind::Aborted
+            }
+            gloo_net::Error::JsError(_) => ErrorKind::Network,
+            gloo_net::Error::SerdeError(_) => ErrorKind::Decode,
+            gloo_net::Error::GlooError(_) => ErrorKind::Other,
+        };
+
+        Self::with_source(kind, e)
     }
 }
@@ -0,0 +1,156 @@
+use crate::{Error, ErrorKind};
+
+/// Declares the message key and English message of each error code. Keys are the error code with an `error.` prefix, so they never change as long as Lemmy keeps the code.
+macro_rules! error_messages {
+    ($($code:ident => $english:literal,)+) => {
+        /// The error codes with their message keys and English messages.
+        static ERROR_MESSAGES: &[(&str, &str, &str)] = &[
+            $((stringify!($code), concat!("error.", stringify!($code)), $english),)+
+        ];
+    };
+}
+
+error_messages! {
+    // Account
+    incorrect_login => "The username, email, or password is incorrect.",
+    not_logged_in => "You need to log in first.",
+    email_not_verified => "Verify your email address before logging in.",
+    registration_application_is_pending => "Your registration application has not been approved yet.",
+    registration_denied => "Your registration application was denied.",
+    missing_totp_token => "Enter the code from your two-factor authentication app.",
+    incorrect_totp_token => "The two-factor authentication code is incorrect.",
+    passwords_do_not_match => "The passwords do not match.",
+    invalid_password => "The password must be between 10 and 60 characters long.",
+    user_already_exists => "That username is already taken.",
+    email_already_exists => "That email address is already in use.",
+    email_required => "An email address is required to sign up on this instance.",
+    invalid_name => "That name is not allowed.",
+    invalid_display_name => "That display name is not allowed.",
+    captcha_incorrect => "The captcha answer is incorrect.",
+    honeypot_failed => "Your sign up was flagged as spam.",
+    password_reset_limit_reached => "Too many password resets were requested. Try again later.",
+    token_not_found => "The link has expired or was already used.",
+    site_ban => "You are banned from this instance.",
+    banned => "You are banned.",
+    deleted => "This account was deleted.",
+    // Permissions
+    not_a_moderator => "Only moderators can do that.",
+    not_an_admin => "Only admins can do that.",
+    not_a_mod_or_admin => "Only moderators and admins can do that.",
+    not_top_admin => "Only the oldest admin can do that.",
+    not_top_mod => "Only the top moderator of the community can do that.",
+    banned_from_community => "You are banned from this community.",
+    only_admins_can_create_communities => "Only admins can create communities on this instance.",
+    only_mods_can_post_in_community => "Only moderators can post in this community.",
+    cannot_leave_admin => "You are the only admin, so you cannot leave.",
+    rate_limit_error => "You are doing that too often. Wait a moment and try again.",
+    // Content
+    couldnt_find_post => "That post could not be found.",
+    couldnt_find_comment => "That comment could not be found.",
+    couldnt_find_community => "That community could not be found.",
+    couldnt_find_person => "That user could not be found.",
+    couldnt_find_private_message => "That message could not be found.",
+    couldnt_find_object => "Nothing could be found at that link.",
+    couldnt_create_post => "The post could not be created.",
+    couldnt_update_post => "The post could not be updated.",
+    couldnt_create_comment => "The comment could not be created.",
+    couldnt_update_comment => "The comment could not be updated.",
+    couldnt_create_private_message => "The message could not be sent.",
+    couldnt_like_post => "Your vote on the post could not be saved.",
+    couldnt_like_comment => "Your vote on the comment could not be saved.",
+    couldnt_save_post => "The post could not be saved.",
+    couldnt_save_comment => "The comment could not be saved.",
+    couldnt_create_report => "The report could not be sent.",
+    couldnt_resolve_report => "The report could not be resolved.",
+    couldnt_update_community => "The community could not be updated.",
+    community_already_exists => "A community with that name already exists.",
+    locked => "This post is locked.",
+    max_comment_depth_reached => "The thread is too deep to reply to.",
+    no_comment_edit_allowed => "You cannot edit this comment.",
+    post_title_too_long => "The title is too long.",
+    invalid_post_title => "The title is not allowed.",
+    invalid_body_field => "The text is too long.",
+    language_not_allowed => "That language is not allowed in this community.",
+    downvotes_are_disabled => "Downvotes are disabled on this instance.",
+    slurs => "The text contains words that are not allowed on this instance.",
+    report_reason_required => "Give a reason for the report.",
+    report_too_long => "The report is too long.",
+    // Blocking and federation
+    cant_block_yourself => "You cannot block yourself.",
+    cant_block_admin => "You cannot block admins.",
+    person_is_blocked => "You blocked this user.",
+    community_is_blocked => "You blocked this community.",
+    instance_is_blocked => "You blocked this instance.",
+    instance_is_private => "This instance is private.",
+    federation_disabled => "Federation is disabled on this instance.",
+    domain_blocked => "That instance is blocked.",
+    // Images
+    pictrs_response_error => "The image could not be uploaded.",
+    pictrs_caching_disabled => "This instance does not store images from other sites.",
+    not_an_image => "That file is not an image.",
+    image_too_large => "That image is too large.",
+    upload_failed => "The image could not be uploaded.",
+    // Client
+    client_shut_down => "The app is shutting down.",
+    not_implemented => "This is not supported yet.",
+    dry_run => "The change was not made, because this is a dry run.",
+}
+
+/// Message key of errors whose code has no message.
+const UNKNOWN_KEY: &str = "error.unknown";
+
+/// Every message key with its English message, e.g. to generate the catalog translators start from.
+pub fn error_message_catalog() -> impl Iterator<Item = (&'static str, &'static str)> {
+    ERROR_MESSAGES
+        .iter()
+        .map(|&(_, key, english)| (key, english))
+        .chain([
+            ("error.network", "Could not connect to the instance."),
+            (
+                "error.server",
+                "The instance had a problem. Try again later.",
+            ),
+            (
+                "error.unsupported",
+                "The instance runs a version of Lemmy this app does not support.",
+            ),
+            ("error.aborted", "The request was cancelled."),
+            (UNKNOWN_KEY, "Something went wrong."),
+        ])
+}
+
+impl Error {
+    /// A stable key for translating the error for users, such as `error.couldnt_create_comment`, instead of showing them the error code. Errors from the network or the server get a key for their kind, such as `error.network`, and other unknown codes get `error.unknown`.
+    ///
+    /// ```
+    /// use lemmy_client::Error;
+    ///
+    /// let error = Error::new("couldnt_create_comment");
+    /// assert_eq!(error.message_key(), "error.couldnt_create_comment");
+    /// assert_eq!(error.english_message(), "The comment could not be created.");
+    /// ```
+    pub fn message_key(&self) -> &'static str {
+        if let Some(&(_, key, _)) = ERROR_MESSAGES
+            .iter()
+            .find(|(code, _, _)| *code == self.message())
+        {
+            return key;
+        }
+
+        match self.kind() {
+            ErrorKind::Network => "error.network",
+            ErrorKind::Decode => "error.unsupported",
+            ErrorKind::Aborted => "error.aborted",
+            ErrorKind::Api if self.message().starts_with("http_status_5") => "error.server",
+            _ => UNKNOWN_KEY,
+        }
+    }
+
+    /// The English message for [`Error::message_key`], to show when no translation is available.
+    pub fn english_message(&self) -> &'static str {
+        let key = self.message_key();
+        error_message_catalog()
+            .find(|(catalog_key, _)| *catalog_key == key)
+            .map_or("Something went wrong.", |(_, english)| english)
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    lemmy_client_trait::{private_trait::LemmyClientInternal as _, LemmyClientInternal},
+    response::LemmyResult,
+    ApiVersion, LemmyClient, LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::{
+    lemmy_db_schema::source::instance::Instance,
+    site::{EditSite, FederatedInstances, InstanceWithFederationState},
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Which list of [`FederatedInstances`] an instance is in.
+pub enum FederationStatus {
+    /// The instance federates with this one.
+    Linked,
+    /// The instance is on the allowlist.
+    Allowed,
+    /// The instance is on the blocklist.
+    Blocked,
+}
+
+#[derive(Debug, Clone)]
+/// An instance from the response of [`LemmyClient::get_federated_instances`], along with the list it was in.
+pub struct FederatedInstance {
+    /// Which list the instance was in.
+    pub status: FederationStatus,
+    /// The instance, and the state of federation to it.
+    pub instance: InstanceWithFederationState,
+}
+
+impl FederatedInstance {
+    /// Flattens the three lists of a response into one.
+    pub fn from_lists(instances: FederatedInstances) -> Vec<Self> {
+        let FederatedInstances {
+            linked,
+            allowed,
+            blocked,
+        } = instances;
+        [
+            (FederationStatus::Linked, linked),
+            (FederationStatus::Allowed, allowed),
+            (FederationStatus::Blocked, blocked),
+        ]
+        .into_iter()
+        .flat_map(|(status, instances)| {
+            instances
+                .into_iter()
+                .map(move |instance| Self { status, instance })
+        })
+        .collect()
+    }
+
+    /// The instance without its federation state.
+    pub fn info(&self) -> &Instance {
+        &self.instance.instance
+    }
+
+    /// The version of the software the instance runs, if it is known and can be parsed.
+    pub fn version(&self) -> Option<ApiVersion> {
+        self.info().version.as_deref().and_then(ApiVersion::parse)
+    }
+
+    /// When the instance was last updated, or when it was first seen if it never was.
+    pub fn last_updated(&self) -> DateTime<Utc> {
+        self.info().updated.unwrap_or(self.info().published)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Conditions for [`filter_instances`]. Instances have to match every condition that is set.
+pub struct InstanceFilter {
+    /// Only instances in this list.
+    pub status: Option<FederationStatus>,
+    /// Only instances running this software, such as `lemmy` or `mbin`. Compared case-insensitively.
+    pub software: Option<String>,
+    /// Only instances running at least this version. Instances whose version is unknown are left out.
+    pub min_version: Option<ApiVersion>,
+    /// Only instances updated at or after this time. See [`FederatedInstance::last_updated`].
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only instances whose domain contains this text.
+    pub domain_contains: Option<String>,
+}
+
+impl InstanceFilter {
+    /// Returns true if `instance` matches every condition of the filter.
+    pub fn matches(&self, instance: &FederatedInstance) -> bool {
+        let info = instance.info();
+        self.status.is_none_or(|status| status == instance.status)
+            && self.software.as_deref().is_none_or(|software| {
+                info.software
+                    .as_deref()
+                    .is_some_and(|actual| actual.eq_ignore_ascii_case(software))
+            })
+            && self.min_version.is_none_or(|min_version| {
+                instance
+                    .version()
+                    .is_some_and(|version| version >= min_version)
+            })
+            && self
+                .updated_since
+                .is_none_or(|since| instance.last_updated() >= since)
+            && self
+                .domain_contains
+                .as_deref()
+                .is_none_or(|text| info.domain.contains(text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// The order of [`sort_instances`].
+pub enum InstanceSort {
+    /// Alphabetically by domain.
+    #[default]
+    Domain,
+    /// Most recently seen first.
+    Newest,
+    /// Most recently updated first. See [`FederatedInstance::last_updated`].
+    RecentlyUpdated,
+    /// Alphabetically by software, then by domain. Instances whose software is unknown come last.
+    Software,
+}
+
+/// Returns the instances matching `filter`.
+pub fn filter_instances<'a>(
+    instances: &'a [FederatedInstance],
+    filter: &'a InstanceFilter,
+) -> impl Iterator<Item = &'a FederatedInstance> {
+    instances
+        .iter()
+        .filter(move |instance| filter.matches(instance))
+}
+
+/// Sorts instances in the given order.
+pub fn sort_instances(instances: &mut [FederatedInstance], sort: InstanceSort) {
+    match sort {
+        InstanceSort::Domain => instances.sort_by(|a, b| a.info().domain.cmp(&b.info().domain)),
+        InstanceSort::Newest => {
+            instances.sort_by_key(|instance| std::cmp::Reverse(instance.info().published))
+        }
+        InstanceSort::RecentlyUpdated => {
+            instances.sort_by_key(|instance| std::cmp::Reverse(instance.last_updated()))
+        }
+        InstanceSort::Software => instances.sort_by(|a, b| {
+            let software = |instance: &FederatedInstance| {
+                instance
+                    .info()
+                    .software
+                    .as_deref()
+                    .map(str::to_ascii_lowercase)
+            };
+            match (software(a), software(b)) {
+                (Some(a_software), Some(b_software)) => a_software.cmp(&b_software),
+                (a_software, b_software) => b_software.is_some().cmp(&a_software.is_some()),
+            }
+            .then_with(|| a.info().domain.cmp(&b.info().domain))
+        }),
+    }
+}
+
+/// Returns page `page` of `instances`, counting from 1, with `limit` instances per page.
+pub fn paginate_instances<T>(instances: &[T], page: usize, limit: usize) -> &[T] {
+    let start = page.saturating_sub(1).saturating_mul(limit);
+    let end = start.saturating_add(limit).min(instances.len());
+    instances.get(start..end).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The differences between two lists of federated instances, from [`InstanceDiff::between`].
+pub struct InstanceDiff {
+    /// Domains of instances that only appear in the newer list.
+    pub added: Vec<String>,
+    /// Domains of instances that only appear in the older list.
+    pub removed: Vec<String>,
+    /// Instances that moved to a different list, with the old and new lists.
+    pub status_changed: Vec<(String, FederationStatus, FederationStatus)>,
+    /// Instances whose software or version changed, with the old and new `software version`.
+    pub software_changed: Vec<(String, String, String)>,
+}
+
+impl InstanceDiff {
+    /// Compares an older and a newer list of instances, such as two responses saved a day apart. Every list of the diff is sorted by domain.
+    pub fn between(old: &[FederatedInstance], new: &[FederatedInstance]) -> Self {
+        fn by_domain(instances: &[FederatedInstance]) -> HashMap<&str, &FederatedInstance> {
+            instances
+                .iter()
+                .map(|instance| (instance.info().domain.as_str(), instance))
+                .collect()
+        }
+        let (old, new) = (by_domain(old), by_domain(new));
+        let software = |instance: &FederatedInstance| {
+            let info = instance.info();
+            format!(
+                "{} {}",
+                info.software.as_deref().unwrap_or_default(),
+                info.version.as_deref().unwrap_or_default()
+            )
+            .trim()
+            .to_owned()
+        };
+
+        let mut diff = Self::default();
+        for (domain, new_instance) in &new {
+            let Some(old_instance) = old.get(domain) else {
+                diff.added.push(domain.to_string());
+                continue;
+            };
+            if old_instance.status != new_instance.status {
+                diff.status_changed.push((
+                    domain.to_string(),
+                    old_instance.status,
+                    new_instance.status,
+                ));
+            }
+            let (old_software, new_software) = (software(old_instance), software(new_instance));
+            if old_software != new_software {
+                diff.software_changed
+                    .push((domain.to_string(), old_software, new_software));
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|domain| !new.contains_key(*domain))
+            .map(|domain| domain.to_string())
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.status_changed.sort_by(|a, b| a.0.cmp(&b.0));
+        diff.software_changed.sort_by(|a, b| a.0.cmp(&b.0));
+        diff
+    }
+
+    /// Returns true if the lists had no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.status_changed.is_empty()
+            && self.software_changed.is_empty()
+    }
+}
+
+impl LemmyClient {
+    /// Gets the instances this instance knows about as one list. See [`FederatedInstance::from_lists`].
+    ///
+    /// HTTP GET /federated_instances
+    pub async fn federated_instance_list(
+        &self,
+        jwt: Option<String>,
+    ) -> LemmyResult<Vec<FederatedInstance>> {
+        Ok(self
+            .client
+            .get_federated_instances(LemmyRequest::from_jwt(jwt), &self.headers)
+            .await?
+            .federated_instances
+            .map(FederatedInstance::from_lists)
+            .unwrap_or_default())
+    }
+}
+
+/// Parses a list of instance domains, one per line.
+///
+/// Blank lines and lines starting with `#` are skipped, and only the first column of CSV files is used, so blocklists exported by Mastodon and shared as plain text both work. Domains are lowercased, and duplicates are removed.
+///
+/// ```
+/// use lemmy_client::parse_domain_list;
+///
+/// let list = "#domain,#severity\nspam.example,suspend\n\nEvil.example\n# a comment\nspam.example";
+/// assert_eq!(parse_domain_list(list), ["spam.example", "evil.example"]);
+/// ```
+pub fn parse_domain_list(list: &str) -> Vec<String> {
+    let mut domains = Vec::new();
+    for line in list.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let domain = line
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if !domain.is_empty() && !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    domains
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// How [`LemmyClient::import_instance_blocklist`] combines the imported domains with the instance's blocklist.
+pub enum BlocklistImportMode {
+    /// Block the imported domains in addition to those already blocked.
+    #[default]
+    Merge,
+    /// Block exactly the imported domains, unblocking the others.
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The outcome of [`LemmyClient::import_instance_blocklist`].
+pub struct InstanceBlocklistReport {
+    /// Domains that were added to the blocklist.
+    pub added: Vec<String>,
+    /// Domains that were removed from the blocklist.
+    pub removed: Vec<String>,
+    /// Imported domains that were already blocked.
+    pub unchanged: Vec<String>,
+    /// True if the blocklist was changed, which is never the case for a dry run.
+    pub applied: bool,
+}
+
+impl LemmyClient {
+    /// Downloads a list of instance domains, such as a shared blocklist, and parses it with [`parse_domain_list`].
+    ///
+    /// The list is usually hosted elsewhere, so the request is sent without the `Authorization` and `Cookie` headers of the client, and outside of the limits and metrics of the instance.
+    pub async fn fetch_domain_list(&self, url: &str) -> LemmyResult<Vec<String>> {
+        Ok(parse_domain_list(
+            &self
+                .client
+                .get_text(url, &self.third_party_headers())
+                .await?,
+        ))
+    }
+
+    /// Updates the federation blocklist of the instance with a list of domains, e.g. from [`parse_domain_list`] or [`LemmyClient::fetch_domain_list`]. Only admins can do this.
+    ///
+    /// Only the blocklist is sent with [`LemmyClient::edit_site`], so other site settings are left untouched. Nothing is sent if the blocklist would not change.
+    ///
+    /// If `dry_run` is true, nothing is changed and the report lists what would have been done.
+    pub async fn import_instance_blocklist<Domain: AsRef<str>>(
+        &self,
+        domains: impl IntoIterator<Item = Domain>,
+        mode: BlocklistImportMode,
+        dry_run: bool,
+        jwt: Option<String>,
+    ) -> LemmyResult<InstanceBlocklistReport> {
+        let current = self
+            .federated_instance_list(jwt.clone())
+            .await?
+            .into_iter()
+            .filter(|instance| instance.status == FederationStatus::Blocked)
+            .map(|instance| instance.instance.instance.domain)
+            .collect::<Vec<_>>();
+
+        let mut imported = Vec::<String>::new();
+        for domain in domains {
+            let domain = domain.as_ref().trim().to_ascii_lowercase();
+            if !domain.is_empty() && !imported.contains(&domain) {
+                imported.push(domain);
+            }
+        }
+
+        let mut report = InstanceBlocklistReport::default();
+        for domain in &imported {
+            if current.contains(domain) {
+                report.unchanged.push(domain.clone());
+            } else {
+                report.added.push(domain.clone());
+            }
+        }
+        let mut blocked = current.clone();
+        blocked.extend(report.added.iter().cloned());
+        if mode == BlocklistImportMode::Replace {
+            report.removed = current
+                .iter()
+                .filter(|domain| !imported.contains(domain))
+                .cloned()
+                .collect();
+            blocked.retain(|domain| !report.removed.contains(domain));
+        }
+
+        if !dry_run && (!report.added.is_empty() || !report.removed.is_empty()) {
+            let form = EditSite {
+                blocked_instances: Some(blocked),
+                ..Default::default()
+            };
+            self.client
+                .edit_site(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                .await?;
+            report.applied = true;
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    response::LemmyResult, utils::sleep, FederatedInstance, FederationStatus, LemmyClient,
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use serde::Deserialize;
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+/// The state of outgoing federation from an instance to another, as returned by [`FederatedInstance::queue_state`].
+pub struct FederationQueueState {
+    /// The id of the last activity that was delivered.
+    pub last_successful_id: Option<i64>,
+    /// When the last activity that was delivered was created.
+    pub last_successful_published_time: Option<DateTime<Utc>>,
+    /// How many times delivering the next activity failed in a row.
+    pub fail_count: i32,
+    /// When delivering the next activity was last retried.
+    pub last_retry: Option<DateTime<Utc>>,
+    /// When delivering the next activity is retried next, if it failed.
+    pub next_retry: Option<DateTime<Utc>>,
+}
+
+impl FederationQueueState {
+    /// How far behind delivery is at `now`, counted from when the last delivered activity was created. [`None`] if nothing was ever delivered.
+    ///
+    /// An instance that sends few activities looks behind even when nothing is waiting, so compare lags against thresholds longer than the quiet periods of the instance.
+    pub fn lag(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let published = self.last_successful_published_time?;
+        Some((now - published).to_std().unwrap_or_default())
+    }
+
+    /// Whether delivering the next activity is failing.
+    pub fn is_failing(&self) -> bool {
+        self.fail_count > 0
+    }
+}
+
+impl FederatedInstance {
+    /// The state of outgoing federation to the instance, if federation to it is or was active.
+    pub fn queue_state(&self) -> Option<FederationQueueState> {
+        // The fields of the state are private in lemmy_api_common, so it is read through its JSON.
+        let state = serde_json::to_value(self.instance.federation_state.as_ref()?).ok()?;
+        serde_json::from_value(state).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An instance that outgoing federation is behind on, as returned by [`lagging_instances`].
+pub struct LaggingInstance {
+    /// The domain of the instance.
+    pub domain: String,
+    /// How far behind delivery is, or [`None`] if nothing was ever delivered.
+    pub lag: Option<Duration>,
+    /// The state of federation to the instance.
+    pub state: FederationQueueState,
+}
+
+/// Returns the instances that outgoing federation is more than `threshold` behind on at `now`, or that nothing was ever delivered to despite trying, most behind first. Blocked instances are skipped.
+///
+/// ```no_run
+/// # use lemmy_client::{lagging_instances, LemmyClient};
+/// # async fn example(client: LemmyClient) -> Result<(), lemmy_client::Error> {
+/// use std::time::Duration;
+///
+/// let instances = client.federated_instance_list(None).await?;
+/// for lagging in lagging_instances(&instances, Duration::from_secs(60 * 60), lemmy_client::now()) {
+///     println!("{} is {:?} behind", lagging.domain, lagging.lag);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn lagging_instances(
+    instances: &[FederatedInstance],
+    threshold: Duration,
+    now: DateTime<Utc>,
+) -> Vec<LaggingInstance> {
+    let mut lagging = instances
+        .iter()
+        .filter(|instance| instance.status != FederationStatus::Blocked)
+        .filter_map(|instance| {
+            let state = instance.queue_state()?;
+            let lag = state.lag(now);
+            let behind = match lag {
+                Some(lag) => lag > threshold,
+                None => state.is_failing(),
+            };
+            behind.then(|| LaggingInstance {
+                domain: instance.info().domain.clone(),
+                lag,
+                state,
+            })
+        })
+        .collect::<Vec<_>>();
+    // Instances nothing was ever delivered to come first.
+    lagging.sort_by(|a, b| {
+        let rank = |instance: &LaggingInstance| instance.lag.unwrap_or(Duration::MAX);
+        rank(b).cmp(&rank(a)).then_with(|| a.domain.cmp(&b.domain))
+    });
+
+    lagging
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [`LemmyClient::watch_federation_lag`].
+pub struct FederationWatchOptions {
+    /// How long to wait between two checks.
+    pub interval: Duration,
+    /// How far behind outgoing federation to an instance has to be to report it.
+    pub threshold: Duration,
+}
+
+impl Default for FederationWatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5 * 60),
+            threshold: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A change streamed by [`LemmyClient::watch_federation_lag`].
+pub enum FederationLagEvent {
+    /// Outgoing federation to an instance fell behind the threshold.
+    Lagging(LaggingInstance),
+    /// Outgoing federation to an instance that was lagging caught up, or the instance is no longer federated with. Holds its domain.
+    Recovered(String),
+}
+
+impl LemmyClient {
+    /// Checks the federation queues of the instance every [`FederationWatchOptions::interval`], streaming an event when an instance starts or stops lagging. See [`lagging_instances`]. A failed check streams its error, and watching continues until the client is [shut down](LemmyClient::shutdown).
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lemmy_client::{FederationLagEvent, LemmyClient};
+    ///
+    /// # async fn example(client: LemmyClient) {
+    /// let mut events = std::pin::pin!(client.watch_federation_lag(Default::default(), None));
+    /// while let Some(Ok(event)) = events.next().await {
+    ///     match event {
+    ///         FederationLagEvent::Lagging(lagging) => println!("{} is behind", lagging.domain),
+    ///         FederationLagEvent::Recovered(domain) => println!("{domain} caught up"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// HTTP GET /federated_instances
+    pub fn watch_federation_lag(
+        &self,
+        options: FederationWatchOptions,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<FederationLagEvent>> + '_ {
+        stream::unfold(
+            (HashSet::<String>::new(), VecDeque::new(), true),
+            move |(mut lagging, mut pending, mut first)| {
+                let (options, jwt) = (options.clone(), jwt.clone());
+                async move {
+                    loop {
+                        if self.is_shut_down() {
+                            return None;
+                        }
+                        if let Some(event) = pending.pop_front() {
+                            return Some((Ok(event), (lagging, pending, first)));
+                        }
+                        if !first {
+                            sleep(options.interval).await;
+                        }
+                        first = false;
+
+                        let instances = match self.federated_instance_list(jwt.clone()).await {
+                            Ok(instances) => instances,
+                            Err(_) if self.is_shut_down() => return None,
+                            Err(e) => return Some((Err(e), (lagging, pending, first))),
+                        };
+                        let now_lagging =
+                            lagging_instances(&instances, options.threshold, crate::now());
+                        let domains = now_lagging
+                            .iter()
+                            .map(|instance| instance.domain.clone())
+                            .collect::<HashSet<_>>();
+                        let mut recovered =
+                            lagging.difference(&domains).cloned().collect::<Vec<_>>();
+                        recovered.sort();
+                        pending.extend(recovered.into_iter().map(FederationLagEvent::Recovered));
+                        pending.extend(
+                            now_lagging
+                                .into_iter()
+                                .filter(|instance| !lagging.contains(&instance.domain))
+                                .map(FederationLagEvent::Lagging),
+                        );
+                        lagging = domains;
+                    }
+                }
+            },
+        )
+    }
+}
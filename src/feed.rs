@@ -0,0 +1,259 @@
+use crate::{
+    languages::{language_allowed, UNDETERMINED_LANGUAGE},
+    lemmy_client_trait::LemmyClientInternal,
+    response::LemmyResult,
+    ContentFilter, LemmyClient, LemmyRequest,
+};
+use futures::{stream, Stream, TryStreamExt};
+use lemmy_api_common::{
+    lemmy_db_schema::newtypes::{LanguageId, PostId},
+    lemmy_db_views::structs::{PaginationCursor, PostView},
+    post::{GetPosts, MarkPostAsRead},
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// How many posts are marked as read in a single request by default.
+const DEFAULT_READ_BATCH_SIZE: usize = 20;
+/// How many post ids a [`Feed`] remembers by default to filter out repeated posts.
+const DEFAULT_DEDUP_WINDOW: usize = 1000;
+
+#[derive(Debug, Clone)]
+/// The most recently seen ids, up to a fixed number, for filtering out repeated items without remembering every item ever seen.
+///
+/// Offset pagination shifts when items are added while paging, so the next page repeats items from the end of the previous one.
+///
+/// ```
+/// use lemmy_client::RecentlySeen;
+///
+/// let mut seen = RecentlySeen::new(2);
+/// assert!(seen.insert(1));
+/// assert!(!seen.insert(1));
+/// seen.insert(2);
+/// seen.insert(3);
+/// // The oldest id was forgotten to make room.
+/// assert!(!seen.contains(&1));
+/// ```
+pub struct RecentlySeen<Id> {
+    capacity: usize,
+    order: VecDeque<Id>,
+    ids: HashSet<Id>,
+}
+
+impl<Id> RecentlySeen<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// Creates an empty set that remembers at most `capacity` ids.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            order: VecDeque::new(),
+            ids: HashSet::new(),
+        }
+    }
+
+    /// Remembers `id`, forgetting the oldest id if the set is full. Returns false if `id` was already seen.
+    pub fn insert(&mut self, id: Id) -> bool {
+        if self.ids.contains(&id) {
+            return false;
+        }
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        self.order.push_back(id.clone());
+        self.ids.insert(id)
+    }
+
+    /// Returns true if `id` is remembered.
+    pub fn contains(&self, id: &Id) -> bool {
+        self.ids.contains(id)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Filters applied by the client to the posts of a [`Feed`].
+pub struct FeedFilters {
+    /// Hides posts whose title or body contains any of these words, ignoring case.
+    pub keywords: Vec<String>,
+    /// Hides posts marked as NSFW, and posts in NSFW communities.
+    pub hide_nsfw: bool,
+    /// Hides posts made by bot accounts.
+    pub hide_bots: bool,
+    /// Hides posts the user has already read.
+    pub hide_read: bool,
+    /// Only shows posts in these languages, and posts whose language is undetermined. Empty shows every language.
+    pub languages: Vec<LanguageId>,
+}
+
+impl FeedFilters {
+    /// Returns true if the post passes all filters.
+    pub fn allows(&self, post_view: &PostView) -> bool {
+        if (self.hide_nsfw && (post_view.post.nsfw || post_view.community.nsfw))
+            || (self.hide_bots && post_view.creator.bot_account)
+            || (self.hide_read && post_view.read)
+            || !(post_view.post.language_id == UNDETERMINED_LANGUAGE
+                || language_allowed(&self.languages, post_view.post.language_id))
+        {
+            return false;
+        }
+
+        let title = post_view.post.name.to_lowercase();
+        let body = post_view
+            .post
+            .body
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        !self.keywords.iter().any(|keyword| {
+            let keyword = keyword.to_lowercase();
+            title.contains(&keyword) || body.contains(&keyword)
+        })
+    }
+}
+
+/// A paginated list of posts, created with [`LemmyClient::feed`].
+pub struct Feed<'client> {
+    client: &'client LemmyClient,
+    form: GetPosts,
+    filters: FeedFilters,
+    jwt: Option<String>,
+    next_page: Option<PaginationCursor>,
+    exhausted: bool,
+    read_batch_size: usize,
+    pending_read: Vec<PostId>,
+    seen: RecentlySeen<PostId>,
+}
+
+impl<'client> Feed<'client> {
+    /// Fetches the next page of posts, with the feed's filters and the client's [content filter](LemmyClient::set_content_filter) applied.
+    ///
+    /// Posts that were already returned by an earlier page are left out, since pages shift when posts are added while paging. Returns [`None`] once there are no more posts. Since filtering happens after fetching, a page can be empty without the feed being exhausted.
+    pub async fn next_page(&mut self) -> LemmyResult<Option<Vec<PostView>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let form = GetPosts {
+            page_cursor: self.next_page.take(),
+            ..self.form.clone()
+        };
+        let response = self
+            .client
+            .client
+            .list_posts(
+                LemmyRequest::with_jwt(form, self.jwt.clone()),
+                &self.client.headers,
+            )
+            .await?;
+
+        self.exhausted = response.posts.is_empty() || response.next_page.is_none();
+        self.next_page = response.next_page;
+
+        Ok(Some(
+            response
+                .posts
+                .into_iter()
+                .filter(|post_view| {
+                    self.seen.insert(post_view.post.id)
+                        && self.filters.allows(post_view)
+                        && self
+                            .client
+                            .client
+                            .hooks
+                            .content_filter
+                            .as_ref()
+                            .is_none_or(|filter| {
+                                filter.allows(post_view, ContentFilter::check_post)
+                            })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Turns the feed into a stream of its posts, fetching pages as needed.
+    pub fn into_stream(self) -> impl Stream<Item = LemmyResult<PostView>> + 'client {
+        stream::try_unfold(self, |mut feed| async move {
+            let posts = feed.next_page().await?;
+            LemmyResult::Ok(posts.map(|posts| (stream::iter(posts.into_iter().map(Ok)), feed)))
+        })
+        .try_flatten()
+    }
+
+    /// Sets how many post ids are remembered to leave out posts that were already returned. Defaults to 1000.
+    pub fn set_dedup_window(&mut self, window: usize) {
+        let mut seen = RecentlySeen::new(window);
+        for id in self.seen.order.drain(..) {
+            seen.insert(id);
+        }
+        self.seen = seen;
+    }
+
+    /// Returns true if every page has been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Queues a post to be marked as read, e.g. when it is scrolled past.
+    ///
+    /// Queued posts are sent together once the batch size is reached. Call [`Feed::flush_read`] to send them earlier.
+    pub async fn mark_read(&mut self, post_id: PostId) -> LemmyResult<()> {
+        if !self.pending_read.contains(&post_id) {
+            self.pending_read.push(post_id);
+        }
+        if self.pending_read.len() >= self.read_batch_size {
+            self.flush_read().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Marks all queued posts as read.
+    pub async fn flush_read(&mut self) -> LemmyResult<()> {
+        if self.pending_read.is_empty() {
+            return Ok(());
+        }
+
+        let form = MarkPostAsRead {
+            post_id: None,
+            post_ids: Some(self.pending_read.clone()),
+            read: true,
+        };
+        self.client
+            .client
+            .mark_post_as_read(
+                LemmyRequest::with_jwt(form, self.jwt.clone()),
+                &self.client.headers,
+            )
+            .await?;
+        self.pending_read.clear();
+
+        Ok(())
+    }
+
+    /// Sets how many posts are queued by [`Feed::mark_read`] before they are sent. Defaults to 20.
+    pub fn set_read_batch_size(&mut self, read_batch_size: usize) {
+        self.read_batch_size = read_batch_size.max(1);
+    }
+}
+
+impl LemmyClient {
+    /// Creates a [`Feed`] of posts matching `form`, which sets the listing type, sort, and other filters of the feed.
+    pub fn feed(&self, form: GetPosts, filters: FeedFilters, jwt: Option<String>) -> Feed<'_> {
+        Feed {
+            client: self,
+            next_page: form.page_cursor.clone(),
+            form,
+            filters,
+            jwt,
+            exhausted: false,
+            read_batch_size: DEFAULT_READ_BATCH_SIZE,
+            pending_read: Vec::new(),
+            seen: RecentlySeen::new(DEFAULT_DEDUP_WINDOW),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Error, ErrorKind};
+use thiserror::Error as ThisError;
+
+/// Declares an error enum for a flow, mapping the error codes its UI cares about to variants. Every other error is kept in `Other`.
+macro_rules! flow_error {
+    (
+        $(#[$attr:meta])*
+        $name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident => $code:literal $(| $alias:literal)*,
+            )+
+        }
+    ) => {
+        #[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+        #[non_exhaustive]
+        $(#[$attr])*
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                #[error("Lemmy Error: {}", $code)]
+                $variant,
+            )+
+            /// Any other error.
+            #[error(transparent)]
+            Other(Error),
+        }
+
+        impl $name {
+            /// The error code Lemmy uses for this error.
+            pub fn code(&self) -> &str {
+                match self {
+                    $(Self::$variant => $code,)+
+                    Self::Other(e) => e.message(),
+                }
+            }
+        }
+
+        impl From<Error> for $name {
+            fn from(e: Error) -> Self {
+                match e.message() {
+                    $($code $(| $alias)* => Self::$variant,)+
+                    _ => Self::Other(e),
+                }
+            }
+        }
+
+        impl From<$name> for Error {
+            fn from(e: $name) -> Self {
+                match e {
+                    $name::Other(e) => e,
+                    e => Error::with_kind(ErrorKind::Api, e.code()),
+                }
+            }
+        }
+    };
+}
+
+flow_error! {
+    /// Why logging in failed, as returned by [`LemmyClient::login_new`](crate::LemmyClient::login_new). Convert any [`Error`] from logging in with `LoginError::from`.
+    ///
+    /// ```
+    /// use lemmy_client::{Error, LoginError};
+    ///
+    /// let error = LoginError::from(Error::new("incorrect_login"));
+    /// assert_eq!(error, LoginError::WrongCredentials);
+    /// ```
+    LoginError {
+        /// The username, email, or password is wrong.
+        WrongCredentials => "incorrect_login" | "couldnt_find_that_username_or_email",
+        /// The account has two-factor authentication enabled, and no code was given.
+        TotpRequired => "missing_totp_token",
+        /// The two-factor authentication code is wrong.
+        WrongTotp => "incorrect_totp_token",
+        /// The account has to verify its email address first.
+        EmailNotVerified => "email_not_verified",
+        /// The registration application of the account has not been approved yet.
+        RegistrationPending => "registration_application_is_pending",
+        /// The registration application of the account was denied.
+        RegistrationDenied => "registration_denied",
+        /// The account is banned from the instance.
+        SiteBan => "site_ban",
+        /// The account was deleted.
+        Deleted => "deleted",
+        /// Too many logins were attempted.
+        RateLimited => "rate_limit_error",
+    }
+}
+
+flow_error! {
+    /// Why changing a password or verifying an email address failed, as returned by the account helpers such as [`LemmyClient::update_password`](crate::LemmyClient::update_password).
+    AccountError {
+        /// The new password is too short or too long.
+        InvalidPassword => "invalid_password",
+        /// The current password is wrong.
+        WrongPassword => "incorrect_login",
+        /// The link from the email expired or was already used.
+        TokenExpired => "token_not_found",
+        /// Too many password resets were requested.
+        RateLimited => "password_reset_limit_reached" | "rate_limit_error",
+        /// The account has no email address, or none matches.
+        NoEmail => "no_email_setup" | "couldnt_find_that_username_or_email",
+    }
+}
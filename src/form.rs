@@ -4,11 +4,32 @@ use lemmy_api_common::{
 use serde::Serialize;
 use std::fmt;
 
-use crate::utils::impl_marker_trait;
+use crate::{newer_api::*, utils::impl_marker_trait, validation::ValidationError};
 
-pub trait LemmyForm: Serialize + Clone + fmt::Debug {}
+/// A type that can be sent as the body of a request.
+///
+/// Implemented for every form in [`lemmy_api_common`]. Implement it for your own types to send them with [`LemmyClient::send_custom`](crate::LemmyClient::send_custom), for example to call endpoints added by plugins, proxies, or newer versions of Lemmy:
+///
+/// ```
+/// use lemmy_client::LemmyForm;
+/// use serde::Serialize;
+///
+/// #[derive(Debug, Clone, Serialize)]
+/// struct GetPluginStats {
+///     community_id: i32,
+/// }
+///
+/// impl LemmyForm for GetPluginStats {}
+/// ```
+pub trait LemmyForm: Serialize + Clone + fmt::Debug {
+    /// Checks the form before it is sent. Only forms that implement [`Validate`](crate::Validate) check anything.
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// A request to send to lemmy. If you don't want to set the JWT for each request, you can set the Authorization header with [`LemmyClient::headers_mut`](lemmy_client::LemmyClient.headers_mut).
 pub struct LemmyRequest<Body>
 where
@@ -35,6 +56,16 @@ impl LemmyRequest<()> {
     }
 }
 
+impl<Form> LemmyRequest<Form>
+where
+    Form: LemmyForm,
+{
+    /// Returns a request with the given body and JWT.
+    pub(crate) fn with_jwt(body: Form, jwt: Option<String>) -> Self {
+        Self { body, jwt }
+    }
+}
+
 impl<Form> From<Form> for LemmyRequest<Form>
 where
     Form: LemmyForm,
@@ -50,12 +81,10 @@ impl_marker_trait!(
         (),
         String,
         // Comments
-        CreateComment,
         CreateCommentLike,
         CreateCommentReport,
         DeleteComment,
         DistinguishComment,
-        EditComment,
         GetComment,
         GetComments,
         ListCommentLikes,
@@ -67,9 +96,7 @@ impl_marker_trait!(
         AddModToCommunity,
         BanFromCommunity,
         BlockCommunity,
-        CreateCommunity,
         DeleteCommunity,
-        EditCommunity,
         FollowCommunity,
         GetCommunity,
         HideCommunity,
@@ -101,11 +128,9 @@ impl_marker_trait!(
         UpdateTotp,
         VerifyEmail,
         // Posts
-        CreatePost,
         CreatePostLike,
         CreatePostReport,
         DeletePost,
-        EditPost,
         FeaturePost,
         GetPost,
         GetPosts,
@@ -118,10 +143,8 @@ impl_marker_trait!(
         ResolvePostReport,
         SavePost,
         // Private Messages
-        CreatePrivateMessage,
         CreatePrivateMessageReport,
         DeletePrivateMessage,
-        EditPrivateMessage,
         GetPrivateMessages,
         ListPrivateMessageReports,
         MarkPrivateMessageAsRead,
@@ -140,6 +163,9 @@ impl_marker_trait!(
         PurgePerson,
         PurgePost,
         ResolveObject,
-        Search
+        Search,
+        // Lemmy 0.19.4
+        GetPostsWithHidden,
+        HidePost,
     ]
 );
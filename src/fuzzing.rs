@@ -0,0 +1,100 @@
+//! [`Arbitrary`] implementations for types whose derived values would be useless, such as domains that are not valid hosts.
+
+use crate::{ClientOptions, EndpointInfo, Error, ErrorKind, RetryPolicy, ENDPOINTS};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::time::Duration;
+
+/// Error codes Lemmy and the client actually return, so that tests also cover the codes code matches on.
+const ERROR_CODES: &[&str] = &[
+    "couldnt_find_post",
+    "incorrect_login",
+    "not_logged_in",
+    "not_a_mod_or_admin",
+    "rate_limit_error",
+    "http_status_404",
+    "http_status_502",
+    "client_shut_down",
+];
+
+/// Generates a DNS label of lowercase letters, digits and inner hyphens.
+fn label(u: &mut Unstructured<'_>) -> Result<String> {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let len = u.int_in_range(1..=12)?;
+    let mut label = (0..len)
+        .map(|_| u.choose(CHARS).map(|&c| char::from(c)))
+        .collect::<Result<String>>()?;
+    if len > 2 && u.ratio(1, 8)? {
+        label.replace_range(1..2, "-");
+    }
+    Ok(label)
+}
+
+/// Generates `domain` as one to three labels and a top level domain, sometimes with a port.
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use lemmy_client::ClientOptions;
+///
+/// let mut u = Unstructured::new(&[7, 3, 250, 12, 99, 4, 180, 31, 2, 65]);
+/// let options = ClientOptions::arbitrary(&mut u).unwrap();
+/// assert!(url::Url::parse(&format!("https://{}", options.domain)).is_ok());
+/// ```
+impl<'a> Arbitrary<'a> for ClientOptions {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut domain = (0..u.int_in_range(1..=3)?)
+            .map(|_| label(u))
+            .collect::<Result<Vec<_>>>()?
+            .join(".");
+        domain.push('.');
+        domain.push_str(u.choose(&["ml", "world", "social", "org", "localhost"])?);
+        if u.ratio(1, 4)? {
+            domain.push_str(&format!(":{}", u.int_in_range(1..=u16::MAX)?));
+        }
+
+        Ok(Self {
+            domain,
+            secure: u.arbitrary()?,
+        })
+    }
+}
+
+/// Generates a known error code most of the time, and any message otherwise.
+impl<'a> Arbitrary<'a> for Error {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let kind = ErrorKind::arbitrary(u)?;
+        let message = if u.ratio(3, 4)? {
+            String::from(*u.choose(ERROR_CODES)?)
+        } else {
+            u.arbitrary()?
+        };
+
+        Ok(Self::with_kind(kind, message))
+    }
+}
+
+/// Generates a policy with at most 10 retries and delays under a minute, retrying writes to endpoints from [`ENDPOINTS`]. The write check is always [`None`].
+impl<'a> Arbitrary<'a> for RetryPolicy {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let base_delay = Duration::from_millis(u.int_in_range(0..=10_000)?);
+        let max_delay = base_delay + Duration::from_millis(u.int_in_range(0..=50_000)?);
+        let retried_writes = (0..u.int_in_range(0..=8)?)
+            .map(|_| Ok(u.choose(ENDPOINTS)?.name))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            max_retries: u.int_in_range(0..=10)?,
+            base_delay,
+            max_delay,
+            retry_writes: u.arbitrary()?,
+            retried_writes,
+            write_check: None,
+        })
+    }
+}
+
+/// Picks one of the [`ENDPOINTS`].
+impl<'a> Arbitrary<'a> for EndpointInfo {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(ENDPOINTS).cloned()
+    }
+}
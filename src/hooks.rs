@@ -0,0 +1,85 @@
+use crate::{
+    audit::AuditSink,
+    compat::ServerAdapter,
+    concurrency::{ConcurrencyLimit, DispatchQueue, Priority},
+    content_filter::ActiveFilter,
+    customize::RequestCustomizer,
+    dry_run::DryRunLog,
+    metrics::RequestMetric,
+    retry::RetryPolicy,
+    shutdown::Lifecycle,
+    throttle::Throttle,
+    transform::ResponseTransform,
+    MetricsRecorder,
+};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+#[derive(Clone, Default)]
+/// State shared by the request backends that customizes how requests are sent.
+pub(crate) struct RequestHooks {
+    pub(crate) metrics: Option<Arc<dyn MetricsRecorder>>,
+    pub(crate) audit: Option<Arc<dyn AuditSink>>,
+    pub(crate) client_limit: Option<ConcurrencyLimit>,
+    pub(crate) host_limit: Option<ConcurrencyLimit>,
+    pub(crate) queue: Option<(DispatchQueue, Priority)>,
+    pub(crate) lifecycle: Arc<Lifecycle>,
+    pub(crate) retry: Option<RetryPolicy>,
+    pub(crate) throttle: Option<Arc<Throttle>>,
+    /// The JWT sent with requests that do not pass one, set by [`LemmyClient::derive`](crate::LemmyClient::derive).
+    pub(crate) jwt: Option<String>,
+    pub(crate) legacy_auth: bool,
+    pub(crate) method_override: bool,
+    pub(crate) adapter: Option<Arc<dyn ServerAdapter>>,
+    pub(crate) content_filter: Option<Arc<ActiveFilter>>,
+    pub(crate) transforms: Vec<Arc<dyn ResponseTransform>>,
+    pub(crate) customize: Option<RequestCustomizer>,
+    pub(crate) dry_run: Option<DryRunLog>,
+    pub(crate) instance_version: Arc<InstanceVersion>,
+}
+
+#[derive(Debug, Default)]
+/// The last version of Lemmy the instance reported, attached to errors that suggest it runs a version this crate does not support.
+pub(crate) struct InstanceVersion(Mutex<Option<String>>);
+
+impl InstanceVersion {
+    pub(crate) fn get(&self) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn set(&self, version: String) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(version);
+    }
+}
+
+impl RequestHooks {
+    pub(crate) fn record_request(&self, metric: &RequestMetric<'_>) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_request(metric);
+        }
+    }
+
+    pub(crate) fn record_retry(&self, endpoint: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_retry(endpoint);
+        }
+    }
+
+    pub(crate) fn record_rate_limit_wait(&self, endpoint: &str, wait: Duration) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rate_limit_wait(endpoint, wait);
+        }
+    }
+
+    /// Waits for the bandwidth limit after `bytes` of a response were downloaded.
+    pub(crate) async fn record_download(&self, bytes: usize) {
+        if let Some(throttle) = &self.throttle {
+            throttle.consume(bytes).await;
+        }
+    }
+}
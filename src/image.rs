@@ -0,0 +1,618 @@
+use crate::{
+    audit::audit, lemmy_client_internal::build_url,
+    lemmy_client_trait::private_trait::LemmyClientInternal,
+    lemmy_client_trait::LemmyClientInternal as _, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use futures::AsyncWrite;
+use http::Method;
+use lemmy_api_common::{
+    community::EditCommunity,
+    lemmy_db_schema::{newtypes::CommunityId, source::post::Post},
+    person::SaveUserSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::HashMap, sync::Arc, time::Duration};
+use url::Url;
+
+/// File extensions of links that point directly at an image.
+const IMAGE_EXTENSIONS: &[&str] = &["avif", "gif", "jpeg", "jpg", "jxl", "png", "webp"];
+
+/// Called while an image is uploaded with the number of bytes sent so far and the total number of bytes to send, e.g. to render a progress bar.
+///
+/// The total includes the multipart encoding of the upload on WASM, so it is slightly larger than the image.
+pub type UploadProgress = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The response pict-rs sends after an image upload.
+pub struct UploadImageResponse {
+    /// `ok` if the upload succeeded, otherwise the reason it failed.
+    pub msg: String,
+    /// The uploaded files.
+    pub files: Option<Vec<ImageFile>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A file stored by pict-rs.
+pub struct ImageFile {
+    /// Name of the file.
+    pub file: String,
+    /// Token used to delete the file.
+    pub delete_token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An image uploaded to the instance.
+pub struct UploadedImage {
+    /// URL the image can be viewed at.
+    pub url: String,
+    /// Name of the file.
+    pub file: String,
+    /// Token used to delete the image with [`LemmyClient::delete_image`].
+    pub delete_token: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An image that replaced another one, as returned by [`LemmyClient::set_avatar`] and similar methods.
+pub struct ReplacedImage {
+    /// The uploaded image, which is now in use.
+    pub image: UploadedImage,
+    /// Whether the previous image was deleted, if one was given. The new image is in use either way.
+    pub previous_deleted: Option<LemmyResult<()>>,
+}
+
+/// Default for [`DownloadOptions::max_size`], 20 MiB.
+pub const DEFAULT_MAX_DOWNLOAD_SIZE: u64 = 20 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Options for [`LemmyClient::download_image`].
+pub struct DownloadOptions {
+    /// Largest number of bytes to download before failing with `image_too_large`.
+    pub max_size: u64,
+    /// Fetch images hosted by other servers through the instance's image proxy, so that they do not learn the user's IP address. The image proxy was added in Lemmy 0.19.4.
+    pub use_image_proxy: bool,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_DOWNLOAD_SIZE,
+            use_image_proxy: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An image written by [`LemmyClient::download_image`].
+pub struct DownloadedImage {
+    /// The `Content-Type` the image was served with.
+    pub content_type: String,
+    /// Number of bytes written.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// What a media URL points to, as reported by [`LemmyClient::probe_media`].
+pub struct MediaInfo {
+    /// The `Content-Type` of the media, without parameters.
+    pub content_type: Option<String>,
+    /// Size of the media in bytes.
+    pub size: Option<u64>,
+    /// Whether the media may be stored in a cache, i.e. it was not served with `Cache-Control: no-store`.
+    pub cacheable: bool,
+    /// How long the media may be cached before it has to be fetched again, from the `max-age` directive of `Cache-Control`.
+    pub max_age: Option<Duration>,
+}
+
+impl MediaInfo {
+    pub(crate) fn from_headers(
+        content_type: Option<&str>,
+        content_length: Option<u64>,
+        cache_control: Option<&str>,
+    ) -> Self {
+        let directives = cache_control
+            .into_iter()
+            .flat_map(|cache_control| cache_control.split(','))
+            .map(str::trim);
+        let mut cacheable = true;
+        let mut max_age = None;
+        for directive in directives {
+            if directive.eq_ignore_ascii_case("no-store") {
+                cacheable = false;
+            } else if let Some((name, seconds)) = directive.split_once('=') {
+                if name.trim().eq_ignore_ascii_case("max-age") {
+                    max_age = seconds.trim().parse().ok().map(Duration::from_secs);
+                }
+            }
+        }
+
+        Self {
+            content_type: content_type
+                .and_then(|content_type| content_type.split(';').next())
+                .map(|content_type| content_type.trim().to_ascii_lowercase())
+                .filter(|content_type| !content_type.is_empty()),
+            size: content_length,
+            cacheable,
+            max_age,
+        }
+    }
+
+    /// Whether the media is an image.
+    pub fn is_image(&self) -> bool {
+        self.has_type("image/")
+    }
+
+    /// Whether the media is a video.
+    pub fn is_video(&self) -> bool {
+        self.has_type("video/")
+    }
+
+    fn has_type(&self, prefix: &str) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with(prefix))
+    }
+}
+
+enum ImageKind {
+    Icon,
+    Banner,
+}
+
+impl LemmyClient {
+    /// Uploads an image to the instance's pict-rs server.
+    ///
+    /// HTTP POST /pictrs/image
+    pub async fn upload_image(
+        &self,
+        image: Vec<u8>,
+        jwt: Option<String>,
+    ) -> LemmyResult<UploadedImage> {
+        self.upload_image_with_progress(image, None, jwt).await
+    }
+
+    /// Uploads an image to the instance's pict-rs server, reporting how much of it has been sent to `progress`. See [`UploadProgress`].
+    ///
+    /// HTTP POST /pictrs/image
+    pub async fn upload_image_with_progress(
+        &self,
+        image: Vec<u8>,
+        progress: Option<UploadProgress>,
+        jwt: Option<String>,
+    ) -> LemmyResult<UploadedImage> {
+        let jwt = self.jwt_or_default(jwt);
+        let result = self
+            .client
+            .upload_image(image, progress, jwt.clone(), &self.headers)
+            .await;
+        audit(
+            &self.client.hooks,
+            &Method::POST,
+            "pictrs/image",
+            &(),
+            jwt.as_deref(),
+            &self.headers,
+            &result,
+        );
+        let response = result?;
+
+        let ImageFile { file, delete_token } = response
+            .files
+            .and_then(|files| files.into_iter().next())
+            .ok_or_else(|| Error::new(response.msg))?;
+
+        Ok(UploadedImage {
+            url: build_url(&format!("pictrs/image/{file}"), self.client_options()),
+            file,
+            delete_token,
+        })
+    }
+
+    /// Deletes an image that was uploaded with [`LemmyClient::upload_image`].
+    ///
+    /// HTTP GET /pictrs/image/delete/{delete_token}/{file}
+    pub async fn delete_image(
+        &self,
+        image: &UploadedImage,
+        jwt: Option<String>,
+    ) -> LemmyResult<()> {
+        let jwt = self.jwt_or_default(jwt);
+        let result = self
+            .client
+            .delete_image(&image.file, &image.delete_token, jwt.clone(), &self.headers)
+            .await;
+        audit(
+            &self.client.hooks,
+            &Method::GET,
+            "pictrs/image/delete",
+            &serde_json::json!({ "file": image.file }),
+            jwt.as_deref(),
+            &self.headers,
+            &result,
+        );
+
+        result
+    }
+
+    /// Downloads an image to `writer`, such as a file opened with an async runtime.
+    ///
+    /// Fails with `not_an_image` if the response is not an image, and with `image_too_large` once more than [`DownloadOptions::max_size`] bytes have been received, in which case part of the image may already have been written. Images on the client's instance are requested with the client's headers and the JWT if given, so this also works for instances that only serve images to logged in users. Other hosts never get the JWT, nor the `Authorization` and `Cookie` headers.
+    ///
+    /// On WASM, the body is read in full before it is written.
+    pub async fn download_image<W>(
+        &self,
+        image_url: &str,
+        writer: &mut W,
+        options: &DownloadOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<DownloadedImage>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let url = match Url::parse(image_url) {
+            Ok(url) if options.use_image_proxy && !self.is_local_pictrs_url(&url) => {
+                self.image_proxy_url(image_url)
+            }
+            Ok(_) => image_url.to_owned(),
+            Err(e) => return Err(Error::new(format!("invalid_url: {e}"))),
+        };
+
+        let (jwt, headers) = match Url::parse(&url) {
+            Ok(parsed) if self.is_instance_url(&parsed) => {
+                (self.jwt_or_default(jwt), Cow::Borrowed(&self.headers))
+            }
+            _ => (None, self.third_party_headers()),
+        };
+        self.client
+            .download_image(&url, options.max_size, writer, jwt, &headers)
+            .await
+    }
+
+    /// Asks the server hosting `media_url` what it is with a `HEAD` request, without downloading it. Useful to decide whether to show media inline, as a thumbnail, or as a link. Hosts other than the client's instance do not get the `Authorization` and `Cookie` headers.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::{LemmyClient, ClientOptions, LemmyResult};
+    /// # async fn example(client: &LemmyClient) -> LemmyResult<()> {
+    /// let media = client.probe_media("https://lemmy.ml/pictrs/image/example.png").await?;
+    /// if media.is_image() && media.size.is_some_and(|size| size < 5 * 1024 * 1024) {
+    ///     // Show the image inline.
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn probe_media(&self, media_url: &str) -> LemmyResult<MediaInfo> {
+        let headers = match Url::parse(media_url) {
+            Ok(url) if self.is_instance_url(&url) => Cow::Borrowed(&self.headers),
+            _ => self.third_party_headers(),
+        };
+        self.client.probe_media(media_url, &headers).await
+    }
+
+    /// Uploads an image and sets it as the logged in user's avatar.
+    ///
+    /// If `previous` is given, that image is deleted once the avatar has been updated, and whether that worked is reported in [`ReplacedImage::previous_deleted`]. If updating the avatar fails, the uploaded image is deleted again.
+    pub async fn set_avatar(
+        &self,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        self.set_profile_image(ImageKind::Icon, image, previous, jwt)
+            .await
+    }
+
+    /// Uploads an image and sets it as the logged in user's banner.
+    ///
+    /// If `previous` is given, that image is deleted once the banner has been updated, and whether that worked is reported in [`ReplacedImage::previous_deleted`]. If updating the banner fails, the uploaded image is deleted again.
+    pub async fn set_banner(
+        &self,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        self.set_profile_image(ImageKind::Banner, image, previous, jwt)
+            .await
+    }
+
+    /// Uploads an image and sets it as the icon of a community the logged in user moderates.
+    ///
+    /// If `previous` is given, that image is deleted once the icon has been updated, and whether that worked is reported in [`ReplacedImage::previous_deleted`]. If updating the icon fails, the uploaded image is deleted again.
+    pub async fn set_community_icon(
+        &self,
+        community_id: CommunityId,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        self.set_community_image(ImageKind::Icon, community_id, image, previous, jwt)
+            .await
+    }
+
+    /// Uploads an image and sets it as the banner of a community the logged in user moderates.
+    ///
+    /// If `previous` is given, that image is deleted once the banner has been updated, and whether that worked is reported in [`ReplacedImage::previous_deleted`]. If updating the banner fails, the uploaded image is deleted again.
+    pub async fn set_community_banner(
+        &self,
+        community_id: CommunityId,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        self.set_community_image(ImageKind::Banner, community_id, image, previous, jwt)
+            .await
+    }
+
+    async fn set_profile_image(
+        &self,
+        kind: ImageKind,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        let uploaded = self.upload_image(image, jwt.clone()).await?;
+
+        let mut form = SaveUserSettings::default();
+        match kind {
+            ImageKind::Icon => form.avatar = Some(uploaded.url.clone()),
+            ImageKind::Banner => form.banner = Some(uploaded.url.clone()),
+        }
+        let edit = self
+            .client
+            .save_user_settings(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+            .await
+            .map(|_| ());
+
+        self.finish_replacement(uploaded, edit, previous, jwt).await
+    }
+
+    async fn set_community_image(
+        &self,
+        kind: ImageKind,
+        community_id: CommunityId,
+        image: Vec<u8>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        let moderates = self
+            .my_user(jwt.clone())
+            .await?
+            .moderates
+            .iter()
+            .any(|moderator_view| moderator_view.community.id == community_id);
+        if !moderates {
+            return Err(Error::new("not_a_mod_or_admin"));
+        }
+
+        let uploaded = self.upload_image(image, jwt.clone()).await?;
+
+        let mut form = EditCommunity {
+            community_id,
+            ..Default::default()
+        };
+        match kind {
+            ImageKind::Icon => form.icon = Some(uploaded.url.clone()),
+            ImageKind::Banner => form.banner = Some(uploaded.url.clone()),
+        }
+        let edit = self
+            .client
+            .edit_community(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+            .await
+            .map(|_| ());
+
+        self.finish_replacement(uploaded, edit, previous, jwt).await
+    }
+
+    /// Finishes replacing an image once the setting pointing at it was edited: deletes `uploaded` again if the edit failed, so it is not left behind, and `previous` if it succeeded.
+    async fn finish_replacement(
+        &self,
+        uploaded: UploadedImage,
+        edit: LemmyResult<()>,
+        previous: Option<&UploadedImage>,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReplacedImage> {
+        if let Err(e) = edit {
+            // The failed edit is what the caller needs to know about, so a failure to clean up is ignored.
+            let _ = self.delete_image(&uploaded, jwt).await;
+            return Err(e);
+        }
+
+        let previous_deleted = match previous {
+            Some(previous) => Some(self.delete_image(previous, jwt).await),
+            None => None,
+        };
+        Ok(ReplacedImage {
+            image: uploaded,
+            previous_deleted,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// An image format pict-rs can convert images to.
+pub enum ImageFormat {
+    /// WebP
+    Webp,
+    /// JPEG
+    Jpg,
+    /// PNG
+    Png,
+    /// AVIF
+    Avif,
+    /// JPEG XL
+    Jxl,
+}
+
+impl ImageFormat {
+    /// The value pict-rs expects in the `format` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Jpg => "jpg",
+            Self::Png => "png",
+            Self::Avif => "avif",
+            Self::Jxl => "jxl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Parameters for a resized or converted variant of an image.
+pub struct ThumbnailOptions {
+    /// Maximum width and height of the image in pixels.
+    pub size: Option<u32>,
+    /// Format to convert the image to.
+    pub format: Option<ImageFormat>,
+}
+
+impl ThumbnailOptions {
+    fn append_to<T: url::form_urlencoded::Target>(
+        &self,
+        query: &mut url::form_urlencoded::Serializer<'_, T>,
+    ) {
+        if let Some(size) = self.size {
+            query.append_pair("thumbnail", &size.to_string());
+        }
+        if let Some(format) = self.format {
+            query.append_pair("format", format.as_str());
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Builds the URL that fetches a remote image through the instance's image proxy.
+    ///
+    /// The image proxy was added in Lemmy 0.19.4.
+    pub fn image_proxy_url(&self, image_url: &str) -> String {
+        self.image_proxy_variant_url(image_url, ThumbnailOptions::default())
+    }
+
+    /// Sets whether images hosted elsewhere are resized and converted through the instance's image proxy. Disabled by default, since the image proxy was added in Lemmy 0.19.4 and this crate targets 0.19.3.
+    ///
+    /// Enable it for instances that run Lemmy 0.19.4 or newer with the image proxy turned on. While it is disabled, [`LemmyClient::thumbnail_url`] returns images hosted elsewhere unchanged, since only pict-rs can resize them.
+    pub fn set_image_proxy(&mut self, enabled: bool) {
+        self.image_proxy = enabled;
+    }
+
+    /// Builds the URL of a resized or converted variant of an image.
+    ///
+    /// Images hosted by the instance's pict-rs server, and images the instance already proxies, get the parameters added directly. Other images are fetched through the instance's image proxy if it was enabled with [`LemmyClient::set_image_proxy`], and are returned unchanged otherwise.
+    ///
+    /// ```
+    /// use lemmy_client::{ClientOptions, ImageFormat, LemmyClient, ThumbnailOptions};
+    ///
+    /// let client = LemmyClient::new(ClientOptions {
+    ///     domain: String::from("lemmy.ml"),
+    ///     secure: true,
+    /// });
+    /// let options = ThumbnailOptions {
+    ///     size: Some(256),
+    ///     format: Some(ImageFormat::Webp),
+    /// };
+    /// assert_eq!(
+    ///     client.thumbnail_url("https://lemmy.ml/pictrs/image/abc.png", options),
+    ///     "https://lemmy.ml/pictrs/image/abc.png?thumbnail=256&format=webp"
+    /// );
+    /// ```
+    pub fn thumbnail_url(&self, image_url: &str, options: ThumbnailOptions) -> String {
+        match Url::parse(image_url) {
+            Ok(mut url) if self.is_local_pictrs_url(&url) || self.is_image_proxy_url(&url) => {
+                // Replaces the parameters of a variant the URL already points at.
+                let pairs = url
+                    .query_pairs()
+                    .filter(|(key, _)| key != "thumbnail" && key != "format")
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect::<Vec<_>>();
+                let mut query = url.query_pairs_mut();
+                query.clear().extend_pairs(pairs);
+                options.append_to(&mut query);
+                drop(query);
+                url.into()
+            }
+            _ if !self.image_proxy => image_url.to_owned(),
+            _ => self.image_proxy_variant_url(image_url, options),
+        }
+    }
+
+    /// Builds the URL of a resized or converted variant of the image of a post: its thumbnail, or its link if that points at an image. Returns [`None`] for posts without an image.
+    pub fn post_thumbnail_url(&self, post: &Post, options: ThumbnailOptions) -> Option<String> {
+        let image_url = post.thumbnail_url.as_ref().or(post
+            .url
+            .as_ref()
+            .filter(|url| self.is_local_pictrs_url(url) || has_image_extension(url)))?;
+
+        Some(self.thumbnail_url(image_url.as_str(), options))
+    }
+
+    /// Builds a `srcset` attribute for an `<img>`, with a variant of the image for each of `sizes` in pixels, so browsers can pick the smallest one that looks sharp.
+    ///
+    /// Returns just the URL of the image if it cannot be resized because the image proxy is disabled.
+    pub fn image_srcset(
+        &self,
+        image_url: &str,
+        sizes: &[u32],
+        format: Option<ImageFormat>,
+    ) -> String {
+        if !self.image_proxy
+            && Url::parse(image_url)
+                .is_ok_and(|url| !self.is_local_pictrs_url(&url) && !self.is_image_proxy_url(&url))
+        {
+            return image_url.to_owned();
+        }
+
+        sizes
+            .iter()
+            .map(|&size| {
+                let options = ThumbnailOptions {
+                    size: Some(size),
+                    format,
+                };
+                format!("{} {size}w", self.thumbnail_url(image_url, options))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The client's headers without those that carry credentials, for requests to hosts other than the instance.
+    pub(crate) fn third_party_headers(&self) -> Cow<'_, HashMap<String, String>> {
+        Cow::Owned(
+            self.headers
+                .iter()
+                .filter(|(header, _)| {
+                    !header.eq_ignore_ascii_case("authorization")
+                        && !header.eq_ignore_ascii_case("cookie")
+                })
+                .map(|(header, value)| (header.clone(), value.clone()))
+                .collect(),
+        )
+    }
+
+    fn is_local_pictrs_url(&self, url: &Url) -> bool {
+        self.is_instance_url(url) && url.path().starts_with("/pictrs/image/")
+    }
+
+    fn is_image_proxy_url(&self, url: &Url) -> bool {
+        self.is_instance_url(url) && url.path() == "/api/v3/image_proxy"
+    }
+
+    fn image_proxy_variant_url(&self, image_url: &str, options: ThumbnailOptions) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query.append_pair("url", image_url);
+        options.append_to(&mut query);
+
+        format!(
+            "{}?{}",
+            build_url("api/v3/image_proxy", self.client_options()),
+            query.finish()
+        )
+    }
+}
+
+/// Returns true if the path of `url` ends with the extension of an image format.
+fn has_image_extension(url: &Url) -> bool {
+    url.path().rsplit_once('.').is_some_and(|(_, extension)| {
+        IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+    })
+}
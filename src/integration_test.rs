@@ -0,0 +1,148 @@
+use crate::{utils::sleep, ClientOptions, Error, LemmyClient, LemmyResult};
+use lemmy_api_common::{person::Login, sensitive::Sensitive};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use testcontainers::{
+    core::{IntoContainerPort, WaitFor},
+    runners::AsyncRunner,
+    ContainerAsync, GenericImage, ImageExt,
+};
+
+/// The Lemmy image the stack runs, matching the version of this crate.
+const LEMMY_IMAGE: (&str, &str) = ("dessalines/lemmy", "0.19.3");
+const POSTGRES_IMAGE: (&str, &str) = ("postgres", "16-alpine");
+const PICTRS_IMAGE: (&str, &str) = ("asonix/pictrs", "0.5");
+/// How long Lemmy is given to run its migrations and start.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Distinguishes the stacks started by one process, since container names have to be unique.
+static STACK_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn start_failed(e: impl std::fmt::Display) -> Error {
+    Error::new(format!("integration_test_failed: {e}"))
+}
+
+/// A throwaway Lemmy instance running in Docker, with postgres and pict-rs, for end-to-end tests. The containers are removed when it is dropped.
+///
+/// Requires a running Docker daemon. Tests using it need an async runtime, such as `#[tokio::test]`.
+///
+/// ```no_run
+/// use lemmy_client::TestInstance;
+///
+/// # async fn example() -> Result<(), lemmy_client::Error> {
+/// let instance = TestInstance::start().await?;
+/// instance.client.validate_jwt(instance.admin_jwt.clone()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TestInstance {
+    /// A client for the instance.
+    pub client: LemmyClient,
+    /// Username of the admin account created during setup.
+    pub admin_username: String,
+    /// Password of the admin account.
+    pub admin_password: String,
+    /// JWT of the admin account.
+    pub admin_jwt: String,
+    _lemmy: ContainerAsync<GenericImage>,
+    _pictrs: ContainerAsync<GenericImage>,
+    _postgres: ContainerAsync<GenericImage>,
+}
+
+impl TestInstance {
+    /// Starts the containers, waits for Lemmy to be ready, and logs in as the admin.
+    pub async fn start() -> LemmyResult<Self> {
+        let id = format!(
+            "lemmy-client-{}-{}",
+            std::process::id(),
+            STACK_COUNT.fetch_add(1, Ordering::SeqCst)
+        );
+        let network = format!("{id}-network");
+        let (postgres_name, pictrs_name) = (format!("{id}-postgres"), format!("{id}-pictrs"));
+        let admin_username = String::from("lemmy");
+        let admin_password = String::from("lemmylemmy");
+
+        let postgres = GenericImage::new(POSTGRES_IMAGE.0, POSTGRES_IMAGE.1)
+            .with_wait_for(WaitFor::message_on_stderr(
+                "database system is ready to accept connections",
+            ))
+            .with_network(&network)
+            .with_container_name(&postgres_name)
+            .with_env_var("POSTGRES_USER", "lemmy")
+            .with_env_var("POSTGRES_PASSWORD", "password")
+            .with_env_var("POSTGRES_DB", "lemmy")
+            .start()
+            .await
+            .map_err(start_failed)?;
+
+        let pictrs = GenericImage::new(PICTRS_IMAGE.0, PICTRS_IMAGE.1)
+            .with_network(&network)
+            .with_container_name(&pictrs_name)
+            .with_env_var("PICTRS__SERVER__API_KEY", "pictrs-api-key")
+            .start()
+            .await
+            .map_err(start_failed)?;
+
+        let config = format!(
+            r#"{{
+  database: {{ uri: "postgresql://lemmy:password@{postgres_name}:5432/lemmy" }}
+  hostname: "localhost"
+  pictrs: {{ url: "http://{pictrs_name}:8080/", api_key: "pictrs-api-key" }}
+  setup: {{
+    admin_username: "{admin_username}"
+    admin_password: "{admin_password}"
+    site_name: "lemmy-client tests"
+  }}
+}}"#
+        );
+        let lemmy = GenericImage::new(LEMMY_IMAGE.0, LEMMY_IMAGE.1)
+            .with_exposed_port(8536.tcp())
+            .with_network(&network)
+            .with_container_name(format!("{id}-lemmy"))
+            .with_env_var("LEMMY_CONFIG_LOCATION", "/config/config.hjson")
+            .with_copy_to("/config/config.hjson", config.into_bytes())
+            .start()
+            .await
+            .map_err(start_failed)?;
+
+        let host = lemmy.get_host().await.map_err(start_failed)?;
+        let port = lemmy.get_host_port_ipv4(8536).await.map_err(start_failed)?;
+        let client = LemmyClient::new(ClientOptions {
+            domain: format!("{host}:{port}"),
+            secure: false,
+        });
+
+        let mut waited = Duration::ZERO;
+        while client.get_site().await.is_err() {
+            if waited >= STARTUP_TIMEOUT {
+                return Err(start_failed("lemmy did not start in time"));
+            }
+            sleep(Duration::from_secs(1)).await;
+            waited += Duration::from_secs(1);
+        }
+
+        let login = Login {
+            username_or_email: Sensitive::new(admin_username.clone()),
+            password: Sensitive::new(admin_password.clone()),
+            totp_2fa_token: None,
+        };
+        let admin_jwt = client
+            .login(login)
+            .await?
+            .jwt
+            .ok_or_else(|| start_failed("login returned no jwt"))?
+            .into_inner();
+
+        Ok(Self {
+            client,
+            admin_username,
+            admin_password,
+            admin_jwt,
+            _lemmy: lemmy,
+            _pictrs: pictrs,
+            _postgres: postgres,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    community::GetCommunity,
+    lemmy_db_schema::{
+        newtypes::{CommunityId, LanguageId},
+        source::language::Language,
+    },
+    site::GetSiteResponse,
+};
+
+/// The language Lemmy assigns to content whose language is not known.
+pub const UNDETERMINED_LANGUAGE: LanguageId = LanguageId(0);
+
+const ACCEPT_LANGUAGE: &str = "Accept-Language";
+
+/// The interface language the logged in user picked in their settings, as a language tag such as `pt-BR`. Returns [`None`] if nobody is logged in, or the user left it at `browser`, which uses the language of their browser.
+pub fn interface_language(site: &GetSiteResponse) -> Option<String> {
+    let language = &site
+        .my_user
+        .as_ref()?
+        .local_user_view
+        .local_user
+        .interface_language;
+    // Lemmy stores tags such as `pt_BR`, while headers use `pt-BR`.
+    (!language.is_empty() && language != "browser").then(|| language.replace('_', "-"))
+}
+
+/// Finds the language with the given code, such as `en`, ignoring case.
+pub fn language_by_code<'site>(
+    site: &'site GetSiteResponse,
+    code: &str,
+) -> Option<&'site Language> {
+    site.all_languages
+        .iter()
+        .find(|language| language.code.eq_ignore_ascii_case(code))
+}
+
+/// Finds the language with the given ID.
+pub fn language_by_id(site: &GetSiteResponse, id: LanguageId) -> Option<&Language> {
+    site.all_languages.iter().find(|language| language.id == id)
+}
+
+/// Maps language codes to IDs, skipping codes the instance does not know.
+pub fn language_ids(site: &GetSiteResponse, codes: &[&str]) -> Vec<LanguageId> {
+    codes
+        .iter()
+        .filter_map(|code| language_by_code(site, code))
+        .map(|language| language.id)
+        .collect()
+}
+
+/// Returns true if content in `language` is allowed where `allowed` are the discussion languages. Lemmy treats an empty list as allowing every language.
+pub fn language_allowed(allowed: &[LanguageId], language: LanguageId) -> bool {
+    allowed.is_empty() || allowed.contains(&language)
+}
+
+/// Picks a `language_id` for a post or comment that both the site and the community accept.
+///
+/// The first of `preferred` that is allowed is used. When there are no preferences, the logged in user's discussion languages from `site` are tried instead. Returns [`None`] if nothing matches, in which case the form's `language_id` should be left empty so that the instance picks one.
+pub fn pick_language_id(
+    site: &GetSiteResponse,
+    community_languages: &[LanguageId],
+    preferred: &[LanguageId],
+) -> Option<LanguageId> {
+    let user_languages = site
+        .my_user
+        .as_ref()
+        .map(|my_user| my_user.discussion_languages.as_slice())
+        .unwrap_or_default();
+    let preferred = if preferred.is_empty() {
+        user_languages
+    } else {
+        preferred
+    };
+
+    preferred.iter().copied().find(|language| {
+        *language != UNDETERMINED_LANGUAGE
+            && language_allowed(&site.discussion_languages, *language)
+            && language_allowed(community_languages, *language)
+    })
+}
+
+impl LemmyClient {
+    /// Sets the `Accept-Language` header sent with each request, such as `de` or `pt-BR, pt;q=0.8`, so that the instance picks languages consistently for this client. [`None`] stops sending it.
+    pub fn set_locale(&mut self, locale: Option<&str>) {
+        self.headers
+            .retain(|name, _| !name.eq_ignore_ascii_case(ACCEPT_LANGUAGE));
+        if let Some(locale) = locale {
+            self.headers
+                .insert(ACCEPT_LANGUAGE.to_owned(), locale.to_owned());
+        }
+    }
+
+    /// Sets the `Accept-Language` header to the logged in user's interface language, and returns it. The header is left unchanged if the user did not pick one. See [`interface_language`].
+    ///
+    /// HTTP GET /site
+    pub async fn use_interface_language(
+        &mut self,
+        jwt: Option<String>,
+    ) -> LemmyResult<Option<String>> {
+        let site = self
+            .client
+            .get_site(LemmyRequest::from_jwt(jwt), &self.headers)
+            .await?;
+        let language = interface_language(&site);
+        if let Some(language) = &language {
+            self.set_locale(Some(language));
+        }
+
+        Ok(language)
+    }
+
+    /// Picks a `language_id` for posting in a community from language codes such as `en`, falling back to the logged in user's discussion languages. See [`pick_language_id`].
+    pub async fn post_language_id(
+        &self,
+        community_id: CommunityId,
+        preferred_codes: &[&str],
+        jwt: Option<String>,
+    ) -> LemmyResult<Option<LanguageId>> {
+        let site = self
+            .client
+            .get_site(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+            .await?;
+        let form = GetCommunity {
+            id: Some(community_id),
+            name: None,
+        };
+        let community = self
+            .client
+            .get_community(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await?;
+
+        Ok(pick_language_id(
+            &site,
+            &community.discussion_languages,
+            &language_ids(&site, preferred_codes),
+        ))
+    }
+}
@@ -1,5 +1,19 @@
-use crate::utils::ClientOptions;
-use std::collections::HashMap;
+use crate::{
+    form::{LemmyForm, LemmyRequest},
+    hooks::RequestHooks,
+    metrics::RequestMetric,
+    registry::endpoint_name,
+    response::LemmyResult,
+    retry::is_transient,
+    transform::apply_transforms,
+    utils::{sleep, ClientOptions},
+    Error, ErrorKind,
+};
+use futures::{AsyncWrite, AsyncWriteExt};
+use http::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, future::Future};
+use web_time::Instant;
 
 trait WithHeaders {
     fn with_headers(self, headers: &HashMap<String, String>) -> Self;
@@ -9,41 +23,288 @@ trait MaybeWithJwt {
     fn maybe_with_jwt(self, jwt: Option<String>) -> Self;
 }
 
-fn build_route(route: &str, ClientOptions { domain, secure }: &ClientOptions) -> String {
-    format!(
-        "http{}://{domain}/api/v3/{route}",
-        if *secure { "s" } else { "" }
-    )
+/// The header that tells the server which method a tunneled request was meant to use.
+const METHOD_OVERRIDE_HEADER: &str = "X-HTTP-Method-Override";
+
+pub(crate) fn build_url(path: &str, ClientOptions { domain, secure }: &ClientOptions) -> String {
+    format!("http{}://{domain}/{path}", if *secure { "s" } else { "" })
+}
+
+/// The path endpoints are under, unless a server adapter says otherwise.
+const API_PATH: &str = "api/v3/";
+
+/// Builds the URL of an endpoint from the URL of the instance, as built once by [`build_url`] when the client is created.
+fn build_route(base_url: &str, route: &str, hooks: &RequestHooks) -> String {
+    match &hooks.adapter {
+        Some(adapter) => base_url.to_owned() + &adapter.api_path(route),
+        None => {
+            let mut url = String::with_capacity(base_url.len() + API_PATH.len() + route.len());
+            url.push_str(base_url);
+            url.push_str(API_PATH);
+            url.push_str(route);
+            url
+        }
+    }
+}
+
+#[derive(Deserialize)]
+/// The version of Lemmy in the response of `GET /site`, read even if the rest of the response does not parse.
+struct SiteVersion {
+    version: String,
+}
+
+/// Parses the body of a successful response, letting the server adapter of the hooks fix it up and the transforms rewrite it first.
+fn parse_response<Response: DeserializeOwned>(
+    hooks: &RequestHooks,
+    path: &str,
+    body: &[u8],
+) -> LemmyResult<Response> {
+    if path == "site" {
+        if let Ok(SiteVersion { version }) = serde_json::from_slice(body) {
+            hooks.instance_version.set(version);
+        }
+    }
+    // Some proxies answer errors with a successful status, so the body is only read as an error once it fails to parse.
+    let decode_error = |e: serde_json::Error| {
+        Error::from_error_body(body).unwrap_or_else(|| {
+            Error::with_kind(ErrorKind::Decode, e.to_string())
+                .with_instance_version(hooks.instance_version.get())
+        })
+    };
+    if hooks.adapter.is_none() && hooks.transforms.is_empty() {
+        return serde_json::from_slice(body).map_err(decode_error);
+    }
+
+    let mut response = serde_json::from_slice(body).map_err(decode_error)?;
+    if let Some(adapter) = &hooks.adapter {
+        adapter.patch_response(path, &mut response);
+    }
+    apply_transforms(&hooks.transforms, path, &mut response);
+    serde_json::from_value(response).map_err(decode_error)
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+/// The body of a request to an endpoint.
+enum FormBody<Form> {
+    Form(Form),
+    /// A form with the JWT in its `auth` field, the way Lemmy 0.18 expects it.
+    LegacyAuth(serde_json::Value),
+}
+
+impl<Form: LemmyForm> FormBody<Form> {
+    /// Splits a request into its body and the JWT to send in the `Authorization` header. Requests without a JWT use the one of the hooks.
+    fn new(
+        LemmyRequest { body, jwt }: LemmyRequest<Form>,
+        hooks: &RequestHooks,
+    ) -> LemmyResult<(Self, Option<String>)> {
+        let jwt = jwt.or_else(|| hooks.jwt.clone());
+        let (true, Some(jwt)) = (hooks.legacy_auth, &jwt) else {
+            return Ok((Self::Form(body), jwt));
+        };
+
+        let mut form = match serde_json::to_value(body).map_err(|e| Error::new(e.to_string()))? {
+            serde_json::Value::Object(form) => form,
+            _ => serde_json::Map::new(),
+        };
+        form.insert("auth".to_owned(), jwt.as_str().into());
+        Ok((Self::LegacyAuth(form.into()), None))
+    }
+}
+
+/// Checks the headers of an image download before its body is read, returning the content type.
+fn check_image_headers(
+    content_type: Option<&str>,
+    content_length: Option<u64>,
+    max_size: u64,
+) -> LemmyResult<String> {
+    let content_type = content_type
+        .filter(|content_type| content_type.starts_with("image/"))
+        .ok_or_else(|| Error::new("not_an_image"))?;
+    if content_length.is_some_and(|length| length > max_size) {
+        return Err(Error::new("image_too_large"));
+    }
+
+    Ok(content_type.to_owned())
+}
+
+/// Writes a chunk of an image download, failing once more than `max_size` bytes have been downloaded. Waits for the bandwidth limit of the hooks first.
+async fn write_image_chunk<W: AsyncWrite + Unpin>(
+    hooks: &RequestHooks,
+    writer: &mut W,
+    chunk: &[u8],
+    size: &mut u64,
+    max_size: u64,
+) -> LemmyResult<()> {
+    *size += chunk.len() as u64;
+    if *size > max_size {
+        return Err(Error::new("image_too_large"));
+    }
+    hooks.record_download(chunk.len()).await;
+
+    writer
+        .write_all(chunk)
+        .await
+        .map_err(|e| Error::new(e.to_string()))
+}
+
+/// Sends a request with `send`, which returns the HTTP status if a response was received.
+///
+/// Fails if the client was shut down. Otherwise waits for the dispatch queue, concurrency limits, and bandwidth limit of the hooks first, reporting the time spent waiting as a rate limit wait, and reports the request to them once it finishes.
+async fn dispatch<T>(
+    hooks: &RequestHooks,
+    method: &Method,
+    path: &str,
+    send: impl Future<Output = (Option<u16>, LemmyResult<T>)>,
+) -> LemmyResult<T> {
+    let _in_flight = hooks.lifecycle.start_request()?;
+    let endpoint = endpoint_name(method, path);
+    let waiting = Instant::now();
+    let _queue_permit = match &hooks.queue {
+        Some((queue, priority)) => Some(queue.acquire(*priority).await),
+        None => None,
+    };
+    let _host_permit = match &hooks.host_limit {
+        Some(limit) => Some(limit.acquire().await),
+        None => None,
+    };
+    let _client_permit = match &hooks.client_limit {
+        Some(limit) => Some(limit.acquire().await),
+        None => None,
+    };
+    if let Some(throttle) = &hooks.throttle {
+        throttle.pace().await;
+    }
+    let waited = waiting.elapsed();
+    if !waited.is_zero() {
+        hooks.record_rate_limit_wait(endpoint, waited);
+    }
+
+    let started = Instant::now();
+    let (status, result) = send.await;
+    hooks.record_request(&RequestMetric {
+        endpoint,
+        method,
+        status,
+        success: result.is_ok(),
+        latency: started.elapsed(),
+    });
+
+    result
+}
+
+/// Sends a request to a site other than the instance, such as a webhook, with `send`.
+///
+/// Fails if the client was shut down, but skips the dispatch queue, limits, and metrics of the hooks, which are there for the instance.
+async fn dispatch_external<T>(
+    hooks: &RequestHooks,
+    send: impl Future<Output = (Option<u16>, LemmyResult<T>)>,
+) -> LemmyResult<T> {
+    let _in_flight = hooks.lifecycle.start_request()?;
+    send.await.1
+}
+
+/// Sends the requests made by `send` with [`dispatch`] until one succeeds or the retry policy of the hooks gives up. See [`RetryPolicy`](crate::RetryPolicy).
+async fn dispatch_with_retries<T, Fut>(
+    hooks: &RequestHooks,
+    method: &Method,
+    path: &str,
+    mut send: impl FnMut() -> Fut,
+) -> LemmyResult<T>
+where
+    Fut: Future<Output = (Option<u16>, LemmyResult<T>)>,
+{
+    let mut attempt = 0;
+    loop {
+        let result = dispatch(hooks, method, path, send()).await;
+        let (Err(error), Some(policy)) = (&result, &hooks.retry) else {
+            return result;
+        };
+        let endpoint = endpoint_name(method, path);
+        if attempt >= policy.max_retries || !is_transient(error) || !policy.allows(method, endpoint)
+        {
+            return result;
+        }
+        if let (true, Some(check)) = (method != Method::GET, &policy.write_check) {
+            if !matches!(check.should_resend(endpoint, error).await, Ok(true)) {
+                return result;
+            }
+        }
+
+        hooks.record_retry(endpoint);
+        let delay = policy.delay(attempt);
+        if error.kind() == ErrorKind::Api && error.message() == "rate_limit_error" {
+            hooks.record_rate_limit_wait(endpoint, delay);
+        }
+        sleep(delay).await;
+        attempt += 1;
+    }
 }
 
 #[cfg(target_family = "wasm")]
 mod goober {
-    use super::{build_route, MaybeWithJwt, WithHeaders};
+    use super::{
+        build_route, build_url, check_image_headers, dispatch, dispatch_external,
+        dispatch_with_retries, parse_response, write_image_chunk, FormBody, MaybeWithJwt,
+        WithHeaders, METHOD_OVERRIDE_HEADER,
+    };
     use crate::{
+        audit::audit,
+        cache::CachePolicy,
+        dry_run::hold_back,
         form::LemmyForm,
+        hooks::RequestHooks,
+        image::{DownloadedImage, MediaInfo, UploadImageResponse, UploadProgress},
         lemmy_client_trait::{private_trait, LemmyClientInternal},
         response::{LemmyResponse, LemmyResult},
-        ClientOptions, LemmyRequest,
+        ClientOptions, Error, ErrorKind, LemmyRequest,
     };
+    use futures::{channel::oneshot, AsyncWrite, AsyncWriteExt};
     use gloo_net::http::{Request, RequestBuilder};
     use http::Method;
+    use serde::de::DeserializeOwned;
     use std::collections::HashMap;
-    use web_sys::wasm_bindgen::UnwrapThrowExt;
+    use web_sys::{
+        wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt},
+        Blob, FormData, ProgressEvent, XmlHttpRequest,
+    };
 
-    pub struct Fetch(pub ClientOptions);
+    #[derive(Clone)]
+    pub struct Fetch {
+        options: ClientOptions,
+        base_url: String,
+        pub(crate) hooks: RequestHooks,
+    }
 
     impl Fetch {
         pub fn new(options: ClientOptions) -> Self {
-            Self(options)
+            Self {
+                base_url: build_url("", &options),
+                options,
+                hooks: RequestHooks::default(),
+            }
+        }
+
+        /// A copy that sends requests to the instance in `options`, with the same hooks.
+        pub fn with_options(&self, options: ClientOptions) -> Self {
+            Self {
+                base_url: build_url("", &options),
+                options,
+                hooks: self.hooks.clone(),
+            }
         }
 
         fn build_fetch_query<T: serde::Serialize>(&self, path: &str, form: &T) -> String {
             let form_str = serde_urlencoded::to_string(form).unwrap_or_else(|_| path.to_string());
-            format!("{}?{}", build_route(path, &self.0), form_str)
+            format!(
+                "{}?{}",
+                build_route(&self.base_url, path, &self.hooks),
+                form_str
+            )
         }
 
         pub fn client_options(&self) -> &ClientOptions {
-            &self.0
+            &self.options
         }
     }
 
@@ -68,60 +329,428 @@ mod goober {
         }
     }
 
+    /// Sends an image upload with `XMLHttpRequest`, since unlike fetch it can report how much of the body has been sent.
+    async fn upload_with_progress(
+        url: &str,
+        form_data: FormData,
+        progress: UploadProgress,
+        jwt: Option<String>,
+        headers: &HashMap<String, String>,
+    ) -> (Option<u16>, LemmyResult<UploadImageResponse>) {
+        let xhr = XmlHttpRequest::new().expect_throw("Could not create XMLHttpRequest");
+        xhr.open("POST", url)
+            .expect_throw("Could not open XMLHttpRequest");
+        for (header, value) in headers {
+            xhr.set_request_header(header, value)
+                .expect_throw("Could not set request header");
+        }
+        if let Some(jwt) = jwt {
+            xhr.set_request_header(
+                http::header::AUTHORIZATION.as_str(),
+                &format!("Bearer {jwt}"),
+            )
+            .expect_throw("Could not set request header");
+        }
+
+        let on_progress = Closure::<dyn FnMut(ProgressEvent)>::new(move |event: ProgressEvent| {
+            if event.length_computable() {
+                progress(event.loaded() as u64, event.total() as u64);
+            }
+        });
+        xhr.upload()
+            .expect_throw("Could not get upload of XMLHttpRequest")
+            .set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+
+        let (finished, on_finished) = oneshot::channel();
+        let on_load_end: Closure<dyn FnMut()> = Closure::once(move || {
+            let _ = finished.send(());
+        });
+        xhr.set_onloadend(Some(on_load_end.as_ref().unchecked_ref()));
+
+        if let Err(e) = xhr.send_with_opt_form_data(Some(&form_data)) {
+            return (
+                None,
+                Err(Error::with_kind(ErrorKind::Network, format!("{e:?}"))),
+            );
+        }
+        let _ = on_finished.await;
+
+        // A status of 0 means no response was received.
+        match xhr.status() {
+            Ok(status) if status != 0 => {
+                let body = xhr.response_text().ok().flatten().unwrap_or_default();
+                (
+                    Some(status),
+                    if (200..300).contains(&status) {
+                        serde_json::from_str(&body)
+                            .map_err(|e| Error::with_kind(ErrorKind::Decode, e.to_string()))
+                    } else {
+                        Err(Error::from_response(status, body.as_bytes()))
+                    },
+                )
+            }
+            _ => (
+                None,
+                Err(Error::with_kind(ErrorKind::Network, "upload_failed")),
+            ),
+        }
+    }
+
     impl private_trait::LemmyClientInternal for Fetch {
-        async fn make_request<Response, Form>(
+        async fn make_request_with_cache_policy<Response, Form>(
             &self,
             method: http::Method,
             path: &str,
             request: LemmyRequest<Form>,
             headers: &HashMap<String, String>,
-        ) -> LemmyResult<Response>
+        ) -> LemmyResult<(Response, CachePolicy)>
         where
             Response: LemmyResponse,
             Form: LemmyForm,
         {
-            let route = &build_route(path, &self.0);
-            let LemmyRequest { body, jwt } = request;
-
-            #[allow(unused_mut)]
-            let mut req = match method {
-                Method::GET => Request::get(&self.build_fetch_query(path, &body)),
-                Method::POST => Request::post(route),
-                Method::PUT => Request::put(route),
-                method => unreachable!(
-                    "This crate only uses GET, POST, and PUT HTTP methods. Got {method:?}"
-                ),
+            let route = &build_route(&self.base_url, path, &self.hooks);
+            let (body, jwt) = FormBody::new(request, &self.hooks)?;
+            hold_back(&self.hooks, &method, path, || {
+                serde_json::to_value(&body).unwrap_or_default()
+            })?;
+
+            let result = dispatch_with_retries(&self.hooks, &method, path, || {
+                let mut req = match method {
+                    Method::GET => Request::get(&self.build_fetch_query(path, &body)),
+                    Method::POST => Request::post(route),
+                    Method::PUT if self.hooks.method_override => {
+                        Request::post(route).header(METHOD_OVERRIDE_HEADER, Method::PUT.as_str())
+                    }
+                    Method::PUT => Request::put(route),
+                    ref method => unreachable!(
+                        "This crate only uses GET, POST, and PUT HTTP methods. Got {method:?}"
+                    ),
+                }
+                .with_headers(headers)
+                .maybe_with_jwt(jwt.clone());
+
+                // Outside a reactive owner, such as in a Web Worker, there is nothing to cancel the request on.
+                #[cfg(all(feature = "leptos", target_family = "wasm"))]
+                if leptos::Owner::current().is_some() {
+                    use web_sys::AbortController;
+                    let abort_controller = AbortController::new().ok();
+                    let abort_signal = abort_controller.as_ref().map(AbortController::signal);
+                    leptos::on_cleanup(move || {
+                        if let Some(abort_controller) = abort_controller {
+                            abort_controller.abort()
+                        }
+                    });
+                    req = req.abort_signal(abort_signal.as_ref());
+                }
+                if let Some(customize) = &self.hooks.customize {
+                    req = customize(req);
+                }
+
+                let req = match method {
+                    Method::GET => req.build().expect_throw("Could not parse query params"),
+                    Method::POST | Method::PUT => {
+                        req.json(&body).expect_throw("Could not parse JSON body")
+                    }
+                    ref method => unreachable!(
+                        "This crate only uses GET, POST, and PUT HTTP methods. Got {method:?}"
+                    ),
+                };
+
+                async move {
+                    match req.send().await {
+                        Ok(response) if response.ok() => {
+                            let header = |name| response.headers().get(name);
+                            let policy = CachePolicy::from_headers(
+                                header("cache-control").as_deref(),
+                                header("expires").as_deref(),
+                                header("date").as_deref(),
+                                header("age").as_deref(),
+                            );
+                            (
+                                Some(response.status()),
+                                match response.binary().await {
+                                    Ok(body) => {
+                                        self.hooks.record_download(body.len()).await;
+                                        parse_response(&self.hooks, path, &body)
+                                            .map(|response| (response, policy))
+                                    }
+                                    Err(e) => Err(e.into()),
+                                },
+                            )
+                        }
+                        Ok(response) => {
+                            let status = response.status();
+                            let body = response.binary().await.unwrap_or_default();
+                            (Some(status), Err(Error::from_response(status, &body)))
+                        }
+                        Err(e) => (None, Err(e.into())),
+                    }
+                }
+            })
+            .await;
+            audit(
+                &self.hooks,
+                &method,
+                path,
+                &body,
+                jwt.as_deref(),
+                headers,
+                &result,
+            );
+
+            result
+        }
+
+        async fn upload_image(
+            &self,
+            image: Vec<u8>,
+            progress: Option<UploadProgress>,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<UploadImageResponse> {
+            hold_back(&self.hooks, &Method::POST, "pictrs/image", || {
+                serde_json::Value::Null
+            })?;
+            let bytes = js_sys::Uint8Array::from(image.as_slice());
+            let blob = Blob::new_with_u8_array_sequence(&js_sys::Array::of1(&bytes))
+                .expect_throw("Could not create image blob");
+            let form_data = FormData::new().expect_throw("Could not create form data");
+            form_data
+                .append_with_blob_and_filename("images[]", &blob, "image")
+                .expect_throw("Could not add image to form data");
+
+            let url = build_url("pictrs/image", &self.options);
+            if let Some(progress) = progress {
+                return dispatch(
+                    &self.hooks,
+                    &Method::POST,
+                    "pictrs/image",
+                    upload_with_progress(&url, form_data, progress, jwt, headers),
+                )
+                .await;
             }
+
+            let req = Request::post(&url)
+                .with_headers(headers)
+                .maybe_with_jwt(jwt)
+                .body(form_data)
+                .expect_throw("Could not set multipart body");
+
+            dispatch(&self.hooks, &Method::POST, "pictrs/image", async {
+                match req.send().await {
+                    Ok(response) => (
+                        Some(response.status()),
+                        response
+                            .json::<UploadImageResponse>()
+                            .await
+                            .map_err(Into::into),
+                    ),
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
+            .await
+        }
+
+        async fn delete_image(
+            &self,
+            file: &str,
+            delete_token: &str,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<()> {
+            hold_back(
+                &self.hooks,
+                &Method::GET,
+                "pictrs/image/delete",
+                || serde_json::json!({ "file": file }),
+            )?;
+            let req = Request::get(&build_url(
+                &format!("pictrs/image/delete/{delete_token}/{file}"),
+                &self.options,
+            ))
             .with_headers(headers)
             .maybe_with_jwt(jwt);
 
-            #[cfg(all(feature = "leptos", target_family = "wasm"))]
-            {
-                use web_sys::AbortController;
-                let abort_controller = AbortController::new().ok();
-                let abort_signal = abort_controller.as_ref().map(AbortController::signal);
-                leptos::on_cleanup(move || {
-                    if let Some(abort_controller) = abort_controller {
-                        abort_controller.abort()
+            dispatch(&self.hooks, &Method::GET, "pictrs/image/delete", async {
+                match req.send().await {
+                    Ok(response) if response.ok() => (Some(response.status()), Ok(())),
+                    Ok(response) => (
+                        Some(response.status()),
+                        Err(Error::with_kind(ErrorKind::Api, response.status_text())),
+                    ),
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
+            .await
+        }
+
+        async fn download_image<W: AsyncWrite + Unpin>(
+            &self,
+            url: &str,
+            max_size: u64,
+            writer: &mut W,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<DownloadedImage> {
+            let req = Request::get(url).with_headers(headers).maybe_with_jwt(jwt);
+
+            dispatch(&self.hooks, &Method::GET, "image_download", async {
+                let response = match req.send().await {
+                    Ok(response) => response,
+                    Err(e) => return (None, Err(e.into())),
+                };
+                let status = response.status();
+                if !response.ok() {
+                    return (Some(status), Err(Error::new(response.status_text())));
+                }
+
+                let result = async {
+                    let headers = response.headers();
+                    let content_type = check_image_headers(
+                        headers.get("content-type").as_deref(),
+                        headers
+                            .get("content-length")
+                            .and_then(|length| length.parse().ok()),
+                        max_size,
+                    )?;
+                    // Fetch bodies are read in full, so the size limit is checked before writing anything.
+                    let body = response.binary().await?;
+                    let mut size = 0;
+                    write_image_chunk(&self.hooks, writer, &body, &mut size, max_size).await?;
+                    writer
+                        .flush()
+                        .await
+                        .map_err(|e| Error::new(e.to_string()))?;
+
+                    Ok(DownloadedImage { content_type, size })
+                }
+                .await;
+                (Some(status), result)
+            })
+            .await
+        }
+
+        async fn probe_media(
+            &self,
+            url: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<MediaInfo> {
+            let req = Request::get(url)
+                .method(gloo_net::http::Method::HEAD)
+                .with_headers(headers);
+
+            dispatch(&self.hooks, &Method::HEAD, "media_probe", async {
+                match req.send().await {
+                    Ok(response) if response.ok() => {
+                        let headers = response.headers();
+                        (
+                            Some(response.status()),
+                            Ok(MediaInfo::from_headers(
+                                headers.get("content-type").as_deref(),
+                                headers
+                                    .get("content-length")
+                                    .and_then(|length| length.parse().ok()),
+                                headers.get("cache-control").as_deref(),
+                            )),
+                        )
                     }
-                });
-                req = req.abort_signal(abort_signal.as_ref());
-            }
+                    Ok(response) => (
+                        Some(response.status()),
+                        Err(Error::with_kind(ErrorKind::Api, response.status_text())),
+                    ),
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
+            .await
+        }
+
+        async fn get_json<Response: DeserializeOwned>(
+            &self,
+            path: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<Response> {
+            let req = Request::get(&build_url(path, &self.options)).with_headers(headers);
 
-            match method {
-                Method::GET => req.build().expect_throw("Could not parse query params"),
-                Method::POST | Method::PUT => {
-                    req.json(&body).expect_throw("Could not parse JSON body")
+            dispatch(&self.hooks, &Method::GET, path, async {
+                match req.send().await {
+                    Ok(response) if response.ok() => (
+                        Some(response.status()),
+                        response.json::<Response>().await.map_err(Into::into),
+                    ),
+                    Ok(response) => (
+                        Some(response.status()),
+                        Err(Error::from_response(response.status(), b"")),
+                    ),
+                    Err(e) => (None, Err(e.into())),
                 }
-                method => unreachable!(
-                    "This crate only uses GET, POST, and PUT HTTP methods. Got {method:?}"
-                ),
+            })
+            .await
+        }
+
+        async fn get_text(
+            &self,
+            url: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<String> {
+            let req = Request::get(url).with_headers(headers);
+
+            dispatch_external(&self.hooks, async {
+                match req.send().await {
+                    Ok(response) if response.ok() => (
+                        Some(response.status()),
+                        response.text().await.map_err(Into::into),
+                    ),
+                    Ok(response) => (
+                        Some(response.status()),
+                        Err(Error::from_response(response.status(), b"")),
+                    ),
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
+            .await
+        }
+
+        async fn send_json(
+            &self,
+            method: Method,
+            url: &str,
+            body: &serde_json::Value,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<()> {
+            hold_back(&self.hooks, &method, url, || body.clone())?;
+            let req = match method {
+                Method::PUT => Request::put(url),
+                _ => Request::post(url),
             }
-            .send()
-            .await?
-            .json::<Response>()
+            .with_headers(headers)
+            .json(body)?;
+
+            dispatch_external(&self.hooks, async {
+                match req.send().await {
+                    Ok(response) if response.ok() => (Some(response.status()), Ok(())),
+                    Ok(response) => {
+                        let status = response.status();
+                        let body = response.binary().await.unwrap_or_default();
+                        (Some(status), Err(Error::from_response(status, &body)))
+                    }
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
+            .await
+        }
+
+        async fn preconnect(&self, headers: &HashMap<String, String>) -> LemmyResult<()> {
+            let req = Request::get(&build_url("", &self.options))
+                .method(gloo_net::http::Method::HEAD)
+                .with_headers(headers);
+
+            dispatch(&self.hooks, &Method::HEAD, "preconnect", async {
+                match req.send().await {
+                    Ok(response) => (Some(response.status()), Ok(())),
+                    Err(e) => (None, Err(e.into())),
+                }
+            })
             .await
-            .map_err(Into::into)
         }
     }
 
@@ -132,16 +761,58 @@ mod goober {
 mod goober {
     use std::collections::HashMap;
 
+    use futures::{AsyncWrite, AsyncWriteExt, StreamExt};
     use http::Method;
+    use serde::de::DeserializeOwned;
 
     use crate::{
+        audit::audit,
+        cache::CachePolicy,
+        dry_run::hold_back,
         form::LemmyForm,
+        hooks::RequestHooks,
+        image::{DownloadedImage, MediaInfo, UploadImageResponse, UploadProgress},
         lemmy_client_trait::{private_trait, LemmyClientInternal},
         response::{LemmyResponse, LemmyResult},
-        ClientOptions, LemmyRequest,
+        ClientOptions, Error, LemmyRequest,
     };
 
-    use super::{build_route, MaybeWithJwt, WithHeaders};
+    use super::{
+        build_route, build_url, check_image_headers, dispatch, dispatch_external,
+        dispatch_with_retries, parse_response, write_image_chunk, FormBody, MaybeWithJwt,
+        WithHeaders, METHOD_OVERRIDE_HEADER,
+    };
+
+    /// How many bytes of an upload are handed to the connection at once when reporting progress.
+    const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Sends a request, returning the HTTP status if a response was received.
+    async fn send<T, Fut>(
+        request: reqwest::RequestBuilder,
+        read: impl FnOnce(reqwest::Response) -> Fut,
+    ) -> (Option<u16>, LemmyResult<T>)
+    where
+        Fut: std::future::Future<Output = LemmyResult<T>>,
+    {
+        match request.send().await {
+            Ok(response) => (Some(response.status().as_u16()), read(response).await),
+            Err(e) => (None, Err(e.into())),
+        }
+    }
+
+    /// Reads the body of a response chunk by chunk, waiting for the bandwidth limit of the hooks after each chunk.
+    async fn read_body(
+        hooks: &RequestHooks,
+        mut response: reqwest::Response,
+    ) -> LemmyResult<Vec<u8>> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            hooks.record_download(chunk.len()).await;
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
 
     impl WithHeaders for reqwest::RequestBuilder {
         fn with_headers(self, headers: &HashMap<String, String>) -> Self {
@@ -170,16 +841,59 @@ mod goober {
         }
     }
 
+    #[derive(Clone)]
     pub struct ClientWrapper {
         client: reqwest::Client,
+        /// Whether compressed responses are asked for. The client always decompresses them, so turning this off only changes the `Accept-Encoding` header.
+        compression: bool,
         pub options: ClientOptions,
+        /// The URL of the instance, built once so requests only append their path to it.
+        base_url: String,
+        pub(crate) hooks: RequestHooks,
+    }
+
+    /// Builds the underlying client, which accepts responses compressed with gzip or brotli and decompresses them transparently.
+    fn build_client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .gzip(true)
+            .brotli(true)
+            .build()
+            .expect("The TLS backend can be initialized")
     }
 
     impl ClientWrapper {
         pub fn new(options: ClientOptions) -> Self {
             Self {
-                client: reqwest::Client::new(),
+                client: build_client(),
+                compression: true,
+                base_url: build_url("", &options),
                 options,
+                hooks: RequestHooks::default(),
+            }
+        }
+
+        /// A copy that sends requests to the instance in `options` through the same connection pool, with the same hooks.
+        pub fn with_options(&self, options: ClientOptions) -> Self {
+            Self {
+                client: self.client.clone(),
+                compression: self.compression,
+                base_url: build_url("", &options),
+                options,
+                hooks: self.hooks.clone(),
+            }
+        }
+
+        pub fn set_compression(&mut self, enabled: bool) {
+            self.compression = enabled;
+        }
+
+        /// Starts a request, asking for an uncompressed response if compression is disabled.
+        fn request(&self, method: Method, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+            let request = self.client.request(method, url);
+            if self.compression {
+                request
+            } else {
+                request.header(reqwest::header::ACCEPT_ENCODING, "identity")
             }
         }
 
@@ -189,33 +903,326 @@ mod goober {
     }
 
     impl private_trait::LemmyClientInternal for ClientWrapper {
-        async fn make_request<Response, Form>(
+        async fn make_request_with_cache_policy<Response, Form>(
             &self,
             method: Method,
             path: &str,
             request: LemmyRequest<Form>,
             headers: &HashMap<String, String>,
-        ) -> LemmyResult<Response>
+        ) -> LemmyResult<(Response, CachePolicy)>
         where
             Response: LemmyResponse,
             Form: LemmyForm,
         {
-            let route = build_route(path, &self.options);
-            let LemmyRequest { body, jwt } = request;
-
-            match method {
-                Method::GET => self.client.get(route).query(&body),
-                Method::POST => self.client.post(route).json(&body),
-                Method::PUT => self.client.put(route).json(&body),
-                _ => unreachable!("This crate does not use other HTTP methods."),
-            }
-            .with_headers(headers)
-            .maybe_with_jwt(jwt)
-            .send()
-            .await?
-            .json::<Response>()
+            let route = build_route(&self.base_url, path, &self.hooks);
+            let (body, jwt) = FormBody::new(request, &self.hooks)?;
+            hold_back(&self.hooks, &method, path, || {
+                serde_json::to_value(&body).unwrap_or_default()
+            })?;
+
+            let result = dispatch_with_retries(&self.hooks, &method, path, || {
+                let request = match method {
+                    Method::GET => self.request(Method::GET, &route).query(&body),
+                    Method::POST => self.request(Method::POST, &route).json(&body),
+                    Method::PUT if self.hooks.method_override => self
+                        .request(Method::POST, &route)
+                        .header(METHOD_OVERRIDE_HEADER, Method::PUT.as_str())
+                        .json(&body),
+                    Method::PUT => self.request(Method::PUT, &route).json(&body),
+                    _ => unreachable!("This crate does not use other HTTP methods."),
+                }
+                .with_headers(headers)
+                .maybe_with_jwt(jwt.clone());
+                let request = match &self.hooks.customize {
+                    Some(customize) => customize(request),
+                    None => request,
+                };
+
+                send(request, |response| async {
+                    let status = response.status();
+                    if !status.is_success() {
+                        let body = response.bytes().await?;
+                        return Err(Error::from_response(status.as_u16(), &body));
+                    }
+
+                    let header = |name| {
+                        response
+                            .headers()
+                            .get(name)
+                            .and_then(|value| value.to_str().ok())
+                    };
+                    let policy = CachePolicy::from_headers(
+                        header(http::header::CACHE_CONTROL),
+                        header(http::header::EXPIRES),
+                        header(http::header::DATE),
+                        header(http::header::AGE),
+                    );
+                    let body = read_body(&self.hooks, response).await?;
+                    Ok((parse_response(&self.hooks, path, &body)?, policy))
+                })
+            })
+            .await;
+            audit(
+                &self.hooks,
+                &method,
+                path,
+                &body,
+                jwt.as_deref(),
+                headers,
+                &result,
+            );
+
+            result
+        }
+
+        async fn upload_image(
+            &self,
+            image: Vec<u8>,
+            progress: Option<UploadProgress>,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<UploadImageResponse> {
+            hold_back(&self.hooks, &Method::POST, "pictrs/image", || {
+                serde_json::Value::Null
+            })?;
+            let part = match progress {
+                Some(progress) => {
+                    let total = image.len() as u64;
+                    let mut sent = 0;
+                    let chunks = image
+                        .chunks(UPLOAD_CHUNK_SIZE)
+                        .map(<[u8]>::to_vec)
+                        .collect::<Vec<_>>();
+                    let body = futures::stream::iter(chunks).map(move |chunk| {
+                        sent += chunk.len() as u64;
+                        progress(sent, total);
+                        Ok::<_, std::io::Error>(chunk)
+                    });
+                    reqwest::multipart::Part::stream_with_length(
+                        reqwest::Body::wrap_stream(body),
+                        total,
+                    )
+                }
+                None => reqwest::multipart::Part::bytes(image),
+            };
+            let form = reqwest::multipart::Form::new().part("images[]", part.file_name("image"));
+
+            let request = self
+                .request(Method::POST, build_url("pictrs/image", &self.options))
+                .multipart(form)
+                .with_headers(headers)
+                .maybe_with_jwt(jwt);
+
+            dispatch(
+                &self.hooks,
+                &Method::POST,
+                "pictrs/image",
+                send(request, |response| async {
+                    response
+                        .json::<UploadImageResponse>()
+                        .await
+                        .map_err(Into::into)
+                }),
+            )
+            .await
+        }
+
+        async fn delete_image(
+            &self,
+            file: &str,
+            delete_token: &str,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<()> {
+            hold_back(
+                &self.hooks,
+                &Method::GET,
+                "pictrs/image/delete",
+                || serde_json::json!({ "file": file }),
+            )?;
+            let request = self
+                .request(
+                    Method::GET,
+                    build_url(
+                        &format!("pictrs/image/delete/{delete_token}/{file}"),
+                        &self.options,
+                    ),
+                )
+                .with_headers(headers)
+                .maybe_with_jwt(jwt);
+
+            dispatch(
+                &self.hooks,
+                &Method::GET,
+                "pictrs/image/delete",
+                send(request, |response| async {
+                    response.error_for_status()?;
+                    Ok(())
+                }),
+            )
+            .await
+        }
+
+        async fn download_image<W: AsyncWrite + Unpin>(
+            &self,
+            url: &str,
+            max_size: u64,
+            writer: &mut W,
+            jwt: Option<String>,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<DownloadedImage> {
+            let request = self
+                .request(Method::GET, url)
+                .with_headers(headers)
+                .maybe_with_jwt(jwt);
+
+            dispatch(
+                &self.hooks,
+                &Method::GET,
+                "image_download",
+                send(request, |response| async move {
+                    let response = response.error_for_status()?;
+                    let content_type = check_image_headers(
+                        response
+                            .headers()
+                            .get(http::header::CONTENT_TYPE)
+                            .and_then(|content_type| content_type.to_str().ok()),
+                        response.content_length(),
+                        max_size,
+                    )?;
+
+                    let mut size = 0;
+                    let mut body = response.bytes_stream();
+                    while let Some(chunk) = body.next().await {
+                        write_image_chunk(&self.hooks, writer, &chunk?, &mut size, max_size)
+                            .await?;
+                    }
+                    writer
+                        .flush()
+                        .await
+                        .map_err(|e| Error::new(e.to_string()))?;
+
+                    Ok(DownloadedImage { content_type, size })
+                }),
+            )
+            .await
+        }
+
+        async fn probe_media(
+            &self,
+            url: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<MediaInfo> {
+            let request = self.request(Method::HEAD, url).with_headers(headers);
+
+            dispatch(
+                &self.hooks,
+                &Method::HEAD,
+                "media_probe",
+                send(request, |response| async move {
+                    let response = response.error_for_status()?;
+                    let header = |name| {
+                        response
+                            .headers()
+                            .get(name)
+                            .and_then(|value| value.to_str().ok())
+                    };
+
+                    Ok(MediaInfo::from_headers(
+                        header(http::header::CONTENT_TYPE),
+                        // The body of a response to HEAD is empty, so the header is read instead of `content_length`.
+                        header(http::header::CONTENT_LENGTH).and_then(|length| length.parse().ok()),
+                        header(http::header::CACHE_CONTROL),
+                    ))
+                }),
+            )
+            .await
+        }
+
+        async fn get_json<Response: DeserializeOwned>(
+            &self,
+            path: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<Response> {
+            let request = self
+                .request(Method::GET, build_url(path, &self.options))
+                .with_headers(headers);
+
+            dispatch(
+                &self.hooks,
+                &Method::GET,
+                path,
+                send(request, |response| async {
+                    response
+                        .error_for_status()?
+                        .json::<Response>()
+                        .await
+                        .map_err(Into::into)
+                }),
+            )
+            .await
+        }
+
+        async fn get_text(
+            &self,
+            url: &str,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<String> {
+            let request = self.request(Method::GET, url).with_headers(headers);
+
+            dispatch_external(
+                &self.hooks,
+                send(request, |response| async {
+                    response
+                        .error_for_status()?
+                        .text()
+                        .await
+                        .map_err(Into::into)
+                }),
+            )
+            .await
+        }
+
+        async fn send_json(
+            &self,
+            method: Method,
+            url: &str,
+            body: &serde_json::Value,
+            headers: &HashMap<String, String>,
+        ) -> LemmyResult<()> {
+            hold_back(&self.hooks, &method, url, || body.clone())?;
+            let request = self
+                .request(method.clone(), url)
+                .with_headers(headers)
+                .json(body);
+
+            dispatch_external(
+                &self.hooks,
+                send(request, |response| async {
+                    let status = response.status();
+                    if !status.is_success() {
+                        let body = response.bytes().await?;
+                        return Err(Error::from_response(status.as_u16(), &body));
+                    }
+
+                    Ok(())
+                }),
+            )
+            .await
+        }
+
+        async fn preconnect(&self, headers: &HashMap<String, String>) -> LemmyResult<()> {
+            let request = self
+                .request(Method::HEAD, build_url("", &self.options))
+                .with_headers(headers);
+
+            dispatch(
+                &self.hooks,
+                &Method::HEAD,
+                "preconnect",
+                send(request, |_| async { Ok(()) }),
+            )
             .await
-            .map_err(Into::into)
         }
     }
 
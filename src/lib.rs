@@ -8,21 +8,28 @@
 //! A Rust HTTP client for Lemmy.
 //! If used when targeting WASM, uses the browser's built-in fetch API to reduce bundle size.
 //! # Example
-//! ```
+//! ```no_run
 //! use lemmy_client::{LemmyClient, ClientOptions};
 //!
+//! # async fn example() {
 //! let client = LemmyClient::new(ClientOptions {
 //!     domain: String::from("lemmy.ml"),
 //!     secure: true
 //! });
 //!
-//! let res = client.get_site(()).await;
+//! let res = client.get_site().await;
 //!
 //! assert!(res.is_ok());
+//! # }
 //! ```
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::{lemmy_client_trait::LemmyClientInternal, response::LemmyResult};
+use crate::{
+    cache::{TtlCache, DEFAULT_SITE_METADATA_TTL},
+    concurrency::ConcurrencyLimit,
+    endpoints::with_endpoints,
+    lemmy_client_trait::{private_trait, LemmyClientInternal},
+};
 use lemmy_api_common::{
     comment::*, community::*, custom_emoji::*, lemmy_db_schema::source::login_token::LoginToken,
     person::*, post::*, private_message::*, site::*, SuccessResponse,
@@ -32,21 +39,185 @@ use lemmy_client_internal::ClientWrapper;
 #[cfg(target_family = "wasm")]
 use lemmy_client_internal::Fetch;
 
+mod account;
+mod api;
+#[cfg(not(target_family = "wasm"))]
+mod archive;
+mod audit;
+mod blocklist;
+mod bookmarks;
+mod cache;
+mod comments;
+mod compat;
+mod concurrency;
+mod content_filter;
+mod conversations;
+mod crosspost;
+mod customize;
+mod deep_links;
+#[cfg(feature = "miette")]
+mod diagnostics;
+mod dry_run;
+mod emoji_sync;
+mod endpoints;
 mod error;
+#[cfg(feature = "i18n")]
+mod error_messages;
+mod federation;
+mod federation_queue;
+mod feed;
+mod flow_errors;
 mod form;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+mod hooks;
+mod image;
+#[cfg(all(feature = "integration-test", not(target_family = "wasm")))]
+mod integration_test;
+mod languages;
 mod lemmy_client_internal;
 mod lemmy_client_trait;
+mod links;
+mod login;
+mod markdown;
+mod metrics;
+#[cfg(all(feature = "mirror", not(target_family = "wasm")))]
+mod mirror;
+#[cfg(all(feature = "mirror", not(target_family = "wasm")))]
+mod mirror_search;
+mod newer_api;
+mod nodeinfo;
+mod notifications;
+mod opml;
+mod polling;
+#[cfg(all(feature = "prometheus", not(target_family = "wasm")))]
+mod prometheus_metrics;
+mod reddit_import;
+mod registry;
+mod reports;
 mod response;
+mod retry;
+mod roles;
+mod search;
+mod shutdown;
+mod sinks;
+mod site_settings;
+mod site_snapshot;
+mod stats;
+mod subscriptions;
+#[cfg(all(feature = "mirror", not(target_family = "wasm")))]
+mod sync;
+mod throttle;
+mod timestamps;
+mod transform;
+mod typestate;
 mod utils;
+mod validation;
+mod vote_analysis;
+mod votes;
+mod worker;
 
-pub use error::Error;
-pub use form::LemmyRequest;
+pub use account::PasswordResetRequest;
+pub use api::{ApiFuture, EndpointRequest, EndpointResponse, LemmyApi, ENDPOINTS};
+#[cfg(not(target_family = "wasm"))]
+pub use archive::{ArchiveCheckpoint, ArchiveOptions, HistoryProgress};
+#[cfg(not(target_family = "wasm"))]
+pub use audit::FileAuditSink;
+#[cfg(all(feature = "audit-sqlite", not(target_family = "wasm")))]
+pub use audit::SqliteAuditSink;
+pub use audit::{AuditEntry, AuditSink};
+pub use blocklist::{Blocklist, BlocklistReport};
+pub use bookmarks::{ContentItem, LikeFilter};
+pub use comments::{build_comment_tree, CommentNode, PostWithComments};
+pub use compat::{PieFedAdapter, ServerAdapter};
+pub use concurrency::{DispatchQueue, Priority};
+pub use content_filter::{ContentFilter, FilterReason, FilterStats};
+pub use conversations::{group_conversations, Conversation};
+pub use crosspost::CrosspostOutcome;
+pub use customize::{DeriveOptions, RequestBuilder};
+pub use deep_links::DeepLinks;
+pub use dry_run::{DryRunLog, DryRunRequest};
+pub use emoji_sync::{EmojiImage, EmojiManifest, EmojiManifestEntry, EmojiSyncReport};
+pub use error::{Error, ErrorKind};
+#[cfg(feature = "i18n")]
+pub use error_messages::error_message_catalog;
+pub use federation::{
+    filter_instances, paginate_instances, parse_domain_list, sort_instances, BlocklistImportMode,
+    FederatedInstance, FederationStatus, InstanceBlocklistReport, InstanceDiff, InstanceFilter,
+    InstanceSort,
+};
+pub use federation_queue::{
+    lagging_instances, FederationLagEvent, FederationQueueState, FederationWatchOptions,
+    LaggingInstance,
+};
+pub use feed::{Feed, FeedFilters, RecentlySeen};
+pub use flow_errors::{AccountError, LoginError};
+pub use form::{LemmyForm, LemmyRequest};
+pub use http::Method;
+pub use image::{
+    DownloadOptions, DownloadedImage, ImageFile, ImageFormat, MediaInfo, ReplacedImage,
+    ThumbnailOptions, UploadImageResponse, UploadProgress, UploadedImage,
+    DEFAULT_MAX_DOWNLOAD_SIZE,
+};
+#[cfg(all(feature = "integration-test", not(target_family = "wasm")))]
+pub use integration_test::TestInstance;
+pub use languages::{
+    interface_language, language_allowed, language_by_code, language_by_id, language_ids,
+    pick_language_id, UNDETERMINED_LANGUAGE,
+};
 pub use lemmy_api_common;
+pub use links::{ap_id_from_link, canonical_url, same_url};
+pub use login::{LoginOutcome, PendingLogin};
+pub use markdown::{escape_markdown, extract_references, ContentReference, MarkdownBuilder};
+pub use metrics::{MetricsRecorder, RequestMetric};
+#[cfg(all(feature = "mirror", not(target_family = "wasm")))]
+pub use mirror::{Mirror, SyncReport};
+pub use newer_api::{GetPostsWithHidden, HidePost};
+pub use nodeinfo::{NodeInfo, NodeInfoSoftware};
+pub use notifications::{MarkReadReport, MarkedCount};
+pub use opml::OpmlImportReport;
+pub use polling::{MemorySeenStore, PollEvent, PollOptions, SeenStore};
+#[cfg(all(feature = "prometheus", not(target_family = "wasm")))]
+pub use prometheus_metrics::PrometheusMetrics;
+pub use reddit_import::{
+    parse_reddit_subscriptions, RedditCandidate, RedditFollowReport, RedditImportOptions,
+    RedditMapping,
+};
+pub use registry::{endpoint, endpoints_supported_by, ApiVersion, AuthRequirement, EndpointInfo};
+pub use reports::{Report, ReportFilter, ReportResolutionSummary};
+pub use response::{LemmyResponse, LemmyResult};
+pub use retry::{RetryPolicy, WriteRetryCheck};
+pub use roles::{AdminClient, ModClient};
+pub use search::{merge_search_results, SearchBuilder, SearchItem};
+pub use sinks::{EventSink, SinkMessage};
+pub use site_snapshot::{SiteDiff, SiteSnapshot};
+pub use stats::{
+    growth_series, GrowthPoint, MemoryStatsStore, StatsSnapshot, StatsStore, StatsTarget,
+};
+pub use subscriptions::{CommunityHandle, SubscriptionDiff};
+#[cfg(all(feature = "mirror", not(target_family = "wasm")))]
+pub use sync::SyncOptions;
+pub use throttle::BandwidthLimit;
+pub use timestamps::{ban_for, ban_until, humanize_relative, now, time_ago};
+#[cfg(feature = "time")]
+pub use timestamps::{from_offset_date_time, to_offset_date_time};
+pub use transform::ResponseTransform;
+pub use typestate::{Anonymous, Authenticated, Dynamic};
 pub use utils::ClientOptions;
+pub use validation::{Validate, ValidationError};
+pub use vote_analysis::{VoteAnalysis, VoteAnalysisOptions, VoteFlag, VoterSummary};
+pub use votes::{Vote, VoteCache};
+pub use worker::{WorkerRequest, WorkerResponse};
 
 /// API wrapper for lemmy
-pub struct LemmyClient {
+///
+/// By default the client is in [`Dynamic`] mode, where a JWT is passed with each request. See [`Anonymous`] and [`Authenticated`] for clients that check at compile time that requests are authenticated.
+pub struct LemmyClient<State = Dynamic> {
+    state: State,
     headers: HashMap<String, String>,
+    site_metadata_cache: Arc<TtlCache<String, GetSiteMetadataResponse>>,
+    validate_forms: bool,
+    image_proxy: bool,
     #[cfg(target_family = "wasm")]
     client: Fetch,
     #[cfg(not(target_family = "wasm"))]
@@ -54,25 +225,94 @@ pub struct LemmyClient {
 }
 
 macro_rules! expose_wrapped_fn {
-    ($name:ident, (), $response:ty, $doc:expr) => {
-        #[doc = $doc]
+    ($name:ident, (), $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
         pub async fn $name(&self) -> LemmyResult<$response> {
-            self.client
+            let response = self
+                .client
                 .$name(LemmyRequest::empty(), &self.headers)
-                .await
+                .await?;
+            self.filter_response($path, response)
+        }
+
+        paste::paste! {
+            #[doc = concat!("Like [`LemmyClient::", stringify!($name), "`], but deserializes the response into `Response`, e.g. for instances whose response differs from the one in [`lemmy_api_common`]. See [`LemmyResponse`].")]
+            pub async fn [<$name _as>]<Response: LemmyResponse>(&self) -> LemmyResult<Response> {
+                self.make_filtered_request(Method::$method, $path, LemmyRequest::empty())
+                    .await
+            }
         }
     };
-    ($name:ident, $form:ty, $response:ty, $doc:expr) => {
-        #[doc = $doc]
+    ($name:ident, $form:ty, $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
         pub async fn $name<Request>(&self, request: Request) -> LemmyResult<$response>
         where
             Request: Into<LemmyRequest<$form>>,
         {
-            self.client.$name(request.into(), &self.headers).await
+            let request = request.into();
+            if self.validate_forms {
+                LemmyForm::validate(&request.body)?;
+            }
+
+            let response = self.client.$name(request, &self.headers).await?;
+            self.filter_response($path, response)
+        }
+
+        paste::paste! {
+            #[doc = concat!("Like [`LemmyClient::", stringify!($name), "`], but deserializes the response into `Response`, e.g. for instances whose response differs from the one in [`lemmy_api_common`]. See [`LemmyResponse`].")]
+            pub async fn [<$name _as>]<Response, Request>(&self, request: Request) -> LemmyResult<Response>
+            where
+                Response: LemmyResponse,
+                Request: Into<LemmyRequest<$form>>,
+            {
+                let request = request.into();
+                if self.validate_forms {
+                    LemmyForm::validate(&request.body)?;
+                }
+
+                self.make_filtered_request(Method::$method, $path, request)
+                    .await
+            }
         }
     };
 }
 
+macro_rules! expose_wrapped_fns {
+    ($(
+        $(#[$doc:meta])*
+        $name:ident($variant:ident):
+        $method:ident $path:literal, $auth:ident, ($major:literal, $minor:literal, $patch:literal):
+        $form:tt => $response:ty;
+    )+) => {
+        impl LemmyClient {
+            $( expose_wrapped_fn!($name, $form, $response, $method, $path, $(#[$doc])*); )+
+        }
+    };
+}
+
+with_endpoints!(expose_wrapped_fns);
+
+impl<State> LemmyClient<State> {
+    /// Map of headers that will be included with each request.
+    pub fn headers(&self) -> &HashMap<String, String> {
+        &self.headers
+    }
+
+    /// Mutable map of headers that will be included with each request. Use this method if you want to add headers other than the JWT.
+    pub fn headers_mut(&mut self) -> &mut HashMap<String, String> {
+        &mut self.headers
+    }
+
+    /// The options that were provided during the initialization of the [`LemmyClient`].
+    pub fn client_options(&self) -> &ClientOptions {
+        self.client.client_options()
+    }
+}
+
 impl LemmyClient {
     /// Creates a new `LemmyClient`.
     /// # Examples
@@ -87,798 +327,115 @@ impl LemmyClient {
         #[cfg(target_family = "wasm")]
         {
             Self {
+                state: Dynamic,
                 client: Fetch::new(options),
                 headers: HashMap::new(),
+                site_metadata_cache: Arc::new(TtlCache::new(DEFAULT_SITE_METADATA_TTL)),
+                validate_forms: false,
+                image_proxy: false,
             }
         }
         #[cfg(not(target_family = "wasm"))]
         {
             Self {
+                state: Dynamic,
                 client: ClientWrapper::new(options),
                 headers: HashMap::new(),
+                site_metadata_cache: Arc::new(TtlCache::new(DEFAULT_SITE_METADATA_TTL)),
+                validate_forms: false,
+                image_proxy: false,
             }
         }
     }
 
-    /// Map of headers that will be included with each request.
-    pub fn headers(&self) -> &HashMap<String, String> {
-        &self.headers
+    /// Sets where measurements about every request made by this client are sent. See [`MetricsRecorder`].
+    pub fn set_metrics_recorder(&mut self, metrics: Arc<dyn MetricsRecorder>) {
+        self.client.hooks.metrics = Some(metrics);
     }
 
-    /// Mutable map of headers that will be included with each request. Use this method if you want to add headers other than the JWT.
-    pub fn headers_mut(&mut self) -> &mut HashMap<String, String> {
-        &mut self.headers
+    /// Sets where every mutating request made by this client is recorded, such as a [`FileAuditSink`], so moderation teams can review what their tools did. Requests held back by a [dry run](LemmyClient::dry_run) are not recorded. See [`AuditSink`].
+    pub fn set_audit_sink(&mut self, sink: Arc<dyn AuditSink>) {
+        self.client.hooks.audit = Some(sink);
     }
 
-    /// The options that were provided during the initialization of the [`LemmyClient`].
-    pub fn client_options(&self) -> &ClientOptions {
-        return self.client.client_options();
+    /// Limits how many requests this client can have in flight at once. Requests over the limit wait until another request finishes. [`None`] removes the limit, which is the default.
+    pub fn set_concurrency_limit(&mut self, max: Option<usize>) {
+        self.client.hooks.client_limit = max.map(ConcurrencyLimit::new);
     }
 
-    expose_wrapped_fn!(
-        get_site,
-        (),
-        GetSiteResponse,
-        r#"Gets the site and, if you pass an authorized JWT, information about the logged in user.
-
-HTTP GET /site"#
-    );
-    expose_wrapped_fn!(
-        create_site,
-        CreateSite,
-        SiteResponse,
-        r#"Creates site during initial setup.
-
-HTTP POST /site"#
-    );
-    expose_wrapped_fn!(
-        edit_site,
-        EditSite,
-        SiteResponse,
-        r#"Edits your site.
-
-HTTP PUT /site"#
-    );
-    expose_wrapped_fn!(
-        get_modlog,
-        GetModlog,
-        GetModlogResponse,
-        r#"Gets the modlog.
-
-HTTP GET /modlog"#
-    );
-    expose_wrapped_fn!(
-        search,
-        Search,
-        SearchResponse,
-        r#"Searches for content.
-
-HTTP GET /search"#
-    );
-    expose_wrapped_fn!(
-        resolve_object,
-        ResolveObject,
-        ResolveObjectResponse,
-        r#"Fetches an object from a non-local instance.
-
-HTTP GET /resolve_object"#
-    );
-    expose_wrapped_fn!(
-        get_community,
-        GetCommunity,
-        GetCommunityResponse,
-        r#"Gets a community.
-
-HTTP GET /community"#
-    );
-    expose_wrapped_fn!(
-        create_community,
-        CreateCommunity,
-        CommunityResponse,
-        r#"Creates a new community.
-
-HTTP POST /community"#
-    );
-    expose_wrapped_fn!(
-        edit_community,
-        EditCommunity,
-        CommunityResponse,
-        r#"Edits a community.
-
-HTTP PUT /community"#
-    );
-    expose_wrapped_fn!(
-        hide_community,
-        HideCommunity,
-        SuccessResponse,
-        r#"Hides a community from public view.
-
-HTTP PUT /community_hide"#
-    );
-    expose_wrapped_fn!(
-        list_communities,
-        ListCommunities,
-        ListCommunitiesResponse,
-        r#"Lists communities.
-
-HTTP GET /community/list"#
-    );
-    expose_wrapped_fn!(
-        follow_community,
-        FollowCommunity,
-        CommunityResponse,
-        r#"Subscribes to a community.
-
-HTTP POST /community/follow"#
-    );
-    expose_wrapped_fn!(
-        block_community,
-        BlockCommunity,
-        BlockCommunityResponse,
-        r#"Blocks a community.
-
-HTTP POST /community/block"#
-    );
-    expose_wrapped_fn!(
-        delete_community,
-        DeleteCommunity,
-        CommunityResponse,
-        r#"Deletes a community.
-
-HTTP POST /community/delete"#
-    );
-    expose_wrapped_fn!(
-        remove_community,
-        RemoveCommunity,
-        CommunityResponse,
-        r#"Removes a community (moderation action).
-
-HTTP POST /community/remove"#
-    );
-    expose_wrapped_fn!(
-        transfer_community,
-        TransferCommunity,
-        GetCommunityResponse,
-        r#"Transfers a community you own to another user on that community's moderation team.
-
-HTTP POST community/transfer"#
-    );
-    expose_wrapped_fn!(
-        ban_from_community,
-        BanFromCommunity,
-        BanFromCommunityResponse,
-        r#"Bans a user from a community.
-
-HTTP POST /community/ban_user"#
-    );
-    expose_wrapped_fn!(
-        add_mod_to_community,
-        AddModToCommunity,
-        AddModToCommunityResponse,
-        r#"Adds a moderator to your community.
-
-HTTP POST /community/mod"#
-    );
-    expose_wrapped_fn!(
-        get_federated_instances,
-        FederatedInstances,
-        GetFederatedInstancesResponse,
-        r#"Gets the instances that are federated with your instance.
-
-HTTP GET /federated_instances"#
-    );
-    expose_wrapped_fn!(
-        get_post,
-        GetPost,
-        GetPostResponse,
-        r#"Gets post.
-
-HTTP GET /post"#
-    );
-    expose_wrapped_fn!(
-        create_post,
-        CreatePost,
-        PostResponse,
-        r#"Creates a post.
-
-HTTP POST /post"#
-    );
-    expose_wrapped_fn!(
-        edit_post,
-        EditPost,
-        PostResponse,
-        r#"Edits a post you have already created.
-
-HTTP PUT /post"#
-    );
-    expose_wrapped_fn!(
-        delete_post,
-        DeletePost,
-        PostResponse,
-        r#"Deletes a post you have made.
-
-HTTP POST /post/delete"#
-    );
-    expose_wrapped_fn!(
-        remove_post,
-        RemovePost,
-        PostResponse,
-        r#"Removes a post (moderator action).
-
-HTTP POST /post/remove"#
-    );
-    expose_wrapped_fn!(
-        mark_post_as_read,
-        MarkPostAsRead,
-        SuccessResponse,
-        r#"Marks a post as read.
-
-HTTP POST /post/mark_as_read"#
-    );
-    expose_wrapped_fn!(
-        lock_post,
-        LockPost,
-        PostResponse,
-        r#"Prevents users from commenting on the post (moderator action).
-
-HTTP POST /post/lock"#
-    );
-    expose_wrapped_fn!(
-        feature_post,
-        FeaturePost,
-        PostResponse,
-        r#"Pins a post to the top of the community page (moderator action).
-
-HTTP POST /post/feature"#
-    );
-    expose_wrapped_fn!(
-        list_posts,
-        GetPosts,
-        GetPostsResponse,
-        r#"Gets posts with a variety of filters.
-
-HTTP GET /post/list"#
-    );
-    expose_wrapped_fn!(
-        like_post,
-        CreatePostLike,
-        PostResponse,
-        r#"Votes on a post.
-
-HTTP POST /post/like"#
-    );
-    expose_wrapped_fn!(
-        list_post_likes,
-        ListPostLikes,
-        ListPostLikesResponse,
-        r#"Lists the likes for a post.
-
-HTTP GET /post/like/list"#
-    );
-    expose_wrapped_fn!(
-        save_post,
-        SavePost,
-        PostResponse,
-        r#"Saves a post to your favorites list.
-
-HTTP PUT /post/save"#
-    );
-    expose_wrapped_fn!(
-        report_post,
-        CreatePostReport,
-        PostReportResponse,
-        r#"Reports a post to the moderator team of the community the post is in, the admin team of your instance, and the admin team of the poster's instance.
-
-HTTP POST /post/report"#
-    );
-    expose_wrapped_fn!(
-        resolve_post_report,
-        ResolvePostReport,
-        PostReportResponse,
-        r#"Resolves a post report (moderator action).
-
-HTTP PUT /post/report/resolve"#
-    );
-    expose_wrapped_fn!(
-        list_post_reports,
-        ListPostReports,
-        ListPostReportsResponse,
-        r#"Gets reports of posts that you are able to moderate.
-
-HTTP GET /post/report/list"#
-    );
-    expose_wrapped_fn!(
-        get_post_url_metadata,
-        GetSiteMetadata,
-        GetSiteMetadataResponse,
-        r#"Gets the metadata of a given site.
-
-HTTP POST /post/site_metadata"#
-    );
-    expose_wrapped_fn!(
-        get_comment,
-        GetComment,
-        CommentResponse,
-        r#"Gets a comment.
-
-HTTP GET /comment"#
-    );
-    expose_wrapped_fn!(
-        create_comment,
-        CreateComment,
-        CommentResponse,
-        r#"Creates a new comment.
-
-HTTP POST /comment"#
-    );
-    expose_wrapped_fn!(
-        edit_comment,
-        EditComment,
-        CommentResponse,
-        r#"Edits one of your already-created comments.
-
-HTTP PUT /comment"#
-    );
-    expose_wrapped_fn!(
-        delete_comment,
-        DeleteComment,
-        CommentResponse,
-        r#"Deletes one of your already-existing comments.
-
-HTTP POST /comment/delete"#
-    );
-    expose_wrapped_fn!(
-        remove_comment,
-        RemoveComment,
-        CommentResponse,
-        r#"Removes a post (moderator action).
-
-HTTP POST /comment/remove"#
-    );
-    expose_wrapped_fn!(
-        mark_reply_as_read,
-        MarkCommentReplyAsRead,
-        CommentReplyResponse,
-        r#"Marks a reply to one of your posts or comments as read.
-
-HTTP POST /comment/mark_as_read"#
-    );
-    expose_wrapped_fn!(
-        distinguish_comment,
-        DistinguishComment,
-        CommentResponse,
-        r#"Pins a comment to the top of a post's comment section (speak as moderator).
-
-HTTP POST /comment/distinguish"#
-    );
-    expose_wrapped_fn!(
-        like_comment,
-        CreateCommentLike,
-        CommentResponse,
-        r#"Votes on a comment.
-
-HTTP POST /comment/like"#
-    );
-    expose_wrapped_fn!(
-        list_comment_likes,
-        ListCommentLikes,
-        ListCommentLikesResponse,
-        r#"Gets the votes for a comment.
-
-HTTP GET /comment/like/list"#
-    );
-    expose_wrapped_fn!(
-        save_comment,
-        SaveComment,
-        CommentResponse,
-        r#"Saves a comment to your favorites list.
-
-HTTP PUT /comment/save"#
-    );
-    expose_wrapped_fn!(
-        list_comments,
-        GetComments,
-        GetCommentsResponse,
-        r#"Gets comments with various filters.
-
-HTTP GET /comment/list"#
-    );
-    expose_wrapped_fn!(
-        create_comment_report,
-        CreateCommentReport,
-        CommentResponse,
-        r#"Reports a comment to the moderator team of the community the comment is in, your instance's admin team, and the commentor's instance's admin team.
-
-HTTP POST /comment/report"#
-    );
-    expose_wrapped_fn!(
-        resolve_comment_report,
-        ResolveCommentReport,
-        CommentReportResponse,
-        r#"Resolves a report on a comment made in a community you moderate or instance you administrate.
-
-HTTP PUT /comment/report/resolve"#
-    );
-    expose_wrapped_fn!(
-        list_comment_reports,
-        ListCommentReports,
-        ListCommentReportsResponse,
-        r#"Lists reports for comments in communities you moderate or instances you adminstrate.
-
-HTTP GET /comment/report/list"#
-    );
-    expose_wrapped_fn!(
-        create_private_message,
-        CreatePrivateMessage,
-        PrivateMessageResponse,
-        r#"Creates and send a private message to another user.
-
-HTTP POST /private_message"#
-    );
-    expose_wrapped_fn!(
-        edit_private_message,
-        EditPrivateMessage,
-        PrivateMessageResponse,
-        r#"Edits a private message you have already sent.
-
-HTTP PUT /private_message"#
-    );
-    expose_wrapped_fn!(
-        list_private_messages,
-        GetPrivateMessages,
-        PrivateMessagesResponse,
-        r#"Lists private messages that have been sent to you.
-
-HTTP GET /private_message/list"#
-    );
-    expose_wrapped_fn!(
-        delete_private_message,
-        DeletePrivateMessage,
-        PrivateMessageResponse,
-        r#"Deletes a private that you have already sent.
-
-HTTP POST /private_message/delete"#
-    );
-    expose_wrapped_fn!(
-        mark_private_message_as_read,
-        MarkPrivateMessageAsRead,
-        PrivateMessageResponse,
-        r#"Marks a private message that was sent to you as read.
-
-HTTP POST /private_message/mark_as_read"#
-    );
-    expose_wrapped_fn!(
-        create_private_message_report,
-        CreatePrivateMessageReport,
-        PrivateMessageReportResponse,
-        r#"Reports a private message that was sent to you to your instance's admin team and the sender's instance's admin team.
-
-HTTP POST /private_message/report"#
-    );
-    expose_wrapped_fn!(
-        resolve_private_message_report,
-        ResolvePrivateMessageReport,
-        PrivateMessageReportResponse,
-        r#"Resolves a report of a private message sent to a user on the instance you administrate.
-
-HTTP PUT /private_message/report/resolve"#
-    );
-    expose_wrapped_fn!(
-        list_private_message_reports,
-        ListPrivateMessageReports,
-        ListPrivateMessageReportsResponse,
-        r#"Lists reports of private messages received on the isntance you administrate.
-
-HTTP GET /private_message/report/list"#
-    );
-    expose_wrapped_fn!(
-        get_person,
-        GetPersonDetails,
-        GetPersonDetailsResponse,
-        r#"Gets the publicly viewable details of a user's account.
-
-HTTP GET /user"#
-    );
-    expose_wrapped_fn!(
-        register_account,
-        Register,
-        RegistrationApplicationResponse,
-        r#"Registers a new account on an instance.
-
-HTTP POST /user/register"#
-    );
-    expose_wrapped_fn!(
-        get_captcha,
-        (),
-        GetCaptchaResponse,
-        r#"Gets a captcha.
-
-HTTP GET /user/get_captcha"#
-    );
-    expose_wrapped_fn!(
-        export_settings,
-        (),
-        String,
-        r#"Exports a backup of your user settings - including your saved content, followed communities, and blocks - as JSON.
-
-HTTP GET /user/export_settings"#
-    );
-    expose_wrapped_fn!(
-        import_settings,
-        String,
-        SuccessResponse,
-        r#"Imports a backup of your user settings.
-
-HTTP POST /user/import_settings"#
-    );
-    expose_wrapped_fn!(
-        list_mentions,
-        GetPersonMentions,
-        GetPersonMentionsResponse,
-        r#"Gets mentions of the authenticated user.
-
-HTTP GET /user/mention"#
-    );
-    expose_wrapped_fn!(
-        mark_mention_as_read,
-        MarkPersonMentionAsRead,
-        PersonMentionResponse,
-        r#"Marks a mention as read.
-
-HTTP POST /user/mention/mark_as_read"#
-    );
-    expose_wrapped_fn!(
-        list_replies,
-        GetReplies,
-        GetRepliesResponse,
-        r#"Gets replies to your posts and comments.
-
-HTTP GET /user/replies"#
-    );
-    expose_wrapped_fn!(
-        ban_from_site,
-        BanPerson,
-        BanPersonResponse,
-        r#"Bans a person from your instance.
-
-HTTP POST /user/ban"#
-    );
-    expose_wrapped_fn!(
-        list_banned_users,
-        (),
-        BannedPersonsResponse,
-        r#"Gets users banned who are banned from your isntance.
-
-HTTP GET /user/banned"#
-    );
-    expose_wrapped_fn!(
-        block_person,
-        BlockPerson,
-        BlockPersonResponse,
-        r#"Blocks a person.
-
-HTTP POST /user/block"#
-    );
-    expose_wrapped_fn!(
-        login,
-        Login,
-        LoginResponse,
-        r#"Logs into the instance, giving you a JWT to use to make authorized requests.
-
-HTTP POST /user/login"#
-    );
-    expose_wrapped_fn!(
-        logout,
-        (),
-        SuccessResponse,
-        r#"Deletes the active session associated with the JWT.
-
-HTTP POST /user/logout"#
-    );
-    expose_wrapped_fn!(
-        delete_account,
-        DeleteAccount,
-        SuccessResponse,
-        r#"Deletes your account.
-
-HTTP POST /user/delete_account"#
-    );
-    expose_wrapped_fn!(
-        reset_password,
-        PasswordReset,
-        SuccessResponse,
-        r#"Sends an email to your account (if you have one) with a one time link to change your password. Use this if you forgot your password.
-
-HTTP POST /user/password_reset"#
-    );
-    expose_wrapped_fn!(
-        change_password_after_reset,
-        PasswordChangeAfterReset,
-        SuccessResponse,
-        r#"Follows through with one time link password reset request.
-
-HTTP POST /user/password_change"#
-    );
-    expose_wrapped_fn!(
-        mark_all_notifications_as_read,
-        (),
-        GetRepliesResponse,
-        r#"Marks all notifications (replies, mentions, private messages) as read.
-
-HTTP POST /user/mark_all_as_read"#
-    );
-    expose_wrapped_fn!(
-        save_user_settings,
-        SaveUserSettings,
-        SuccessResponse,
-        r#"Saves your account settings.
-
-HTTP PUT /user/save_user_settings"#
-    );
-    expose_wrapped_fn!(
-        change_password,
-        ChangePassword,
-        LoginResponse,
-        r#"Changes your password if you are already logged in.
-
-HTTP PUT /user/change_password"#
-    );
-    expose_wrapped_fn!(
-        report_count,
-        GetReportCount,
-        GetReportCountResponse,
-        r#"Gets number of reports you can resolve.
-
-HTTP GET /user/report_count"#
-    );
-    expose_wrapped_fn!(
-        unread_count,
-        (),
-        GetUnreadCountResponse,
-        r#"Gets the number of unread notifications.
-
-HTTP GET /user/unread_count"#
-    );
-    expose_wrapped_fn!(
-        verify_email,
-        VerifyEmail,
-        SuccessResponse,
-        r#"Verifies your email. Used when the instance you are registering an account on requires email verification.
-
-HTTP POST /user/verify_email"#
-    );
-    expose_wrapped_fn!(
-        leave_admin,
-        (),
-        GetSiteResponse,
-        r#"Leave your instance's admin team.
-
-HTTP POST /user/leave_admin"#
-    );
-    expose_wrapped_fn!(
-        generate_totp_secret,
-        (),
-        GenerateTotpSecretResponse,
-        r#"Generates a secret to enable time-based one time passwords for two-factor authentication.
-
-After this, you will need to call /user/totp/update with a vaild token to enable it.
-
-HTTP POST /user/totp/generate"#
-    );
-    expose_wrapped_fn!(
-        update_totp,
-        UpdateTotp,
-        UpdateTotpResponse,
-        r#"Enables/disables two-factor authentivation.
-
-To enable, you must first call /user/totp/generate to generate a token to pass to this.
-
-You can only disable this if it is already enabled. Again, you must pass a valid TOTP.
-
-HTTP POST /user/totp/update"#
-    );
-    expose_wrapped_fn!(
-        list_logins,
-        (),
-        Vec<LoginToken>,
-        r#"Lists login tokens for your user's active sessions.
-
-HTTP GET /user/list_logins"#
-    );
-    expose_wrapped_fn!(
-        validate_auth,
-        (),
-        SuccessResponse,
-        r#"Returns an error message if your auth token is invalid.
-
-HTTP GET /user/validate_auth"#
-    );
-    expose_wrapped_fn!(
-        add_admin,
-        AddAdmin,
-        AddAdminResponse,
-        r#"Adds a user to your instance's admin team.
-
-HTTP POST admin/add"#
-    );
-    expose_wrapped_fn!(
-        unread_registration_application_count,
-        (),
-        GetUnreadRegistrationApplicationCountResponse,
-        r#"Gets the number of unread registration applications for the instance you administrate.
-
-HTTP GET /admin/registration_application/count"#
-    );
-    expose_wrapped_fn!(
-        list_registration_applications,
-        ListRegistrationApplications,
-        ListRegistrationApplicationsResponse,
-        r#"Gets applications to register an account on the instance you administer.
-
-HTTP GET /admin/registration_application/list"#
-    );
-    expose_wrapped_fn!(
-        approve_registration_application,
-        ApproveRegistrationApplication,
-        RegistrationApplicationResponse,
-        r#"Approves a pending registration application.
-
-HTTP PUT /admin/registration_application/approve"#
-    );
-    expose_wrapped_fn!(
-        purge_person,
-        PurgePerson,
-        SuccessResponse,
-        r#"Purges a user from the database.
+    /// Limits how many requests can be in flight at once to this client's instance, counting the requests of every client in the process that sets a host limit for the same instance. Applies in addition to [`LemmyClient::set_concurrency_limit`]. [`None`] removes the limit for this client, which is the default.
+    pub fn set_host_concurrency_limit(&mut self, max: Option<usize>) {
+        self.client.hooks.host_limit =
+            max.map(|max| ConcurrencyLimit::for_host(&self.client_options().domain, max));
+    }
 
-HTTP POST /admin/purge/person"#
-    );
-    expose_wrapped_fn!(
-        purge_community,
-        PurgeCommunity,
-        SuccessResponse,
-        r#"Purges a community from the database.
+    /// Sends this client's requests through a [`DispatchQueue`] shared with other clients, with the given priority. [`None`] stops using a queue, which is the default.
+    pub fn set_dispatch_queue(&mut self, queue: Option<(DispatchQueue, Priority)>) {
+        self.client.hooks.queue = queue;
+    }
 
-HTTP POST /admin/purge/community"#
-    );
-    expose_wrapped_fn!(
-        purge_post,
-        PurgePost,
-        SuccessResponse,
-        r#"Purges a post from the datbase.
+    #[cfg(not(target_family = "wasm"))]
+    /// Sets whether responses may be compressed. Enabled by default.
+    ///
+    /// When enabled, requests advertise gzip and brotli support in the `Accept-Encoding` header, and compressed responses are decompressed transparently. Listings compress very well, so this saves a lot of bandwidth on metered connections. Disabling it asks the instance for uncompressed responses, and keeps the connection pool shared with copies of the client.
+    ///
+    /// Only available on native targets, since browsers always handle compression themselves.
+    pub fn set_compression(&mut self, enabled: bool) {
+        self.client.set_compression(enabled);
+    }
 
-HTTP POST /admin/purge/post"#
-    );
-    expose_wrapped_fn!(
-        purge_comment,
-        PurgeComment,
-        SuccessResponse,
-        r#"Purges a comment from the database.
+    /// Opens a connection to the instance ahead of time, so that the first real request does not have to wait for DNS resolution and the TLS handshake. Useful when an app starts or resumes from the background.
+    ///
+    /// Sends a `HEAD` request to the instance's front page. Any response counts as success, since only the connection matters. Like other requests, it fails once the client is shut down, and waits for the limits of the client.
+    pub async fn preconnect(&self) -> LemmyResult<()> {
+        private_trait::LemmyClientInternal::preconnect(&self.client, &self.headers).await
+    }
 
-HTTP POST /admin/purge/comment"#
-    );
-    expose_wrapped_fn!(
-        create_custom_emoji,
-        CreateCustomEmoji,
-        CustomEmojiResponse,
-        r#"Creates a custom emoji.
+    /// Sends a request to an endpoint that this crate does not wrap. `path` is relative to `/api/v3`, like the paths in the docs of the other methods.
+    ///
+    /// Any types implementing [`LemmyForm`] and [`LemmyResponse`] can be used, so this also works for endpoints added by plugins, proxies, or newer versions of Lemmy.
+    pub async fn send_custom<Form, Response>(
+        &self,
+        method: Method,
+        path: &str,
+        request: impl Into<LemmyRequest<Form>>,
+    ) -> LemmyResult<Response>
+    where
+        Form: LemmyForm,
+        Response: LemmyResponse,
+    {
+        let request = request.into();
+        if self.validate_forms {
+            LemmyForm::validate(&request.body)?;
+        }
 
-HTTP POST /custom_emoji"#
-    );
-    expose_wrapped_fn!(
-        edit_custom_emoji,
-        EditCustomEmoji,
-        CustomEmojiResponse,
-        r#"Edits an existing custom emoji.
+        private_trait::LemmyClientInternal::make_request(
+            &self.client,
+            method,
+            path,
+            request,
+            &self.headers,
+        )
+        .await
+    }
 
-HTTP PUT /custom_emoji"#
-    );
-    expose_wrapped_fn!(
-        delete_custom_emoji,
-        DeleteCustomEmoji,
-        CustomEmojiResponse,
-        r#"Deletes an existing custom emoji.
+    /// Checks that a JWT is valid, returning an error if it is not. Much cheaper than checking for the logged in user in [`LemmyClient::get_site`].
+    ///
+    /// Unlike [`LemmyClient::validate_auth`], this takes the JWT directly, e.g. one stored from a previous session.
+    ///
+    /// HTTP GET /user/validate_auth
+    pub async fn validate_jwt(&self, jwt: impl Into<String>) -> LemmyResult<SuccessResponse> {
+        self.client
+            .validate_auth(LemmyRequest::from_jwt(Some(jwt.into())), &self.headers)
+            .await
+    }
 
-HTTP POST /custom_emoji/delete"#
-    );
+    /// Gets the information of the logged in user, failing if the request is not authenticated.
+    pub(crate) async fn my_user(&self, jwt: Option<String>) -> LemmyResult<MyUserInfo> {
+        self.client
+            .get_site(LemmyRequest::from_jwt(jwt), &self.headers)
+            .await?
+            .my_user
+            .ok_or_else(|| Error::new("not_logged_in"))
+    }
 }
@@ -0,0 +1,171 @@
+use crate::{
+    lemmy_client_internal::build_url, lemmy_client_trait::LemmyClientInternal,
+    response::LemmyResult, Error, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    lemmy_db_schema::source::{comment::Comment, community::Community, person::Person, post::Post},
+    site::ResolveObject,
+};
+use url::Url;
+
+/// Normalizes a link to an object, so that links written differently compare equal: the host is lowercased, and the fragment and any trailing slash are removed. Default ports are already removed when parsing.
+///
+/// Fails with `invalid_url` if `url` is not a URL.
+pub fn canonical_url(url: &str) -> LemmyResult<Url> {
+    let mut url = Url::parse(url.trim()).map_err(|e| Error::new(format!("invalid_url: {e}")))?;
+    url.set_fragment(None);
+    if url.path().len() > 1 && url.path().ends_with('/') {
+        let path = url.path().trim_end_matches('/').to_owned();
+        url.set_path(&path);
+    }
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    Ok(url)
+}
+
+/// Returns true if both links point at the same place once canonicalized with [`canonical_url`]. Links that are not URLs are only equal if they are identical.
+///
+/// ```
+/// use lemmy_client::same_url;
+///
+/// assert!(same_url("https://Lemmy.ML/c/rust/", "https://lemmy.ml:443/c/rust#top"));
+/// assert!(!same_url("https://lemmy.ml/c/rust", "https://lemmy.world/c/rust"));
+/// ```
+pub fn same_url(a: &str, b: &str) -> bool {
+    match (canonical_url(a), canonical_url(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Gets the ActivityPub id of the community or user a link on any Lemmy instance points at, such as `https://lemmy.world/c/rust@programming.dev`, which has the id `https://programming.dev/c/rust`.
+///
+/// Returns [`None`] for links to posts and comments, since their ids on the linked instance say nothing about their ids elsewhere. Use [`LemmyClient::resolve_object`] with the link instead. Assumes the home instance runs Lemmy, which uses the same paths for ids.
+///
+/// ```
+/// use lemmy_client::ap_id_from_link;
+///
+/// let ap_id = ap_id_from_link("https://lemmy.world/c/rust@programming.dev").unwrap();
+/// assert_eq!(ap_id.as_str(), "https://programming.dev/c/rust");
+/// ```
+pub fn ap_id_from_link(link: &str) -> Option<Url> {
+    let url = canonical_url(link).ok()?;
+    let mut segments = url.path_segments()?;
+    let (kind, value) = (segments.next()?, segments.next()?);
+    if segments.next().is_some() || !matches!(kind, "c" | "u") {
+        return None;
+    }
+
+    let (name, home) = value.split_once('@').unwrap_or((value, url.host_str()?));
+    canonical_url(&format!("{}://{home}/{kind}/{name}", url.scheme())).ok()
+}
+
+impl LemmyClient {
+    /// Returns true if `url` points at the client's instance.
+    pub fn is_instance_url(&self, url: &Url) -> bool {
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_owned(),
+            (None, _) => return false,
+        };
+
+        host.eq_ignore_ascii_case(&self.client_options().domain)
+    }
+
+    /// The URL of a post on the client's instance. Its ActivityPub id, which is its URL on its home instance, is [`Post::ap_id`].
+    pub fn post_url(&self, post: &Post) -> String {
+        build_url(&format!("post/{}", post.id.0), self.client_options())
+    }
+
+    /// The URL of a comment on the client's instance. Its ActivityPub id, which is its URL on its home instance, is [`Comment::ap_id`].
+    pub fn comment_url(&self, comment: &Comment) -> String {
+        build_url(&format!("comment/{}", comment.id.0), self.client_options())
+    }
+
+    /// The URL of a community on the client's instance, such as `https://lemmy.ml/c/rust@programming.dev`. Its ActivityPub id is [`Community::actor_id`].
+    pub fn community_url(&self, community: &Community) -> String {
+        build_url(
+            &format!(
+                "c/{}",
+                actor_name(&community.name, community.local, &community.actor_id)
+            ),
+            self.client_options(),
+        )
+    }
+
+    /// The URL of a user on the client's instance, such as `https://lemmy.ml/u/alice@lemmy.world`. Its ActivityPub id is [`Person::actor_id`].
+    pub fn person_url(&self, person: &Person) -> String {
+        build_url(
+            &format!(
+                "u/{}",
+                actor_name(&person.name, person.local, &person.actor_id)
+            ),
+            self.client_options(),
+        )
+    }
+
+    /// Turns a link to a Lemmy object on any instance into a link to the same object on the client's instance, e.g. to open links users paste from other instances in their own.
+    ///
+    /// Links to communities and users are rewritten directly. Links to posts and comments are resolved with [`LemmyClient::resolve_object`], which fetches the object if the instance has not seen it yet. Links to the client's instance are returned unchanged. Fails with `couldnt_find_object` if the link does not point at a Lemmy object.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # async fn example(client: LemmyClient) -> Result<(), lemmy_client::Error> {
+    /// // With a client for lemmy.ml:
+    /// let link = client
+    ///     .rewrite_to_instance("https://lemmy.world/c/rust@programming.dev", None)
+    ///     .await?;
+    /// assert_eq!(link, "https://lemmy.ml/c/rust@programming.dev");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rewrite_to_instance(
+        &self,
+        link: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<String> {
+        let url = canonical_url(link)?;
+        if self.is_instance_url(&url) {
+            return Ok(url.into());
+        }
+
+        if let Some(ap_id) = ap_id_from_link(url.as_str()) {
+            let mut segments = ap_id.path_segments().into_iter().flatten();
+            if let (Some(kind), Some(name)) = (segments.next(), segments.next()) {
+                let name = if self.is_instance_url(&ap_id) {
+                    name.to_owned()
+                } else {
+                    actor_name(name, false, &ap_id)
+                };
+                return Ok(build_url(&format!("{kind}/{name}"), self.client_options()));
+            }
+        }
+
+        let form = ResolveObject { q: url.into() };
+        let response = self
+            .client
+            .resolve_object(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await?;
+        if let Some(post_view) = response.post {
+            Ok(self.post_url(&post_view.post))
+        } else if let Some(comment_view) = response.comment {
+            Ok(self.comment_url(&comment_view.comment))
+        } else if let Some(community_view) = response.community {
+            Ok(self.community_url(&community_view.community))
+        } else if let Some(person_view) = response.person {
+            Ok(self.person_url(&person_view.person))
+        } else {
+            Err(Error::new("couldnt_find_object"))
+        }
+    }
+}
+
+/// The name of a user or community in links, with the home instance for remote ones.
+fn actor_name(name: &str, local: bool, actor_id: &Url) -> String {
+    match actor_id.host_str() {
+        Some(home) if !local => format!("{name}@{home}"),
+        _ => name.to_owned(),
+    }
+}
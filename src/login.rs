@@ -0,0 +1,99 @@
+use crate::{Anonymous, Authenticated, ClientOptions, LemmyClient, LoginError};
+use lemmy_api_common::{
+    person::{Login, LoginResponse},
+    sensitive::Sensitive,
+};
+
+/// The result of [`LemmyClient::login_new`].
+pub enum LoginOutcome {
+    /// The login succeeded.
+    LoggedIn(LemmyClient<Authenticated>),
+    /// The account has two-factor authentication enabled. Finish logging in with [`PendingLogin::submit_totp`].
+    TotpRequired(PendingLogin),
+}
+
+/// A login waiting for the two-factor authentication code of the account, returned in [`LoginOutcome::TotpRequired`].
+pub struct PendingLogin {
+    client: LemmyClient<Anonymous>,
+    username_or_email: String,
+    password: String,
+}
+
+impl PendingLogin {
+    /// Finishes logging in with the current code from the authenticator app of the account.
+    ///
+    /// Returns [`LoginOutcome::TotpRequired`] again if the code is wrong, to retry with a new one.
+    pub async fn submit_totp(self, token: &str) -> Result<LoginOutcome, LoginError> {
+        let token = token.trim().to_owned();
+        self.login(Some(token)).await
+    }
+
+    /// Gives up on logging in, returning the client that is not logged in.
+    pub fn into_client(self) -> LemmyClient<Anonymous> {
+        self.client
+    }
+
+    async fn login(self, totp_2fa_token: Option<String>) -> Result<LoginOutcome, LoginError> {
+        let response = self
+            .client
+            .login(Login {
+                username_or_email: Sensitive::new(self.username_or_email.clone()),
+                password: Sensitive::new(self.password.clone()),
+                totp_2fa_token,
+            })
+            .await;
+
+        match response {
+            Ok(LoginResponse { jwt: Some(jwt), .. }) => Ok(LoginOutcome::LoggedIn(
+                self.client.authenticated(jwt.into_inner()),
+            )),
+            Ok(response) if response.verify_email_sent => Err(LoginError::EmailNotVerified),
+            Ok(_) => Err(LoginError::RegistrationPending),
+            Err(e) => match LoginError::from(e) {
+                LoginError::TotpRequired | LoginError::WrongTotp => {
+                    Ok(LoginOutcome::TotpRequired(self))
+                }
+                e => Err(e),
+            },
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Logs in to the instance at `domain` over HTTPS, returning a client ready to use, which is all most bots and scripts need.
+    ///
+    /// Accounts with two-factor authentication return [`LoginOutcome::TotpRequired`], to finish logging in with [`PendingLogin::submit_totp`] once the code is known. Fails with [`LoginError::WrongCredentials`] if the username or password is wrong, and with variants such as [`LoginError::EmailNotVerified`] if the account cannot log in yet.
+    ///
+    /// ```no_run
+    /// use lemmy_client::{LemmyClient, LoginOutcome};
+    ///
+    /// # async fn example() -> Result<(), lemmy_client::Error> {
+    /// let client = match LemmyClient::login_new("lemmy.ml", "bot", "hunter2hunter2").await? {
+    ///     LoginOutcome::LoggedIn(client) => client,
+    ///     LoginOutcome::TotpRequired(pending) => match pending.submit_totp("123456").await? {
+    ///         LoginOutcome::LoggedIn(client) => client,
+    ///         LoginOutcome::TotpRequired(_) => panic!("The code was wrong"),
+    ///     },
+    /// };
+    /// let unread = client.unread_count().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn login_new(
+        domain: &str,
+        username_or_email: &str,
+        password: &str,
+    ) -> Result<LoginOutcome, LoginError> {
+        let client = LemmyClient::anonymous(ClientOptions {
+            domain: domain.to_owned(),
+            secure: true,
+        });
+        PendingLogin {
+            client,
+            username_or_email: username_or_email.to_owned(),
+            password: password.to_owned(),
+        }
+        .login(None)
+        .await
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::site::{ResolveObject, ResolveObjectResponse};
+use std::fmt;
+
+/// Characters that have a meaning in Lemmy's markdown and are escaped by [`escape_markdown`].
+const SPECIAL_CHARACTERS: &[char] = &[
+    '\\', '`', '*', '_', '{', '}', '[', ']', '(', ')', '<', '>', '#', '+', '-', '.', '!', '|', '~',
+    '^', ':', '@',
+];
+
+/// Escapes text so that Lemmy renders it literally instead of as markdown or as a mention.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if SPECIAL_CHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Builds a post, comment, or private message body using the markdown conventions of Lemmy's web UI.
+///
+/// Each block method adds a new paragraph, while inline methods append to the current one.
+///
+/// ```
+/// use lemmy_client::MarkdownBuilder;
+///
+/// let body = MarkdownBuilder::new()
+///     .text("Thanks ")
+///     .user_mention("alice", "lemmy.ml")
+///     .text(", see ")
+///     .community_mention("rust", "programming.dev")
+///     .quote("Quoted text")
+///     .spoiler("Ending", "It was a dream.")
+///     .build();
+///
+/// assert_eq!(
+///     body,
+///     "Thanks @alice@lemmy.ml, see !rust@programming.dev\n\n> Quoted text\n\n::: spoiler Ending\nIt was a dream.\n:::"
+/// );
+/// ```
+pub struct MarkdownBuilder {
+    body: String,
+}
+
+impl MarkdownBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start_block(&mut self) {
+        if !self.body.is_empty() {
+            self.body.push_str("\n\n");
+        }
+    }
+
+    /// Appends raw markdown to the current paragraph without escaping it.
+    pub fn raw(mut self, markdown: &str) -> Self {
+        self.body.push_str(markdown);
+        self
+    }
+
+    /// Appends text to the current paragraph, escaping anything Lemmy would otherwise format.
+    pub fn text(self, text: &str) -> Self {
+        self.raw(&escape_markdown(text))
+    }
+
+    /// Starts a new paragraph containing raw markdown.
+    pub fn paragraph(mut self, markdown: &str) -> Self {
+        self.start_block();
+        self.raw(markdown)
+    }
+
+    /// Appends a mention of a user, which notifies them when they are on an instance that federates with yours.
+    pub fn user_mention(self, name: &str, instance: &str) -> Self {
+        self.raw(&format!("@{name}@{instance}"))
+    }
+
+    /// Appends a link to a community that Lemmy resolves on the reader's instance.
+    pub fn community_mention(self, name: &str, instance: &str) -> Self {
+        self.raw(&format!("!{name}@{instance}"))
+    }
+
+    /// Appends a link. The link text is escaped.
+    pub fn link(self, text: &str, url: &str) -> Self {
+        self.raw(&format!("[{}]({url})", escape_markdown(text)))
+    }
+
+    /// Adds a heading. `level` is clamped to the range 1 to 6.
+    pub fn heading(mut self, level: u8, text: &str) -> Self {
+        self.start_block();
+        let level = usize::from(level.clamp(1, 6));
+        self.raw(&format!("{} {}", "#".repeat(level), escape_markdown(text)))
+    }
+
+    /// Adds a block quote. Every line of `markdown` is quoted.
+    pub fn quote(mut self, markdown: &str) -> Self {
+        self.start_block();
+        let quoted = markdown
+            .lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.raw(&quoted)
+    }
+
+    /// Adds a collapsed spoiler block that shows `title` until it is expanded.
+    pub fn spoiler(mut self, title: &str, markdown: &str) -> Self {
+        self.start_block();
+        self.raw(&format!("::: spoiler {title}\n{markdown}\n:::"))
+    }
+
+    /// Adds a fenced code block. `language` is used for syntax highlighting.
+    pub fn code_block(mut self, language: Option<&str>, code: &str) -> Self {
+        self.start_block();
+        self.raw(&format!("```{}\n{code}\n```", language.unwrap_or_default()))
+    }
+
+    /// Adds a bulleted list.
+    pub fn list<'a>(mut self, items: impl IntoIterator<Item = &'a str>) -> Self {
+        self.start_block();
+        let list = items
+            .into_iter()
+            .map(|item| format!("- {item}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.raw(&list)
+    }
+
+    /// Adds a horizontal rule.
+    pub fn rule(mut self) -> Self {
+        self.start_block();
+        self.raw("---")
+    }
+
+    /// Returns the finished markdown.
+    pub fn build(self) -> String {
+        self.body
+    }
+}
+
+impl fmt::Display for MarkdownBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.body)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A reference to a Lemmy object found in a content body by [`extract_references`].
+pub enum ContentReference {
+    /// A `@name@instance` mention or a `/u/name` link.
+    User {
+        /// Name of the user.
+        name: String,
+        /// Domain of the user's instance.
+        instance: String,
+    },
+    /// A `!name@instance` mention or a `/c/name` link.
+    Community {
+        /// Name of the community.
+        name: String,
+        /// Domain of the community's instance.
+        instance: String,
+    },
+    /// A link to a post.
+    Post {
+        /// The ID of the post on the linked instance.
+        id: i32,
+        /// Domain of the linked instance.
+        instance: String,
+    },
+    /// A link to a comment.
+    Comment {
+        /// The ID of the comment on the linked instance.
+        id: i32,
+        /// Domain of the linked instance.
+        instance: String,
+    },
+}
+
+impl ContentReference {
+    /// Returns the query to pass to [`LemmyClient::resolve_object`](crate::LemmyClient::resolve_object) to fetch the referenced object.
+    pub fn resolve_query(&self) -> String {
+        match self {
+            Self::User { name, instance } => format!("@{name}@{instance}"),
+            Self::Community { name, instance } => format!("!{name}@{instance}"),
+            Self::Post { id, instance } => format!("https://{instance}/post/{id}"),
+            Self::Comment { id, instance } => format!("https://{instance}/comment/{id}"),
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_host_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '.'
+}
+
+/// Parses a mention such as `alice@lemmy.ml` from the text following a `@` or `!`.
+fn parse_mention(text: &str) -> Option<(String, String, usize)> {
+    let name_len = text.find(|c| !is_name_char(c)).unwrap_or(text.len());
+    let rest = text[name_len..].strip_prefix('@')?;
+    let host = &rest[..rest.find(|c| !is_host_char(c)).unwrap_or(rest.len())];
+    let host = host.trim_end_matches(['.', '-']);
+    if name_len == 0 || !host.contains('.') {
+        return None;
+    }
+
+    Some((
+        text[..name_len].to_owned(),
+        host.to_owned(),
+        name_len + 1 + host.len(),
+    ))
+}
+
+/// Parses a Lemmy permalink such as `https://lemmy.ml/post/1`.
+fn parse_permalink(link: &str) -> Option<ContentReference> {
+    let url = url::Url::parse(link).ok()?;
+    let instance = url.host_str()?.to_owned();
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    let (kind, value) = (segments.next()?, segments.next()?);
+    if segments.next().is_some() {
+        return None;
+    }
+
+    let (name, name_instance) = value
+        .split_once('@')
+        .map(|(name, instance)| (name.to_owned(), instance.to_owned()))
+        .unwrap_or_else(|| (value.to_owned(), instance.clone()));
+    match kind {
+        "post" => value
+            .parse()
+            .ok()
+            .map(|id| ContentReference::Post { id, instance }),
+        "comment" => value
+            .parse()
+            .ok()
+            .map(|id| ContentReference::Comment { id, instance }),
+        "u" => Some(ContentReference::User {
+            name,
+            instance: name_instance,
+        }),
+        "c" => Some(ContentReference::Community {
+            name,
+            instance: name_instance,
+        }),
+        _ => None,
+    }
+}
+
+/// Extracts user and community mentions and Lemmy permalinks from a post, comment, or private message body.
+///
+/// References are returned in the order they first appear, without duplicates. Escaped mentions and mentions inside code are skipped.
+///
+/// ```
+/// use lemmy_client::{extract_references, ContentReference};
+///
+/// let references = extract_references(
+///     "Thanks @alice@lemmy.ml! Also see !rust@programming.dev and https://lemmy.world/post/42, but not `@code@example.com`.",
+/// );
+///
+/// assert_eq!(
+///     references,
+///     [
+///         ContentReference::User { name: "alice".into(), instance: "lemmy.ml".into() },
+///         ContentReference::Community { name: "rust".into(), instance: "programming.dev".into() },
+///         ContentReference::Post { id: 42, instance: "lemmy.world".into() },
+///     ]
+/// );
+/// ```
+pub fn extract_references(body: &str) -> Vec<ContentReference> {
+    let mut references = Vec::new();
+    let mut push = |reference| {
+        if !references.contains(&reference) {
+            references.push(reference);
+        }
+    };
+
+    let mut in_fence = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut skip_until = 0;
+        let mut previous = None;
+        for (offset, c) in line.char_indices() {
+            if offset < skip_until {
+                previous = Some(c);
+                continue;
+            }
+
+            let at_word_start = previous.is_none_or(|p: char| !is_name_char(p) && p != '\\');
+            previous = Some(c);
+            match c {
+                '`' => in_code = !in_code,
+                _ if in_code => {}
+                '@' | '!' if at_word_start => {
+                    if let Some((name, instance, len)) = parse_mention(&line[offset + 1..]) {
+                        push(if c == '@' {
+                            ContentReference::User { name, instance }
+                        } else {
+                            ContentReference::Community { name, instance }
+                        });
+                        skip_until = offset + 1 + len;
+                    }
+                }
+                'h' if at_word_start => {
+                    let link = &line[offset..];
+                    if !link.starts_with("https://") && !link.starts_with("http://") {
+                        continue;
+                    }
+
+                    let len = link
+                        .find(|c: char| {
+                            c.is_whitespace() || matches!(c, ')' | ']' | '>' | '<' | '"')
+                        })
+                        .unwrap_or(link.len());
+                    if let Some(reference) = parse_permalink(
+                        link[..len].trim_end_matches(['.', ',', '!', '?', ';', ':']),
+                    ) {
+                        push(reference);
+                    }
+                    skip_until = offset + len;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    references
+}
+
+impl LemmyClient {
+    /// Fetches the object a [`ContentReference`] points to through this client's instance, federating it first if needed.
+    pub async fn resolve_reference(
+        &self,
+        reference: &ContentReference,
+        jwt: Option<String>,
+    ) -> LemmyResult<ResolveObjectResponse> {
+        let form = ResolveObject {
+            q: reference.resolve_query(),
+        };
+        self.client
+            .resolve_object(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+}
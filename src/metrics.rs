@@ -0,0 +1,35 @@
+use http::Method;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A finished request, as passed to [`MetricsRecorder::record_request`].
+pub struct RequestMetric<'a> {
+    /// The name of the endpoint, such as `get_site`, or the path for endpoints this crate does not wrap.
+    pub endpoint: &'a str,
+    /// The HTTP method used.
+    pub method: &'a Method,
+    /// The HTTP status of the response, if one was received.
+    pub status: Option<u16>,
+    /// True if the request succeeded and its response could be read.
+    pub success: bool,
+    /// How long the request took, including reading the response.
+    pub latency: Duration,
+}
+
+/// Receives measurements about the requests made by a [`LemmyClient`](crate::LemmyClient). Set it with [`LemmyClient::set_metrics_recorder`](crate::LemmyClient::set_metrics_recorder).
+///
+/// Only [`MetricsRecorder::record_request`] is required, the other methods default to doing nothing.
+pub trait MetricsRecorder: Send + Sync {
+    /// Called after every request, whether it succeeded or not.
+    fn record_request(&self, metric: &RequestMetric<'_>);
+
+    /// Called before a failed request to `endpoint` is retried.
+    fn record_retry(&self, endpoint: &str) {
+        let _ = endpoint;
+    }
+
+    /// Called after the client waited before sending a request to `endpoint`, to stay under a rate limit. This is the time spent waiting for the dispatch queue, concurrency limits, and bandwidth limit, and the delay before retrying a request the instance rejected with `rate_limit_error`.
+    fn record_rate_limit_wait(&self, endpoint: &str, wait: Duration) {
+        let _ = (endpoint, wait);
+    }
+}
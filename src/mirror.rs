@@ -0,0 +1,679 @@
+use crate::{response::LemmyResult, timestamps::now, EndpointResponse, Error};
+use lemmy_api_common::{
+    lemmy_db_views::structs::{CommentView, PostView},
+    lemmy_db_views_actor::structs::CommunityView,
+};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS communities (
+    ap_id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    published INTEGER NOT NULL,
+    updated INTEGER,
+    fetched_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS posts (
+    ap_id TEXT PRIMARY KEY NOT NULL,
+    community_ap_id TEXT NOT NULL,
+    published INTEGER NOT NULL,
+    updated INTEGER,
+    fetched_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS posts_community ON posts (community_ap_id, published);
+CREATE TABLE IF NOT EXISTS comments (
+    ap_id TEXT PRIMARY KEY NOT NULL,
+    post_ap_id TEXT NOT NULL,
+    published INTEGER NOT NULL,
+    updated INTEGER,
+    fetched_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS comments_post ON comments (post_ap_id, published);
+CREATE TABLE IF NOT EXISTS sync_checkpoints (
+    feed TEXT PRIMARY KEY NOT NULL,
+    newest INTEGER NOT NULL,
+    synced_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sync_feed_items (
+    feed TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    ap_id TEXT NOT NULL,
+    published INTEGER NOT NULL,
+    PRIMARY KEY (feed, kind, ap_id)
+);
+CREATE TABLE IF NOT EXISTS sync_resume (
+    feed TEXT PRIMARY KEY NOT NULL,
+    page INTEGER NOT NULL,
+    newest INTEGER
+);
+";
+
+pub(crate) fn mirror_error(e: impl std::fmt::Display) -> Error {
+    Error::new(format!("mirror_failed: {e}"))
+}
+
+/// A local SQLite copy of posts, comments, and communities fetched from Lemmy, keyed by their ActivityPub ID.
+///
+/// Storing an object that is already in the mirror replaces it, so refetching keeps the mirror up to date. Objects are stored exactly as the API returned them, including fields specific to the user who fetched them, such as [`PostView::saved`].
+///
+/// ```no_run
+/// use lemmy_client::{lemmy_api_common::post::GetPosts, ClientOptions, LemmyClient, Mirror};
+///
+/// # async fn example() -> Result<(), lemmy_client::Error> {
+/// let client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// let mirror = Mirror::open("lemmy.sqlite")?;
+///
+/// let posts = client.list_posts(GetPosts::default()).await?.posts;
+/// mirror.store_posts(&posts)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Mirror {
+    connection: Mutex<Connection>,
+}
+
+impl Mirror {
+    /// Opens the mirror stored at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> LemmyResult<Self> {
+        Self::with_connection(Connection::open(path).map_err(mirror_error)?)
+    }
+
+    /// Opens a mirror that is only kept in memory.
+    pub fn open_in_memory() -> LemmyResult<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(mirror_error)?)
+    }
+
+    fn with_connection(connection: Connection) -> LemmyResult<Self> {
+        connection.execute_batch(SCHEMA).map_err(mirror_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    pub(crate) fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn transaction<T>(
+        &self,
+        f: impl FnOnce(&Transaction) -> LemmyResult<T>,
+    ) -> LemmyResult<T> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction().map_err(mirror_error)?;
+        let value = f(&transaction)?;
+        transaction.commit().map_err(mirror_error)?;
+
+        Ok(value)
+    }
+
+    /// Stores or updates communities. Deleted or removed communities are dropped from the mirror instead.
+    pub fn store_communities(&self, communities: &[CommunityView]) -> LemmyResult<()> {
+        self.transaction(|transaction| {
+            communities
+                .iter()
+                .try_for_each(|community| store_item(transaction, community))
+        })
+    }
+
+    /// Stores or updates posts. Deleted or removed posts are dropped from the mirror instead, along with their comments.
+    pub fn store_posts(&self, posts: &[PostView]) -> LemmyResult<()> {
+        self.transaction(|transaction| {
+            posts
+                .iter()
+                .try_for_each(|post| store_item(transaction, post))
+        })
+    }
+
+    /// Stores or updates comments. Deleted or removed comments are dropped from the mirror instead.
+    pub fn store_comments(&self, comments: &[CommentView]) -> LemmyResult<()> {
+        self.transaction(|transaction| {
+            comments
+                .iter()
+                .try_for_each(|comment| store_item(transaction, comment))
+        })
+    }
+
+    /// Stores every post, comment, and community contained in a response from [`LemmyClient::call`](crate::LemmyClient::call). Deleted or removed ones are dropped from the mirror instead, as in [`Mirror::store_posts`]. Responses without any are ignored.
+    pub fn store_response(&self, response: &EndpointResponse) -> LemmyResult<()> {
+        self.transaction(|transaction| {
+            let (posts, comments, communities): (
+                Vec<&PostView>,
+                Vec<&CommentView>,
+                Vec<&CommunityView>,
+            ) = match response {
+                EndpointResponse::ListPosts(response)
+                | EndpointResponse::ListPostsWithHidden(response) => {
+                    (response.posts.iter().collect(), vec![], vec![])
+                }
+                EndpointResponse::GetPost(response) => (
+                    std::iter::once(&response.post_view)
+                        .chain(&response.cross_posts)
+                        .collect(),
+                    vec![],
+                    vec![&response.community_view],
+                ),
+                EndpointResponse::CreatePost(response)
+                | EndpointResponse::EditPost(response)
+                | EndpointResponse::DeletePost(response)
+                | EndpointResponse::RemovePost(response)
+                | EndpointResponse::LockPost(response)
+                | EndpointResponse::FeaturePost(response)
+                | EndpointResponse::LikePost(response)
+                | EndpointResponse::SavePost(response) => {
+                    (vec![&response.post_view], vec![], vec![])
+                }
+                EndpointResponse::ListComments(response) => {
+                    (vec![], response.comments.iter().collect(), vec![])
+                }
+                EndpointResponse::GetComment(response)
+                | EndpointResponse::CreateComment(response)
+                | EndpointResponse::EditComment(response)
+                | EndpointResponse::DeleteComment(response)
+                | EndpointResponse::RemoveComment(response)
+                | EndpointResponse::DistinguishComment(response)
+                | EndpointResponse::LikeComment(response)
+                | EndpointResponse::SaveComment(response) => {
+                    (vec![], vec![&response.comment_view], vec![])
+                }
+                EndpointResponse::GetCommunity(response) => {
+                    (vec![], vec![], vec![&response.community_view])
+                }
+                EndpointResponse::CreateCommunity(response)
+                | EndpointResponse::EditCommunity(response)
+                | EndpointResponse::FollowCommunity(response)
+                | EndpointResponse::DeleteCommunity(response)
+                | EndpointResponse::RemoveCommunity(response) => {
+                    (vec![], vec![], vec![&response.community_view])
+                }
+                EndpointResponse::ListCommunities(response) => {
+                    (vec![], vec![], response.communities.iter().collect())
+                }
+                EndpointResponse::Search(response) => (
+                    response.posts.iter().collect(),
+                    response.comments.iter().collect(),
+                    response.communities.iter().collect(),
+                ),
+                EndpointResponse::GetPerson(response) => (
+                    response.posts.iter().collect(),
+                    response.comments.iter().collect(),
+                    vec![],
+                ),
+                _ => (vec![], vec![], vec![]),
+            };
+
+            communities
+                .into_iter()
+                .try_for_each(|community| store_item(transaction, community))?;
+            posts
+                .into_iter()
+                .try_for_each(|post| store_item(transaction, post))?;
+            comments
+                .into_iter()
+                .try_for_each(|comment| store_item(transaction, comment))
+        })
+    }
+
+    /// Gets a community by its ActivityPub ID.
+    pub fn community(&self, ap_id: &str) -> LemmyResult<Option<CommunityView>> {
+        self.get("SELECT data FROM communities WHERE ap_id = ?1", ap_id)
+    }
+
+    /// Gets a post by its ActivityPub ID.
+    pub fn post(&self, ap_id: &str) -> LemmyResult<Option<PostView>> {
+        self.get("SELECT data FROM posts WHERE ap_id = ?1", ap_id)
+    }
+
+    /// Gets a comment by its ActivityPub ID.
+    pub fn comment(&self, ap_id: &str) -> LemmyResult<Option<CommentView>> {
+        self.get("SELECT data FROM comments WHERE ap_id = ?1", ap_id)
+    }
+
+    /// Lists the posts of a community, newest first.
+    pub fn community_posts(&self, community_ap_id: &str) -> LemmyResult<Vec<PostView>> {
+        self.list(
+            "SELECT data FROM posts WHERE community_ap_id = ?1 ORDER BY published DESC",
+            community_ap_id,
+        )
+    }
+
+    /// Lists the comments of a post, oldest first.
+    pub fn post_comments(&self, post_ap_id: &str) -> LemmyResult<Vec<CommentView>> {
+        self.list(
+            "SELECT data FROM comments WHERE post_ap_id = ?1 ORDER BY published ASC",
+            post_ap_id,
+        )
+    }
+
+    fn get<T: DeserializeOwned>(&self, query: &str, ap_id: &str) -> LemmyResult<Option<T>> {
+        self.connection()
+            .query_row(query, [ap_id], |row| row.get::<_, String>(0))
+            .optional()
+            .map_err(mirror_error)?
+            .map(|data| serde_json::from_str(&data).map_err(mirror_error))
+            .transpose()
+    }
+
+    fn list<T: DeserializeOwned>(&self, query: &str, key: &str) -> LemmyResult<Vec<T>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(query).map_err(mirror_error)?;
+        let rows = statement
+            .query_map([key], |row| row.get::<_, String>(0))
+            .map_err(mirror_error)?;
+        rows.map(|data| serde_json::from_str(&data.map_err(mirror_error)?).map_err(mirror_error))
+            .collect()
+    }
+}
+
+/// An object that can be stored in a [`Mirror`].
+pub(crate) trait MirrorItem {
+    /// The table the object is stored in.
+    const TABLE: &'static str;
+
+    fn ap_id(&self) -> String;
+
+    /// When the object was published, in microseconds since the Unix epoch.
+    fn published(&self) -> i64;
+
+    /// When the object was last edited, in microseconds since the Unix epoch.
+    fn updated(&self) -> Option<i64>;
+
+    /// Returns true if the object was deleted or removed, and should no longer be mirrored.
+    fn is_gone(&self) -> bool;
+
+    fn insert(&self, transaction: &Transaction) -> LemmyResult<()>;
+}
+
+impl MirrorItem for CommunityView {
+    const TABLE: &'static str = "communities";
+
+    fn ap_id(&self) -> String {
+        self.community.actor_id.to_string()
+    }
+
+    fn published(&self) -> i64 {
+        self.community.published.timestamp_micros()
+    }
+
+    fn updated(&self) -> Option<i64> {
+        self.community
+            .updated
+            .map(|updated| updated.timestamp_micros())
+    }
+
+    fn is_gone(&self) -> bool {
+        self.community.deleted || self.community.removed
+    }
+
+    fn insert(&self, transaction: &Transaction) -> LemmyResult<()> {
+        insert_community(transaction, self)
+    }
+}
+
+impl MirrorItem for PostView {
+    const TABLE: &'static str = "posts";
+
+    fn ap_id(&self) -> String {
+        self.post.ap_id.to_string()
+    }
+
+    fn published(&self) -> i64 {
+        self.post.published.timestamp_micros()
+    }
+
+    fn updated(&self) -> Option<i64> {
+        self.post.updated.map(|updated| updated.timestamp_micros())
+    }
+
+    fn is_gone(&self) -> bool {
+        self.post.deleted || self.post.removed
+    }
+
+    fn insert(&self, transaction: &Transaction) -> LemmyResult<()> {
+        insert_post(transaction, self)
+    }
+}
+
+impl MirrorItem for CommentView {
+    const TABLE: &'static str = "comments";
+
+    fn ap_id(&self) -> String {
+        self.comment.ap_id.to_string()
+    }
+
+    fn published(&self) -> i64 {
+        self.comment.published.timestamp_micros()
+    }
+
+    fn updated(&self) -> Option<i64> {
+        self.comment
+            .updated
+            .map(|updated| updated.timestamp_micros())
+    }
+
+    fn is_gone(&self) -> bool {
+        self.comment.deleted || self.comment.removed
+    }
+
+    fn insert(&self, transaction: &Transaction) -> LemmyResult<()> {
+        insert_comment(transaction, self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// What happened to the objects passed to the mirror during a sync.
+pub struct SyncReport {
+    /// Objects that were not in the mirror before.
+    pub new: usize,
+    /// Objects that were edited since they were last stored.
+    pub updated: usize,
+    /// Objects that had not changed since they were last stored.
+    pub unchanged: usize,
+    /// Objects that were deleted or removed, or are no longer listed, and were dropped from the mirror.
+    pub deleted: usize,
+}
+
+impl std::ops::AddAssign for SyncReport {
+    fn add_assign(&mut self, other: Self) {
+        self.new += other.new;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.deleted += other.deleted;
+    }
+}
+
+impl Mirror {
+    /// Stores objects synced for `feed`, reporting which of them were new, edited, unchanged, or deleted.
+    pub(crate) fn apply<T: MirrorItem>(&self, feed: &str, items: &[T]) -> LemmyResult<SyncReport> {
+        self.transaction(|transaction| {
+            let mut report = SyncReport::default();
+            for item in items {
+                let ap_id = item.ap_id();
+                let stored = transaction
+                    .query_row(
+                        &format!("SELECT updated FROM {} WHERE ap_id = ?1", T::TABLE),
+                        [&ap_id],
+                        |row| row.get::<_, Option<i64>>(0),
+                    )
+                    .optional()
+                    .map_err(mirror_error)?;
+
+                if item.is_gone() {
+                    if stored.is_some() {
+                        delete_item::<T>(transaction, &ap_id)?;
+                        report.deleted += 1;
+                    }
+                    continue;
+                }
+
+                match stored {
+                    None => report.new += 1,
+                    Some(updated) if updated != item.updated() => report.updated += 1,
+                    Some(_) => report.unchanged += 1,
+                }
+                item.insert(transaction)?;
+                transaction
+                    .execute(
+                        "INSERT OR IGNORE INTO sync_feed_items (feed, kind, ap_id, published) VALUES (?1, ?2, ?3, ?4)",
+                        params![feed, T::TABLE, ap_id, item.published()],
+                    )
+                    .map_err(mirror_error)?;
+            }
+
+            Ok(report)
+        })
+    }
+
+    /// Drops the objects synced for `feed` before that were published after `after`, and before `before` if set, but are not in `seen`.
+    ///
+    /// Listings leave out deleted and removed objects for most users, so an object that is no longer listed in a range of a feed that was fetched in full is gone. Objects that are still listed in another feed are only dropped from this one. Returns how many objects were dropped from the mirror.
+    pub(crate) fn drop_missing<T: MirrorItem>(
+        &self,
+        feed: &str,
+        seen: &HashSet<String>,
+        after: i64,
+        before: Option<i64>,
+    ) -> LemmyResult<usize> {
+        self.transaction(|transaction| {
+            let missing = {
+                let mut statement = transaction
+                    .prepare(
+                        "SELECT ap_id FROM sync_feed_items
+                        WHERE feed = ?1 AND kind = ?2 AND published > ?3 AND published < ?4",
+                    )
+                    .map_err(mirror_error)?;
+                let rows = statement
+                    .query_map(
+                        params![feed, T::TABLE, after, before.unwrap_or(i64::MAX)],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .map_err(mirror_error)?;
+                rows.filter(|ap_id| ap_id.as_ref().map_or(true, |ap_id| !seen.contains(ap_id)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(mirror_error)?
+            };
+
+            let mut dropped = 0;
+            for ap_id in missing {
+                transaction
+                    .execute(
+                        "DELETE FROM sync_feed_items WHERE feed = ?1 AND kind = ?2 AND ap_id = ?3",
+                        params![feed, T::TABLE, ap_id],
+                    )
+                    .map_err(mirror_error)?;
+                let in_other_feeds = transaction
+                    .query_row(
+                        "SELECT 1 FROM sync_feed_items WHERE kind = ?1 AND ap_id = ?2",
+                        params![T::TABLE, ap_id],
+                        |_| Ok(()),
+                    )
+                    .optional()
+                    .map_err(mirror_error)?
+                    .is_some();
+                if !in_other_feeds {
+                    delete_item::<T>(transaction, &ap_id)?;
+                    dropped += 1;
+                }
+            }
+
+            Ok(dropped)
+        })
+    }
+
+    /// Gets the publish time, in microseconds since the Unix epoch, of the newest object synced for a feed.
+    pub(crate) fn checkpoint(&self, feed: &str) -> LemmyResult<Option<i64>> {
+        self.connection()
+            .query_row(
+                "SELECT newest FROM sync_checkpoints WHERE feed = ?1",
+                [feed],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(mirror_error)
+    }
+
+    pub(crate) fn set_checkpoint(&self, feed: &str, newest: i64) -> LemmyResult<()> {
+        self.connection()
+            .execute(
+                "INSERT INTO sync_checkpoints (feed, newest, synced_at) VALUES (?1, ?2, ?3)
+                ON CONFLICT (feed) DO UPDATE SET newest = excluded.newest, synced_at = excluded.synced_at",
+                params![feed, newest, now().timestamp()],
+            )
+            .map_err(mirror_error)?;
+
+        Ok(())
+    }
+
+    /// Gets the page an unfinished sync of a feed stopped before, with the publish time of the newest object it fetched.
+    pub(crate) fn resume_point(&self, feed: &str) -> LemmyResult<Option<(i64, Option<i64>)>> {
+        self.connection()
+            .query_row(
+                "SELECT page, newest FROM sync_resume WHERE feed = ?1",
+                [feed],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(mirror_error)
+    }
+
+    pub(crate) fn set_resume_point(
+        &self,
+        feed: &str,
+        page: i64,
+        newest: Option<i64>,
+    ) -> LemmyResult<()> {
+        self.connection()
+            .execute(
+                "INSERT INTO sync_resume (feed, page, newest) VALUES (?1, ?2, ?3)
+                ON CONFLICT (feed) DO UPDATE SET page = excluded.page, newest = excluded.newest",
+                params![feed, page, newest],
+            )
+            .map_err(mirror_error)?;
+
+        Ok(())
+    }
+
+    /// Stores the checkpoint of a finished sync, and forgets where an unfinished one stopped.
+    pub(crate) fn finish_sync(&self, feed: &str, newest: Option<i64>) -> LemmyResult<()> {
+        if let Some(newest) = newest {
+            self.set_checkpoint(feed, newest)?;
+        }
+        self.connection()
+            .execute("DELETE FROM sync_resume WHERE feed = ?1", [feed])
+            .map_err(mirror_error)?;
+
+        Ok(())
+    }
+
+    /// Forgets the checkpoint of a feed, and where an unfinished sync of it stopped, so that the next sync fetches it from the start.
+    pub fn reset_checkpoint(&self, feed: &str) -> LemmyResult<()> {
+        let connection = self.connection();
+        connection
+            .execute("DELETE FROM sync_checkpoints WHERE feed = ?1", [feed])
+            .map_err(mirror_error)?;
+        connection
+            .execute("DELETE FROM sync_resume WHERE feed = ?1", [feed])
+            .map_err(mirror_error)?;
+
+        Ok(())
+    }
+}
+
+/// Stores or updates an object, or deletes it if it is gone.
+fn store_item<T: MirrorItem>(transaction: &Transaction, item: &T) -> LemmyResult<()> {
+    if item.is_gone() {
+        delete_item::<T>(transaction, &item.ap_id())
+    } else {
+        item.insert(transaction)
+    }
+}
+
+/// Deletes an object from the mirror and from the feeds it was synced for, along with the comments of a post.
+fn delete_item<T: MirrorItem>(transaction: &Transaction, ap_id: &str) -> LemmyResult<()> {
+    transaction
+        .execute(
+            &format!("DELETE FROM {} WHERE ap_id = ?1", T::TABLE),
+            [ap_id],
+        )
+        .map_err(mirror_error)?;
+    transaction
+        .execute(
+            "DELETE FROM sync_feed_items WHERE kind = ?1 AND ap_id = ?2",
+            params![T::TABLE, ap_id],
+        )
+        .map_err(mirror_error)?;
+    if T::TABLE == PostView::TABLE {
+        transaction
+            .execute("DELETE FROM comments WHERE post_ap_id = ?1", [ap_id])
+            .map_err(mirror_error)?;
+    }
+
+    Ok(())
+}
+
+fn insert_community(transaction: &Transaction, community_view: &CommunityView) -> LemmyResult<()> {
+    let community = &community_view.community;
+    transaction
+        .execute(
+            "INSERT INTO communities (ap_id, name, published, updated, fetched_at, data)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (ap_id) DO UPDATE SET
+                name = excluded.name,
+                updated = excluded.updated,
+                fetched_at = excluded.fetched_at,
+                data = excluded.data",
+            params![
+                community.actor_id.to_string(),
+                community.name,
+                community.published.timestamp_micros(),
+                community.updated.map(|updated| updated.timestamp_micros()),
+                now().timestamp(),
+                serde_json::to_string(community_view).map_err(mirror_error)?,
+            ],
+        )
+        .map_err(mirror_error)?;
+
+    Ok(())
+}
+
+fn insert_post(transaction: &Transaction, post_view: &PostView) -> LemmyResult<()> {
+    let post = &post_view.post;
+    transaction
+        .execute(
+            "INSERT INTO posts (ap_id, community_ap_id, published, updated, fetched_at, data)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (ap_id) DO UPDATE SET
+                updated = excluded.updated,
+                fetched_at = excluded.fetched_at,
+                data = excluded.data",
+            params![
+                post.ap_id.to_string(),
+                post_view.community.actor_id.to_string(),
+                post.published.timestamp_micros(),
+                post.updated.map(|updated| updated.timestamp_micros()),
+                now().timestamp(),
+                serde_json::to_string(post_view).map_err(mirror_error)?,
+            ],
+        )
+        .map_err(mirror_error)?;
+
+    Ok(())
+}
+
+fn insert_comment(transaction: &Transaction, comment_view: &CommentView) -> LemmyResult<()> {
+    let comment = &comment_view.comment;
+    transaction
+        .execute(
+            "INSERT INTO comments (ap_id, post_ap_id, published, updated, fetched_at, data)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (ap_id) DO UPDATE SET
+                updated = excluded.updated,
+                fetched_at = excluded.fetched_at,
+                data = excluded.data",
+            params![
+                comment.ap_id.to_string(),
+                comment_view.post.ap_id.to_string(),
+                comment.published.timestamp_micros(),
+                comment.updated.map(|updated| updated.timestamp_micros()),
+                now().timestamp(),
+                serde_json::to_string(comment_view).map_err(mirror_error)?,
+            ],
+        )
+        .map_err(mirror_error)?;
+
+    Ok(())
+}
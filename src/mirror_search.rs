@@ -0,0 +1,125 @@
+use crate::{mirror::mirror_error, response::LemmyResult, Mirror, SearchItem};
+
+const SEARCH_SCHEMA: &str = "
+CREATE VIRTUAL TABLE IF NOT EXISTS posts_search USING fts5 (title, body, url);
+CREATE VIRTUAL TABLE IF NOT EXISTS comments_search USING fts5 (body);
+
+CREATE TRIGGER IF NOT EXISTS posts_search_insert AFTER INSERT ON posts BEGIN
+    INSERT INTO posts_search (rowid, title, body, url) VALUES (
+        new.rowid,
+        json_extract(new.data, '$.post.name'),
+        coalesce(json_extract(new.data, '$.post.body'), ''),
+        coalesce(json_extract(new.data, '$.post.url'), '')
+    );
+END;
+CREATE TRIGGER IF NOT EXISTS posts_search_update AFTER UPDATE ON posts BEGIN
+    DELETE FROM posts_search WHERE rowid = old.rowid;
+    INSERT INTO posts_search (rowid, title, body, url) VALUES (
+        new.rowid,
+        json_extract(new.data, '$.post.name'),
+        coalesce(json_extract(new.data, '$.post.body'), ''),
+        coalesce(json_extract(new.data, '$.post.url'), '')
+    );
+END;
+CREATE TRIGGER IF NOT EXISTS posts_search_delete AFTER DELETE ON posts BEGIN
+    DELETE FROM posts_search WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS comments_search_insert AFTER INSERT ON comments BEGIN
+    INSERT INTO comments_search (rowid, body)
+    VALUES (new.rowid, json_extract(new.data, '$.comment.content'));
+END;
+CREATE TRIGGER IF NOT EXISTS comments_search_update AFTER UPDATE ON comments BEGIN
+    DELETE FROM comments_search WHERE rowid = old.rowid;
+    INSERT INTO comments_search (rowid, body)
+    VALUES (new.rowid, json_extract(new.data, '$.comment.content'));
+END;
+CREATE TRIGGER IF NOT EXISTS comments_search_delete AFTER DELETE ON comments BEGIN
+    DELETE FROM comments_search WHERE rowid = old.rowid;
+END;
+";
+
+const REBUILD_SEARCH: &str = "
+DELETE FROM posts_search;
+INSERT INTO posts_search (rowid, title, body, url)
+SELECT
+    rowid,
+    json_extract(data, '$.post.name'),
+    coalesce(json_extract(data, '$.post.body'), ''),
+    coalesce(json_extract(data, '$.post.url'), '')
+FROM posts;
+DELETE FROM comments_search;
+INSERT INTO comments_search (rowid, body)
+SELECT rowid, json_extract(data, '$.comment.content') FROM comments;
+";
+
+const SEARCH_QUERY: &str = "
+SELECT kind, data FROM (
+    SELECT 'post' AS kind, posts.data AS data, bm25(posts_search, 10.0, 1.0, 1.0) AS rank
+    FROM posts_search JOIN posts ON posts.rowid = posts_search.rowid
+    WHERE posts_search MATCH ?1
+    UNION ALL
+    SELECT 'comment' AS kind, comments.data AS data, bm25(comments_search) AS rank
+    FROM comments_search JOIN comments ON comments.rowid = comments_search.rowid
+    WHERE comments_search MATCH ?1
+)
+ORDER BY rank
+LIMIT ?2
+";
+
+impl Mirror {
+    /// Builds a full-text index over the titles, bodies, and URLs of mirrored posts and the content of mirrored comments, and keeps it updated as objects are stored.
+    ///
+    /// The index is saved with the mirror, so this only needs to be called once per database. Calling it again rebuilds the index.
+    pub fn enable_full_text_search(&self) -> LemmyResult<()> {
+        self.transaction(|transaction| {
+            transaction
+                .execute_batch(SEARCH_SCHEMA)
+                .map_err(mirror_error)?;
+            transaction
+                .execute_batch(REBUILD_SEARCH)
+                .map_err(mirror_error)
+        })
+    }
+
+    /// Searches mirrored posts and comments for every word in `query`, best matches first. Matches in post titles rank higher than matches elsewhere.
+    ///
+    /// Requires [`Mirror::enable_full_text_search`] to have been called on the database.
+    pub fn search(&self, query: &str, limit: usize) -> LemmyResult<Vec<SearchItem>> {
+        let query = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.search_raw(&query, limit)
+    }
+
+    /// Searches mirrored posts and comments with an [FTS5 query](https://www.sqlite.org/fts5.html#full_text_query_syntax), such as `rust AND (async OR tokio)` or `title:release`, best matches first.
+    ///
+    /// Requires [`Mirror::enable_full_text_search`] to have been called on the database.
+    pub fn search_raw(&self, query: &str, limit: usize) -> LemmyResult<Vec<SearchItem>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(SEARCH_QUERY).map_err(mirror_error)?;
+        let rows = statement
+            .query_map(
+                rusqlite::params![query, i64::try_from(limit).unwrap_or(i64::MAX)],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .map_err(mirror_error)?;
+
+        rows.map(|row| {
+            let (kind, data) = row.map_err(mirror_error)?;
+            if kind == "post" {
+                serde_json::from_str(&data).map(SearchItem::Post)
+            } else {
+                serde_json::from_str(&data).map(SearchItem::Comment)
+            }
+            .map_err(mirror_error)
+        })
+        .collect()
+    }
+}
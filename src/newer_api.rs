@@ -0,0 +1,23 @@
+//! Forms for endpoints added after the version of [`lemmy_api_common`] this crate is pinned to.
+use lemmy_api_common::{lemmy_db_schema::newtypes::PostId, post::GetPosts};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Hides posts from the feeds of the logged in user. Added in Lemmy 0.19.4.
+pub struct HidePost {
+    /// The posts to hide or unhide.
+    pub post_ids: Vec<PostId>,
+    /// Whether to hide or unhide the posts.
+    pub hide: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// [`GetPosts`] with the filters added in Lemmy 0.19.4.
+pub struct GetPostsWithHidden {
+    #[serde(flatten)]
+    /// The filters supported by all Lemmy 0.19 versions.
+    pub posts: GetPosts,
+    /// Whether to include posts the logged in user hid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_hidden: Option<bool>,
+}
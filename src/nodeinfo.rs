@@ -0,0 +1,75 @@
+use crate::{
+    lemmy_client_trait::private_trait::LemmyClientInternal, ApiVersion, Error, LemmyClient,
+    LemmyResult,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// What an instance reports about itself in its [NodeInfo](https://nodeinfo.diaspora.software/) document. Works for any server in the fediverse, whatever version of Lemmy or other software it runs.
+pub struct NodeInfo {
+    /// The software the instance runs.
+    pub software: NodeInfoSoftware,
+    /// Whether new users can sign up.
+    #[serde(default)]
+    pub open_registrations: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The software an instance runs, as reported in its [`NodeInfo`].
+pub struct NodeInfoSoftware {
+    /// Name of the software, such as `lemmy`, `mbin`, or `piefed`.
+    pub name: String,
+    /// Version of the software.
+    pub version: String,
+}
+
+impl NodeInfo {
+    /// Whether the instance runs Lemmy.
+    pub fn is_lemmy(&self) -> bool {
+        self.software.name.eq_ignore_ascii_case("lemmy")
+    }
+
+    /// The version of the software, if it can be parsed.
+    pub fn version(&self) -> Option<ApiVersion> {
+        ApiVersion::parse(&self.software.version)
+    }
+}
+
+#[derive(Deserialize)]
+struct NodeInfoLinks {
+    links: Vec<NodeInfoLink>,
+}
+
+#[derive(Deserialize)]
+struct NodeInfoLink {
+    href: String,
+}
+
+impl LemmyClient {
+    /// Gets the [`NodeInfo`] of the instance.
+    ///
+    /// Unlike [`LemmyClient::get_site`], this works for every version of Lemmy, so it can be used to find out what an instance runs before talking to it.
+    ///
+    /// HTTP GET /.well-known/nodeinfo
+    pub async fn get_node_info(&self) -> LemmyResult<NodeInfo> {
+        let NodeInfoLinks { links } = self
+            .client
+            .get_json(".well-known/nodeinfo", &self.headers)
+            .await?;
+        // The last link points at the newest version of the schema.
+        let path = links
+            .last()
+            .and_then(|link| url::Url::parse(&link.href).ok())
+            .map(|url| url.path().trim_start_matches('/').to_owned())
+            .ok_or_else(|| Error::new("nodeinfo_not_found"))?;
+
+        let node_info: NodeInfo = self.client.get_json(&path, &self.headers).await?;
+        self.client
+            .hooks
+            .instance_version
+            .set(node_info.software.version.clone());
+
+        Ok(node_info)
+    }
+}
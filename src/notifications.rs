@@ -0,0 +1,229 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use futures::future::join3;
+use lemmy_api_common::{
+    person::{GetPersonMentions, GetReplies, MarkCommentReplyAsRead, MarkPersonMentionAsRead},
+    private_message::{GetPrivateMessages, MarkPrivateMessageAsRead},
+};
+use std::{collections::HashSet, future::Future, hash::Hash};
+
+/// How many unread notifications are fetched at a time.
+const UNREAD_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// How many notifications of one kind [`LemmyClient::mark_everything_read`] marked as read.
+pub struct MarkedCount {
+    /// How many were marked as read, including those marked before an error.
+    pub marked: usize,
+    /// The error that stopped the rest from being marked, if any.
+    pub error: Option<Error>,
+}
+
+impl MarkedCount {
+    fn complete(marked: i64) -> Self {
+        Self {
+            marked: usize::try_from(marked).unwrap_or_default(),
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// The outcome of [`LemmyClient::mark_everything_read`], for each kind of notification.
+pub struct MarkReadReport {
+    /// Replies to the user's posts and comments.
+    pub replies: MarkedCount,
+    /// Mentions of the user.
+    pub mentions: MarkedCount,
+    /// Private messages sent to the user.
+    pub private_messages: MarkedCount,
+}
+
+impl MarkReadReport {
+    /// Returns true if every kind of notification was marked as read.
+    pub fn is_complete(&self) -> bool {
+        self.replies.error.is_none()
+            && self.mentions.error.is_none()
+            && self.private_messages.error.is_none()
+    }
+}
+
+/// Marks unread notifications one by one until none are left, or an error stops it.
+///
+/// Stops once a fetch only returns notifications that were already marked, since the server can keep listing them as unread for a while, e.g. because of replication lag.
+async fn mark_all<Id, Fetch, FetchFut, Mark, MarkFut>(
+    fetch_unread: Fetch,
+    mark_read: Mark,
+) -> MarkedCount
+where
+    Id: Eq + Hash + Clone,
+    Fetch: Fn() -> FetchFut,
+    FetchFut: Future<Output = LemmyResult<Vec<Id>>>,
+    Mark: Fn(Id) -> MarkFut,
+    MarkFut: Future<Output = LemmyResult<()>>,
+{
+    let mut marked = HashSet::new();
+    let stopped_by = |marked: &HashSet<Id>, error| MarkedCount {
+        marked: marked.len(),
+        error: Some(error),
+    };
+    loop {
+        let unread = match fetch_unread().await {
+            Ok(unread) => unread
+                .into_iter()
+                .filter(|id| !marked.contains(id))
+                .collect::<Vec<_>>(),
+            Err(e) => return stopped_by(&marked, e),
+        };
+        if unread.is_empty() {
+            return MarkedCount {
+                marked: marked.len(),
+                error: None,
+            };
+        }
+        for id in unread {
+            if let Err(e) = mark_read(id.clone()).await {
+                return stopped_by(&marked, e);
+            }
+            marked.insert(id);
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Marks all unread replies, mentions, and private messages as read, and reports how many of each were marked.
+    ///
+    /// Everything is marked at once with [`LemmyClient::mark_all_notifications_as_read`] when possible. If that fails, each notification is marked one by one, so a failure for one kind of notification does not stop the others from being marked, and the report keeps the count marked before an error. Posts are marked as read separately, e.g. with [`crate::Feed::mark_read`].
+    pub async fn mark_everything_read(&self, jwt: Option<String>) -> MarkReadReport {
+        let unread = self
+            .client
+            .unread_count(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+            .await;
+        if let Ok(unread) = unread {
+            let marked = self
+                .client
+                .mark_all_notifications_as_read(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+                .await;
+            if marked.is_ok() {
+                return MarkReadReport {
+                    replies: MarkedCount::complete(unread.replies),
+                    mentions: MarkedCount::complete(unread.mentions),
+                    private_messages: MarkedCount::complete(unread.private_messages),
+                };
+            }
+        }
+
+        let replies = mark_all(
+            || {
+                let form = GetReplies {
+                    limit: Some(UNREAD_PAGE_SIZE),
+                    unread_only: Some(true),
+                    ..Default::default()
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    Ok(self
+                        .client
+                        .list_replies(request, &self.headers)
+                        .await?
+                        .replies
+                        .into_iter()
+                        .map(|reply| reply.comment_reply.id)
+                        .collect())
+                }
+            },
+            |comment_reply_id| {
+                let form = MarkCommentReplyAsRead {
+                    comment_reply_id,
+                    read: true,
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    self.client
+                        .mark_reply_as_read(request, &self.headers)
+                        .await
+                        .map(|_| ())
+                }
+            },
+        );
+
+        let mentions = mark_all(
+            || {
+                let form = GetPersonMentions {
+                    limit: Some(UNREAD_PAGE_SIZE),
+                    unread_only: Some(true),
+                    ..Default::default()
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    Ok(self
+                        .client
+                        .list_mentions(request, &self.headers)
+                        .await?
+                        .mentions
+                        .into_iter()
+                        .map(|mention| mention.person_mention.id)
+                        .collect())
+                }
+            },
+            |person_mention_id| {
+                let form = MarkPersonMentionAsRead {
+                    person_mention_id,
+                    read: true,
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    self.client
+                        .mark_mention_as_read(request, &self.headers)
+                        .await
+                        .map(|_| ())
+                }
+            },
+        );
+
+        let private_messages = mark_all(
+            || {
+                let form = GetPrivateMessages {
+                    limit: Some(UNREAD_PAGE_SIZE),
+                    unread_only: Some(true),
+                    ..Default::default()
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    Ok(self
+                        .client
+                        .list_private_messages(request, &self.headers)
+                        .await?
+                        .private_messages
+                        .into_iter()
+                        .map(|message| message.private_message.id)
+                        .collect())
+                }
+            },
+            |private_message_id| {
+                let form = MarkPrivateMessageAsRead {
+                    private_message_id,
+                    read: true,
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    self.client
+                        .mark_private_message_as_read(request, &self.headers)
+                        .await
+                        .map(|_| ())
+                }
+            },
+        );
+
+        let (replies, mentions, private_messages) =
+            join3(replies, mentions, private_messages).await;
+
+        MarkReadReport {
+            replies,
+            mentions,
+            private_messages,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, links::ap_id_from_link, response::LemmyResult, Error,
+    LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{lemmy_db_schema::source::community::Community, site::ResolveObject};
+use std::collections::HashSet;
+use url::Url;
+
+#[derive(Debug, Clone, Default)]
+/// The outcome of [`LemmyClient::import_opml`].
+pub struct OpmlImportReport {
+    /// ActivityPub IDs of communities that were followed.
+    pub followed: Vec<String>,
+    /// ActivityPub IDs of communities the account already followed.
+    pub already_followed: Vec<String>,
+    /// Feeds that are not Lemmy community feeds, or whose community could not be resolved or followed.
+    pub failed: Vec<(String, Error)>,
+}
+
+/// The RSS feed of a community, which its home instance serves.
+fn community_feed_url(community: &Community) -> String {
+    let actor_id: &Url = &community.actor_id;
+    let mut feed = actor_id.clone();
+    feed.set_path(&format!("feeds/c/{}.xml", community.name));
+    feed.set_query(None);
+    feed.set_fragment(None);
+    feed.into()
+}
+
+/// Gets the ActivityPub ID of the community an OPML outline points at, from its feed URL or its web page.
+fn community_ap_id(xml_url: Option<&str>, html_url: Option<&str>) -> Option<Url> {
+    let from_feed = xml_url.and_then(|xml_url| {
+        let url = Url::parse(xml_url.trim()).ok()?;
+        let mut segments = url.path_segments()?;
+        let (feeds, kind, file) = (segments.next()?, segments.next()?, segments.next()?);
+        let name = file.strip_suffix(".xml")?;
+        if feeds != "feeds" || kind != "c" || segments.next().is_some() {
+            return None;
+        }
+        ap_id_from_link(&format!("{}://{}/c/{name}", url.scheme(), url.host_str()?))
+    });
+
+    from_feed.or_else(|| {
+        html_url
+            .and_then(ap_id_from_link)
+            .filter(|ap_id| ap_id.path().starts_with("/c/"))
+    })
+}
+
+/// Escapes text for an XML attribute.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Reverses [`escape_xml`], also decoding numeric character references.
+fn unescape_xml(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+
+    unescaped
+}
+
+/// Reads the attributes of each `<outline>` element of an OPML file. Nothing else of the file matters for importing feeds, so this is not a full XML parser.
+fn opml_outlines(opml: &str) -> Vec<Vec<(String, String)>> {
+    let mut outlines = Vec::new();
+    let mut rest = opml;
+    while let Some(start) = rest.find("<outline") {
+        rest = &rest[start + "<outline".len()..];
+        let end = rest.find('>').unwrap_or(rest.len());
+        let mut tag = rest[..end].trim_end_matches('/');
+        rest = &rest[end..];
+
+        let mut attributes = Vec::new();
+        while let Some(eq) = tag.find('=') {
+            let name = tag[..eq].trim().to_owned();
+            let value = tag[eq + 1..].trim_start();
+            let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+                break;
+            };
+            let Some(len) = value[1..].find(quote) else {
+                break;
+            };
+            attributes.push((name, unescape_xml(&value[1..=len])));
+            tag = &value[len + 2..];
+        }
+        outlines.push(attributes);
+    }
+
+    outlines
+}
+
+impl LemmyClient {
+    /// Exports the communities the logged in user follows as an OPML file of their RSS feeds, which most feed readers can import.
+    pub async fn export_opml(&self, jwt: Option<String>) -> LemmyResult<String> {
+        let follows = self.my_follows(jwt).await?;
+        let title = format!("Subscriptions on {}", self.client_options().domain);
+
+        let mut opml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        opml.push_str("<opml version=\"2.0\">\n");
+        opml.push_str(&format!(
+            "  <head>\n    <title>{}</title>\n  </head>\n  <body>\n",
+            escape_xml(&title)
+        ));
+        for community in &follows {
+            let title = escape_xml(&community.title);
+            opml.push_str(&format!(
+                "    <outline type=\"rss\" text=\"{title}\" title=\"{title}\" xmlUrl=\"{}\" htmlUrl=\"{}\"/>\n",
+                escape_xml(&community_feed_url(community)),
+                escape_xml(community.actor_id.as_str()),
+            ));
+        }
+        opml.push_str("  </body>\n</opml>\n");
+
+        Ok(opml)
+    }
+
+    /// Follows the communities of the Lemmy community feeds in an OPML file, such as one exported by [`LemmyClient::export_opml`] or a feed reader. Communities from other instances are fetched with [`LemmyClient::resolve_object`] first.
+    ///
+    /// Feeds that are not Lemmy community feeds are reported as failed with `couldnt_find_community`, and the rest of the file is still imported.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # async fn example(client: LemmyClient, jwt: String) -> Result<(), lemmy_client::Error> {
+    /// let opml = std::fs::read_to_string("subscriptions.opml").unwrap();
+    /// let report = client.import_opml(&opml, Some(jwt)).await?;
+    /// println!("Followed {} communities", report.followed.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn import_opml(
+        &self,
+        opml: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<OpmlImportReport> {
+        let followed = self
+            .my_follows(jwt.clone())
+            .await?
+            .into_iter()
+            .map(|community| community.actor_id.to_string())
+            .collect::<HashSet<_>>();
+        let mut report = OpmlImportReport::default();
+        let mut seen = HashSet::new();
+
+        for attributes in opml_outlines(opml) {
+            let attribute = |name: &str| {
+                attributes
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.as_str())
+            };
+            let (xml_url, html_url) = (attribute("xmlUrl"), attribute("htmlUrl"));
+            let Some(feed) = xml_url.or(html_url) else {
+                // Outlines without links are folders.
+                continue;
+            };
+            let Some(ap_id) = community_ap_id(xml_url, html_url) else {
+                report
+                    .failed
+                    .push((feed.to_owned(), Error::new("couldnt_find_community")));
+                continue;
+            };
+            let ap_id = ap_id.to_string();
+            if !seen.insert(ap_id.clone()) {
+                continue;
+            }
+            if followed.contains(&ap_id) {
+                report.already_followed.push(ap_id);
+                continue;
+            }
+
+            match self.follow_by_ap_id(&ap_id, jwt.clone()).await {
+                Ok(()) => report.followed.push(ap_id),
+                Err(e) => report.failed.push((ap_id, e)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub(crate) async fn follow_by_ap_id(
+        &self,
+        ap_id: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<()> {
+        let form = ResolveObject {
+            q: ap_id.to_owned(),
+        };
+        let community_id = self
+            .client
+            .resolve_object(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+            .await?
+            .community
+            .ok_or_else(|| Error::new("couldnt_find_community"))?
+            .community
+            .id;
+
+        self.set_follow(community_id, true, jwt).await
+    }
+}
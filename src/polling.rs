@@ -0,0 +1,401 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::sleep, LemmyClient,
+    LemmyRequest, Report,
+};
+use futures::{stream, Stream};
+use lemmy_api_common::{
+    comment::{GetComments, ListCommentReports},
+    lemmy_db_schema::{CommentSortType, SortType},
+    lemmy_db_views::structs::{CommentView, PostView, PrivateMessageView},
+    post::{GetPosts, ListPostReports},
+    private_message::GetPrivateMessages,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// Remembers what a poller already delivered, so that restarting it does not deliver everything again. Keys identify items, such as `post:123`, and values are hashes of their content.
+///
+/// Implement it to persist the hashes, e.g. in a file or database. [`MemorySeenStore`] keeps them in memory.
+pub trait SeenStore: Send + Sync {
+    /// The hash stored for `key`, if the item was delivered before.
+    fn get(&self, key: &str) -> Option<u64>;
+
+    /// Stores the hash of an item that was just delivered.
+    fn set(&self, key: &str, hash: u64);
+}
+
+#[derive(Debug, Default)]
+/// A [`SeenStore`] that keeps the hashes in memory, so they are lost when the process exits.
+pub struct MemorySeenStore {
+    hashes: Mutex<HashMap<String, u64>>,
+}
+
+impl SeenStore for MemorySeenStore {
+    fn get(&self, key: &str) -> Option<u64> {
+        self.hashes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .copied()
+    }
+
+    fn set(&self, key: &str, hash: u64) {
+        self.hashes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key.to_owned(), hash);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An item delivered by a poller, such as [`LemmyClient::poll_posts`].
+pub enum PollEvent<T> {
+    /// An item that was not delivered before.
+    New(T),
+    /// An item that was delivered before, and has changed since. Only delivered if [`PollOptions::detect_edits`] is set.
+    Updated(T),
+}
+
+impl<T> PollEvent<T> {
+    /// The item, whether it is new or updated.
+    pub fn item(&self) -> &T {
+        match self {
+            Self::New(item) | Self::Updated(item) => item,
+        }
+    }
+
+    /// Takes the item, whether it is new or updated.
+    pub fn into_item(self) -> T {
+        match self {
+            Self::New(item) | Self::Updated(item) => item,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [`LemmyClient::poll_posts`] and [`LemmyClient::poll_comments`].
+pub struct PollOptions {
+    /// How long to wait between two polls.
+    pub interval: Duration,
+    /// Deliver items again as [`PollEvent::Updated`] when their content changes, rather than ignoring them.
+    pub detect_edits: bool,
+    /// When detecting edits, also deliver items whose score moved into another multiple of this step, e.g. every 10 points. [`None`] ignores the score.
+    pub score_step: Option<i64>,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            detect_edits: false,
+            score_step: None,
+        }
+    }
+}
+
+/// Hashes content with 64 bit FNV-1a. Unlike [`std::hash::DefaultHasher`], the result never changes between Rust versions, so it can be persisted.
+fn content_hash(parts: &[&[u8]]) -> u64 {
+    parts.iter().fold(0xcbf2_9ce4_8422_2325, |hash, part| {
+        // Separates the parts, so that moving text from one part to another changes the hash.
+        part.iter().chain(&[0xff]).fold(hash, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    })
+}
+
+/// The multiple of `step` the score is in, so that small score changes do not count as edits.
+fn score_bucket(score: i64, options: &PollOptions) -> [u8; 8] {
+    options
+        .score_step
+        .filter(|&step| step > 0)
+        .map_or(0, |step| score.div_euclid(step))
+        .to_le_bytes()
+}
+
+fn post_hash(post_view: &PostView, options: &PollOptions) -> u64 {
+    let post = &post_view.post;
+    content_hash(&[
+        post.name.as_bytes(),
+        post.body.as_deref().unwrap_or_default().as_bytes(),
+        post.url
+            .as_ref()
+            .map(|url| url.as_str())
+            .unwrap_or_default()
+            .as_bytes(),
+        &score_bucket(post_view.counts.score, options),
+    ])
+}
+
+fn comment_hash(comment_view: &CommentView, options: &PollOptions) -> u64 {
+    content_hash(&[
+        comment_view.comment.content.as_bytes(),
+        &score_bucket(comment_view.counts.score, options),
+    ])
+}
+
+fn private_message_hash(message_view: &PrivateMessageView, _options: &PollOptions) -> u64 {
+    content_hash(&[message_view.private_message.content.as_bytes()])
+}
+
+fn report_hash(report: &Report, _options: &PollOptions) -> u64 {
+    content_hash(&[report.reason().as_bytes()])
+}
+
+/// Identifies a report. Report ids are private, but serialize as numbers.
+fn report_key(report: &Report) -> String {
+    let (kind, id) = match report {
+        Report::Post(view) => ("post_report", serde_json::to_string(&view.post_report.id)),
+        Report::Comment(view) => (
+            "comment_report",
+            serde_json::to_string(&view.comment_report.id),
+        ),
+    };
+    format!("{kind}:{}", id.unwrap_or_default())
+}
+
+/// Decides which of the fetched items to deliver, oldest first, along with the keys and hashes to store once each is delivered.
+fn poll_events<T>(
+    items: Vec<T>,
+    store: &dyn SeenStore,
+    options: &PollOptions,
+    key: fn(&T) -> String,
+    hash: fn(&T, &PollOptions) -> u64,
+) -> VecDeque<(PollEvent<T>, String, u64)> {
+    items
+        .into_iter()
+        .rev()
+        .filter_map(|item| {
+            let key = key(&item);
+            let hash = hash(&item, options);
+            let event = match store.get(&key) {
+                None => PollEvent::New(item),
+                Some(stored) if options.detect_edits && stored != hash => PollEvent::Updated(item),
+                Some(_) => return None,
+            };
+            Some((event, key, hash))
+        })
+        .collect()
+}
+
+/// Repeatedly fetches the newest items and streams the ones that are new or changed. A failed poll streams its error, and polling continues until `client` is shut down.
+fn poll_stream<'a, T, F, Fut>(
+    client: &'a LemmyClient,
+    fetch: F,
+    store: Arc<dyn SeenStore>,
+    options: PollOptions,
+    key: fn(&T) -> String,
+    hash: fn(&T, &PollOptions) -> u64,
+) -> impl Stream<Item = LemmyResult<PollEvent<T>>> + 'a
+where
+    T: 'a,
+    F: FnMut() -> Fut + 'a,
+    Fut: Future<Output = LemmyResult<Vec<T>>> + 'a,
+{
+    stream::unfold(
+        (fetch, VecDeque::<(PollEvent<T>, String, u64)>::new(), true),
+        move |(mut fetch, mut pending, mut first)| {
+            let store = store.clone();
+            let options = options.clone();
+            async move {
+                loop {
+                    if client.is_shut_down() {
+                        return None;
+                    }
+                    if let Some((event, seen_key, seen_hash)) = pending.pop_front() {
+                        store.set(&seen_key, seen_hash);
+                        return Some((Ok(event), (fetch, pending, first)));
+                    }
+                    if !first {
+                        sleep(options.interval).await;
+                    }
+                    first = false;
+
+                    match fetch().await {
+                        Ok(items) => {
+                            pending = poll_events(items, store.as_ref(), &options, key, hash);
+                        }
+                        Err(_) if client.is_shut_down() => return None,
+                        Err(e) => return Some((Err(e), (fetch, pending, first))),
+                    }
+                }
+            }
+        },
+    )
+}
+
+impl LemmyClient {
+    /// Polls for new posts matching `form`, streaming each post once, oldest first. The sort is always [`SortType::New`], and only the first page is fetched each time.
+    ///
+    /// `store` remembers which posts were delivered. With [`PollOptions::detect_edits`], posts whose title, body, link, or score changed are delivered again as [`PollEvent::Updated`].
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lemmy_client::{LemmyClient, MemorySeenStore, PollEvent, PollOptions};
+    /// use std::sync::Arc;
+    ///
+    /// # async fn example(client: LemmyClient) {
+    /// let options = PollOptions {
+    ///     detect_edits: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut posts = std::pin::pin!(client.poll_posts(
+    ///     Default::default(),
+    ///     Arc::new(MemorySeenStore::default()),
+    ///     options,
+    ///     None,
+    /// ));
+    /// while let Some(Ok(event)) = posts.next().await {
+    ///     match event {
+    ///         PollEvent::New(post_view) => println!("New: {}", post_view.post.name),
+    ///         PollEvent::Updated(post_view) => println!("Edited: {}", post_view.post.name),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn poll_posts(
+        &self,
+        form: GetPosts,
+        store: Arc<dyn SeenStore>,
+        options: PollOptions,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<PollEvent<PostView>>> + '_ {
+        let form = GetPosts {
+            sort: Some(SortType::New),
+            page: None,
+            page_cursor: None,
+            ..form
+        };
+        poll_stream(
+            self,
+            move || {
+                let request = LemmyRequest::with_jwt(form.clone(), jwt.clone());
+                async move {
+                    let response = self.client.list_posts(request, &self.headers).await?;
+                    Ok(response.posts)
+                }
+            },
+            store,
+            options,
+            |post_view| format!("post:{}", post_view.post.id.0),
+            post_hash,
+        )
+    }
+
+    /// Polls for new comments matching `form`, streaming each comment once, oldest first. The sort is always [`CommentSortType::New`], and only the first page is fetched each time.
+    ///
+    /// `store` remembers which comments were delivered. With [`PollOptions::detect_edits`], comments whose content or score changed are delivered again as [`PollEvent::Updated`].
+    pub fn poll_comments(
+        &self,
+        form: GetComments,
+        store: Arc<dyn SeenStore>,
+        options: PollOptions,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<PollEvent<CommentView>>> + '_ {
+        let form = GetComments {
+            sort: Some(CommentSortType::New),
+            page: None,
+            ..form
+        };
+        poll_stream(
+            self,
+            move || {
+                let request = LemmyRequest::with_jwt(form.clone(), jwt.clone());
+                async move {
+                    let response = self.client.list_comments(request, &self.headers).await?;
+                    Ok(response.comments)
+                }
+            },
+            store,
+            options,
+            |comment_view| format!("comment:{}", comment_view.comment.id.0),
+            comment_hash,
+        )
+    }
+
+    /// Polls for new private messages of the logged in user, sent or received, streaming each message once, oldest first. Only the first page is fetched each time.
+    ///
+    /// `store` remembers which messages were delivered. With [`PollOptions::detect_edits`], edited messages are delivered again as [`PollEvent::Updated`]. Add new messages to their [`Conversation`](crate::Conversation) to keep it up to date.
+    pub fn poll_private_messages(
+        &self,
+        store: Arc<dyn SeenStore>,
+        options: PollOptions,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<PollEvent<PrivateMessageView>>> + '_ {
+        poll_stream(
+            self,
+            move || {
+                let request = LemmyRequest::with_jwt(GetPrivateMessages::default(), jwt.clone());
+                async move {
+                    let response = self
+                        .client
+                        .list_private_messages(request, &self.headers)
+                        .await?;
+                    Ok(response.private_messages)
+                }
+            },
+            store,
+            options,
+            // The id of private messages is private, so they are told apart by their ActivityPub id.
+            |message_view| format!("private_message:{}", message_view.private_message.ap_id),
+            private_message_hash,
+        )
+    }
+
+    /// Polls for new unresolved post and comment reports the logged in user can resolve, streaming each report once, oldest first. Only the first page of each kind is fetched each time.
+    ///
+    /// Forward the reports with [`LemmyClient::forward_events`] to be alerted when one comes in.
+    pub fn poll_reports(
+        &self,
+        store: Arc<dyn SeenStore>,
+        options: PollOptions,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<PollEvent<Report>>> + '_ {
+        poll_stream(
+            self,
+            move || {
+                let post_request = LemmyRequest::with_jwt(
+                    ListPostReports {
+                        unresolved_only: Some(true),
+                        ..Default::default()
+                    },
+                    jwt.clone(),
+                );
+                let comment_request = LemmyRequest::with_jwt(
+                    ListCommentReports {
+                        unresolved_only: Some(true),
+                        ..Default::default()
+                    },
+                    jwt.clone(),
+                );
+                async move {
+                    let post_reports = self
+                        .client
+                        .list_post_reports(post_request, &self.headers)
+                        .await?
+                        .post_reports;
+                    let comment_reports = self
+                        .client
+                        .list_comment_reports(comment_request, &self.headers)
+                        .await?
+                        .comment_reports;
+
+                    let mut reports = post_reports
+                        .into_iter()
+                        .map(Report::Post)
+                        .chain(comment_reports.into_iter().map(Report::Comment))
+                        .collect::<Vec<_>>();
+                    reports.sort_by_key(|report| std::cmp::Reverse(report.published()));
+                    Ok(reports)
+                }
+            },
+            store,
+            options,
+            report_key,
+            report_hash,
+        )
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{MetricsRecorder, RequestMetric};
+use prometheus::{CounterVec, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+/// A [`MetricsRecorder`] that exports measurements as Prometheus metrics:
+/// - `lemmy_client_requests_total`, a counter of requests by `endpoint` and `status`. `status` is the HTTP status, or `error` if no response was received.
+/// - `lemmy_client_request_duration_seconds`, a histogram of request latency by `endpoint`.
+/// - `lemmy_client_retries_total`, a counter of retried requests by `endpoint`.
+/// - `lemmy_client_rate_limit_wait_seconds_total`, a counter of the time spent waiting for rate limits by `endpoint`.
+///
+/// ```
+/// use lemmy_client::{ClientOptions, LemmyClient, PrometheusMetrics};
+/// use std::sync::Arc;
+///
+/// let registry = prometheus::Registry::new();
+/// let mut client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// client.set_metrics_recorder(Arc::new(PrometheusMetrics::new(&registry).unwrap()));
+/// ```
+pub struct PrometheusMetrics {
+    requests: IntCounterVec,
+    latency: HistogramVec,
+    retries: IntCounterVec,
+    rate_limit_wait: CounterVec,
+}
+
+impl PrometheusMetrics {
+    /// Creates the metrics and registers them with `registry`.
+    pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+        let metrics = Self {
+            requests: IntCounterVec::new(
+                Opts::new(
+                    "lemmy_client_requests_total",
+                    "Requests sent to Lemmy, by endpoint and HTTP status.",
+                ),
+                &["endpoint", "status"],
+            )?,
+            latency: HistogramVec::new(
+                HistogramOpts::new(
+                    "lemmy_client_request_duration_seconds",
+                    "Time taken by requests to Lemmy, by endpoint.",
+                ),
+                &["endpoint"],
+            )?,
+            retries: IntCounterVec::new(
+                Opts::new(
+                    "lemmy_client_retries_total",
+                    "Requests to Lemmy that were retried, by endpoint.",
+                ),
+                &["endpoint"],
+            )?,
+            rate_limit_wait: CounterVec::new(
+                Opts::new(
+                    "lemmy_client_rate_limit_wait_seconds_total",
+                    "Time spent waiting for rate limits before sending requests to Lemmy, by endpoint.",
+                ),
+                &["endpoint"],
+            )?,
+        };
+
+        registry.register(Box::new(metrics.requests.clone()))?;
+        registry.register(Box::new(metrics.latency.clone()))?;
+        registry.register(Box::new(metrics.retries.clone()))?;
+        registry.register(Box::new(metrics.rate_limit_wait.clone()))?;
+
+        Ok(metrics)
+    }
+
+    /// Creates the metrics and registers them with the [default registry](prometheus::default_registry).
+    pub fn register_default() -> prometheus::Result<Self> {
+        Self::new(prometheus::default_registry())
+    }
+}
+
+impl MetricsRecorder for PrometheusMetrics {
+    fn record_request(&self, metric: &RequestMetric<'_>) {
+        let status = metric
+            .status
+            .map_or_else(|| String::from("error"), |status| status.to_string());
+        self.requests
+            .with_label_values(&[metric.endpoint, &status])
+            .inc();
+        self.latency
+            .with_label_values(&[metric.endpoint])
+            .observe(metric.latency.as_secs_f64());
+    }
+
+    fn record_retry(&self, endpoint: &str) {
+        self.retries.with_label_values(&[endpoint]).inc();
+    }
+
+    fn record_rate_limit_wait(&self, endpoint: &str, wait: Duration) {
+        self.rate_limit_wait
+            .with_label_values(&[endpoint])
+            .inc_by(wait.as_secs_f64());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, ClientOptions, Error,
+    LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    lemmy_db_schema::{ListingType, SearchType, SortType},
+    lemmy_db_views_actor::structs::CommunityView,
+    site::Search,
+};
+use std::collections::{HashMap, HashSet};
+
+/// How many communities are fetched per search.
+const SEARCH_LIMIT: i64 = 20;
+
+/// Reads the subreddit names of a Reddit data export's `subscribed_subreddits.csv`. Also accepts one name per line, with or without an `r/` prefix. Duplicates and the header are dropped.
+pub fn parse_reddit_subscriptions(export: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    export
+        .lines()
+        .filter_map(|line| {
+            // The name is the first column of the CSV.
+            let name = line.split(',').next()?.trim().trim_matches('"').trim();
+            let name = name
+                .strip_prefix("/r/")
+                .or_else(|| name.strip_prefix("r/"))
+                .unwrap_or(name);
+            let valid =
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            (valid && !name.eq_ignore_ascii_case("subreddit")).then(|| name.to_owned())
+        })
+        .filter(|name| seen.insert(name.to_lowercase()))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [`LemmyClient::map_reddit_subscriptions`]. By default, only the client's instance is searched, keeping 5 candidates per subreddit.
+pub struct RedditImportOptions {
+    /// Domains of other instances to search as well, e.g. from [`LemmyClient::federated_instance_list`] or an instance directory. Communities the client's instance has not seen yet are only found this way. The other instances are searched anonymously, with clients [derived](LemmyClient::derive) from this one, so they share its limits, hooks, and dry run.
+    pub extra_instances: Vec<String>,
+    /// How many candidates to keep for each subreddit.
+    pub max_candidates: usize,
+}
+
+impl Default for RedditImportOptions {
+    fn default() -> Self {
+        Self {
+            extra_instances: Vec::new(),
+            max_candidates: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A Lemmy community that may replace a subreddit, as found by [`LemmyClient::map_reddit_subscriptions`].
+pub struct RedditCandidate {
+    /// The ActivityPub ID of the community, which identifies it on every instance.
+    pub ap_id: String,
+    /// The name of the community.
+    pub name: String,
+    /// The display name of the community.
+    pub title: String,
+    /// How many subscribers the community has, as far as the instance that found it knows.
+    pub subscribers: i64,
+    /// How well the community matches the subreddit, from 0 to 1. An exact name match is 1.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The communities found for one subreddit, best first. Review them, and change [`RedditMapping::selected`] before following with [`LemmyClient::follow_reddit_mapping`].
+pub struct RedditMapping {
+    /// The name of the subreddit, without `r/`.
+    pub subreddit: String,
+    /// The candidates, best first.
+    pub candidates: Vec<RedditCandidate>,
+    /// The index of the candidate to follow. Defaults to the best candidate if its name matches the subreddit, and to none otherwise.
+    pub selected: Option<usize>,
+    /// Why searching failed, if it did on every instance.
+    pub error: Option<Error>,
+}
+
+impl RedditMapping {
+    /// The candidate to follow, if one is selected.
+    pub fn selected_candidate(&self) -> Option<&RedditCandidate> {
+        self.candidates.get(self.selected?)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The outcome of [`LemmyClient::follow_reddit_mapping`].
+pub struct RedditFollowReport {
+    /// ActivityPub IDs of communities that were followed.
+    pub followed: Vec<String>,
+    /// Communities that could not be resolved or followed.
+    pub failed: Vec<(String, Error)>,
+}
+
+/// Lowercases a name and drops separators, so that `rust_lang` matches `RustLang`.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How well a community matches a subreddit, from its name and title.
+fn match_score(subreddit: &str, community_view: &CommunityView) -> f64 {
+    let community = &community_view.community;
+    let subreddit_key = normalize_name(subreddit);
+    if community.name.eq_ignore_ascii_case(subreddit) {
+        1.0
+    } else if normalize_name(&community.name) == subreddit_key {
+        0.9
+    } else if normalize_name(&community.name).contains(&subreddit_key)
+        || subreddit_key.contains(&normalize_name(&community.name))
+    {
+        0.6
+    } else if normalize_name(&community.title).contains(&subreddit_key) {
+        0.4
+    } else {
+        0.1
+    }
+}
+
+impl LemmyClient {
+    /// Searches for Lemmy communities to replace each subreddit, e.g. from [`parse_reddit_subscriptions`], ranking them by how well their name matches and then by subscribers. Nothing is followed, so users can confirm the mapping first.
+    ///
+    /// A search that fails on one instance is skipped, and only reported in [`RedditMapping::error`] if it failed on every instance.
+    ///
+    /// ```no_run
+    /// use lemmy_client::{parse_reddit_subscriptions, LemmyClient, RedditImportOptions};
+    ///
+    /// # async fn example(client: LemmyClient, jwt: String) -> Result<(), lemmy_client::Error> {
+    /// let export = std::fs::read_to_string("subscribed_subreddits.csv").unwrap();
+    /// let subreddits = parse_reddit_subscriptions(&export);
+    /// let mapping = client
+    ///     .map_reddit_subscriptions(&subreddits, &RedditImportOptions::default(), Some(jwt.clone()))
+    ///     .await;
+    /// for entry in &mapping {
+    ///     if let Some(candidate) = entry.selected_candidate() {
+    ///         println!("r/{} → {}", entry.subreddit, candidate.ap_id);
+    ///     }
+    /// }
+    /// let report = client.follow_reddit_mapping(&mapping, Some(jwt)).await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn map_reddit_subscriptions(
+        &self,
+        subreddits: &[String],
+        options: &RedditImportOptions,
+        jwt: Option<String>,
+    ) -> Vec<RedditMapping> {
+        let extra_clients = options
+            .extra_instances
+            .iter()
+            .filter(|domain| !domain.eq_ignore_ascii_case(&self.client_options().domain))
+            .map(|domain| {
+                // Shares the limits, hooks, and dry run of this client, but not its credentials.
+                self.derive(|options| {
+                    options.client_options = ClientOptions {
+                        domain: domain.clone(),
+                        secure: true,
+                    };
+                    options.headers = self.third_party_headers().into_owned();
+                    options.jwt = None;
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut mappings = Vec::with_capacity(subreddits.len());
+        for subreddit in subreddits {
+            let mut found = HashMap::<String, RedditCandidate>::new();
+            let mut last_error = None;
+            let mut any_succeeded = false;
+            let searches = std::iter::once((self, jwt.clone()))
+                .chain(extra_clients.iter().map(|client| (client, None)));
+            for (client, jwt) in searches {
+                match client.search_communities_named(subreddit, jwt).await {
+                    Ok(communities) => {
+                        any_succeeded = true;
+                        for community_view in communities {
+                            let candidate = RedditCandidate {
+                                ap_id: community_view.community.actor_id.to_string(),
+                                name: community_view.community.name.clone(),
+                                title: community_view.community.title.clone(),
+                                subscribers: community_view.counts.subscribers,
+                                score: match_score(subreddit, &community_view),
+                            };
+                            // Instances count the subscribers they know of, so the highest count is the closest.
+                            found
+                                .entry(candidate.ap_id.clone())
+                                .and_modify(|existing| {
+                                    existing.subscribers =
+                                        existing.subscribers.max(candidate.subscribers)
+                                })
+                                .or_insert(candidate);
+                        }
+                    }
+                    Err(e) => last_error = Some(e),
+                }
+            }
+
+            let mut candidates = found.into_values().collect::<Vec<_>>();
+            candidates.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then(b.subscribers.cmp(&a.subscribers))
+            });
+            candidates.truncate(options.max_candidates);
+            let selected = candidates
+                .first()
+                .is_some_and(|best| best.score >= 0.9)
+                .then_some(0);
+
+            mappings.push(RedditMapping {
+                subreddit: subreddit.clone(),
+                candidates,
+                selected,
+                error: last_error.filter(|_| !any_succeeded),
+            });
+        }
+
+        mappings
+    }
+
+    /// Follows the selected candidate of each mapping returned by [`LemmyClient::map_reddit_subscriptions`]. Communities from other instances are fetched with [`LemmyClient::resolve_object`] first.
+    pub async fn follow_reddit_mapping(
+        &self,
+        mapping: &[RedditMapping],
+        jwt: Option<String>,
+    ) -> RedditFollowReport {
+        let mut report = RedditFollowReport::default();
+        for candidate in mapping.iter().filter_map(RedditMapping::selected_candidate) {
+            match self.follow_by_ap_id(&candidate.ap_id, jwt.clone()).await {
+                Ok(()) => report.followed.push(candidate.ap_id.clone()),
+                Err(e) => report.failed.push((candidate.ap_id.clone(), e)),
+            }
+        }
+
+        report
+    }
+
+    async fn search_communities_named(
+        &self,
+        query: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<Vec<CommunityView>> {
+        let form = Search {
+            q: query.to_owned(),
+            type_: Some(SearchType::Communities),
+            listing_type: Some(ListingType::All),
+            sort: Some(SortType::TopAll),
+            limit: Some(SEARCH_LIMIT),
+            ..Default::default()
+        };
+        Ok(self
+            .client
+            .search(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await?
+            .communities)
+    }
+}
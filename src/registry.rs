@@ -0,0 +1,112 @@
+use crate::ENDPOINTS;
+use http::Method;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// Who is allowed to call an endpoint.
+pub enum AuthRequirement {
+    /// The endpoint is meant to be used without logging in.
+    None,
+    /// Anyone can call the endpoint, but logged in users may get a personalized response.
+    Optional,
+    /// Any logged in user can call the endpoint.
+    User,
+    /// Only moderators of the community, and admins, can call the endpoint.
+    Moderator,
+    /// Only admins can call the endpoint.
+    Admin,
+}
+
+impl AuthRequirement {
+    /// Returns true if the endpoint fails without a JWT.
+    pub fn requires_login(&self) -> bool {
+        matches!(self, Self::User | Self::Moderator | Self::Admin)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A Lemmy version.
+pub struct ApiVersion {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u16,
+    /// The patch version.
+    pub patch: u16,
+}
+
+impl ApiVersion {
+    /// Creates a version from its parts.
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses a version such as `0.19.3`, as found in [`GetSiteResponse::version`](lemmy_api_common::site::GetSiteResponse::version). Anything after the patch version, such as `-rc.1`, is ignored.
+    pub fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.trim_start_matches('v').splitn(3, '.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next()?;
+        let patch = patch[..patch
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(patch.len())]
+            .parse()
+            .ok()?;
+
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Information about an endpoint supported by this crate.
+pub struct EndpointInfo {
+    /// The name of the method for the endpoint, such as `get_site`.
+    pub name: &'static str,
+    /// The HTTP method used.
+    pub method: Method,
+    /// The path of the endpoint relative to `/api/v3`.
+    pub path: &'static str,
+    /// Who is allowed to call the endpoint.
+    pub auth: AuthRequirement,
+    /// The first Lemmy version with the endpoint.
+    pub min_version: ApiVersion,
+}
+
+impl EndpointInfo {
+    /// Returns true if an instance running `version` has the endpoint.
+    pub fn supported_by(&self, version: ApiVersion) -> bool {
+        version >= self.min_version
+    }
+}
+
+/// Finds an endpoint by the name of its method.
+pub fn endpoint(name: &str) -> Option<&'static EndpointInfo> {
+    ENDPOINTS.iter().find(|endpoint| endpoint.name == name)
+}
+
+/// Lists the endpoints that an instance running `version` has.
+pub fn endpoints_supported_by(version: ApiVersion) -> impl Iterator<Item = &'static EndpointInfo> {
+    ENDPOINTS
+        .iter()
+        .filter(move |endpoint| endpoint.supported_by(version))
+}
+
+/// Gets the name of the endpoint with the given method and path, or the path itself for endpoints this crate does not wrap.
+pub(crate) fn endpoint_name<'a>(method: &Method, path: &'a str) -> &'a str {
+    ENDPOINTS
+        .iter()
+        .find(|endpoint| endpoint.method == method && endpoint.path == path)
+        .map_or(path, |endpoint| endpoint.name)
+}
@@ -0,0 +1,254 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::sleep, Error,
+    LemmyClient, LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::{
+    comment::{ListCommentReports, ResolveCommentReport},
+    lemmy_db_schema::{
+        newtypes::{CommentReportId, CommunityId, PersonId, PostReportId},
+        source::{community::Community, person::Person},
+    },
+    lemmy_db_views::structs::{CommentReportView, PostReportView},
+    post::{ListPostReports, ResolvePostReport},
+};
+use std::time::Duration;
+
+/// How many reports are fetched at a time.
+const REPORT_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone)]
+/// A report of a post or comment.
+pub enum Report {
+    /// A report of a post.
+    Post(PostReportView),
+    /// A report of a comment.
+    Comment(CommentReportView),
+}
+
+impl Report {
+    /// The person who made the report.
+    pub fn reporter(&self) -> &Person {
+        match self {
+            Self::Post(view) => &view.creator,
+            Self::Comment(view) => &view.creator,
+        }
+    }
+
+    /// The person whose post or comment was reported.
+    pub fn reported(&self) -> &Person {
+        match self {
+            Self::Post(view) => &view.post_creator,
+            Self::Comment(view) => &view.comment_creator,
+        }
+    }
+
+    /// The community the post or comment is in.
+    pub fn community(&self) -> &Community {
+        match self {
+            Self::Post(view) => &view.community,
+            Self::Comment(view) => &view.community,
+        }
+    }
+
+    /// Why the post or comment was reported.
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Post(view) => &view.post_report.reason,
+            Self::Comment(view) => &view.comment_report.reason,
+        }
+    }
+
+    /// The content of the post or comment when it was reported: the title and body of posts, and the text of comments.
+    pub fn reported_content(&self) -> String {
+        match self {
+            Self::Post(view) => format!(
+                "{}\n{}",
+                view.post_report.original_post_name,
+                view.post_report
+                    .original_post_body
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
+            Self::Comment(view) => view.comment_report.original_comment_text.clone(),
+        }
+    }
+
+    /// When the report was made.
+    pub fn published(&self) -> DateTime<Utc> {
+        match self {
+            Self::Post(view) => view.post_report.published,
+            Self::Comment(view) => view.comment_report.published,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Conditions for picking reports, e.g. for [`LemmyClient::resolve_reports_matching`]. Reports have to match every condition that is set.
+pub struct ReportFilter {
+    /// Only reports made by this person.
+    pub reporter: Option<PersonId>,
+    /// Only reports of posts and comments by this person.
+    pub reported: Option<PersonId>,
+    /// Only reports in this community.
+    pub community_id: Option<CommunityId>,
+    /// Only reports made before this time.
+    pub published_before: Option<DateTime<Utc>>,
+    /// Only reports whose reason or reported content contains this text. Compared case-insensitively.
+    pub keyword: Option<String>,
+}
+
+impl ReportFilter {
+    /// Returns true if `report` matches every condition of the filter.
+    pub fn matches(&self, report: &Report) -> bool {
+        let keyword = self.keyword.as_deref().map(str::to_lowercase);
+        self.reporter.is_none_or(|id| report.reporter().id == id)
+            && self.reported.is_none_or(|id| report.reported().id == id)
+            && self
+                .community_id
+                .is_none_or(|id| report.community().id == id)
+            && self
+                .published_before
+                .is_none_or(|before| report.published() < before)
+            && keyword.is_none_or(|keyword| {
+                report.reason().to_lowercase().contains(&keyword)
+                    || report.reported_content().to_lowercase().contains(&keyword)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The outcome of [`LemmyClient::resolve_reports_matching`].
+pub struct ReportResolutionSummary {
+    /// Post reports that were resolved.
+    pub resolved_posts: Vec<PostReportId>,
+    /// Comment reports that were resolved.
+    pub resolved_comments: Vec<CommentReportId>,
+    /// Number of unresolved reports that did not match.
+    pub skipped: usize,
+    /// Reports that matched but could not be resolved.
+    pub failed: Vec<(Report, Error)>,
+}
+
+impl ReportResolutionSummary {
+    /// Total number of reports that were resolved.
+    pub fn resolved(&self) -> usize {
+        self.resolved_posts.len() + self.resolved_comments.len()
+    }
+}
+
+impl LemmyClient {
+    /// Gets every unresolved post and comment report the logged in user can resolve.
+    pub async fn unresolved_reports(&self, jwt: Option<String>) -> LemmyResult<Vec<Report>> {
+        let mut reports = Vec::new();
+        for page in 1.. {
+            let form = ListPostReports {
+                page: Some(page),
+                limit: Some(REPORT_PAGE_SIZE),
+                unresolved_only: Some(true),
+                ..Default::default()
+            };
+            let post_reports = self
+                .client
+                .list_post_reports(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                .await?
+                .post_reports;
+            if post_reports.is_empty() {
+                break;
+            }
+            reports.extend(post_reports.into_iter().map(Report::Post));
+        }
+        for page in 1.. {
+            let form = ListCommentReports {
+                page: Some(page),
+                limit: Some(REPORT_PAGE_SIZE),
+                unresolved_only: Some(true),
+                ..Default::default()
+            };
+            let comment_reports = self
+                .client
+                .list_comment_reports(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+                .await?
+                .comment_reports;
+            if comment_reports.is_empty() {
+                break;
+            }
+            reports.extend(comment_reports.into_iter().map(Report::Comment));
+        }
+
+        Ok(reports)
+    }
+
+    /// Resolves every unresolved report for which `predicate` returns true, such as [`ReportFilter::matches`], e.g. to clear out reports after a spam wave.
+    ///
+    /// Every unresolved report is fetched before any is resolved, so resolving reports does not shift the pages being read. `request_delay` is waited between resolutions to stay under the instance's rate limits. A failure to resolve a report is recorded in the summary, and does not stop the others from being resolved.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::{LemmyClient, ReportFilter};
+    /// # use std::time::Duration;
+    /// # async fn example(client: LemmyClient, spammer: lemmy_client::lemmy_api_common::lemmy_db_schema::newtypes::PersonId) -> Result<(), lemmy_client::Error> {
+    /// let filter = ReportFilter {
+    ///     reported: Some(spammer),
+    ///     ..Default::default()
+    /// };
+    /// let summary = client
+    ///     .resolve_reports_matching(|report| filter.matches(report), Duration::from_millis(250), None)
+    ///     .await?;
+    /// println!("Resolved {} reports", summary.resolved());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_reports_matching(
+        &self,
+        predicate: impl Fn(&Report) -> bool,
+        request_delay: Duration,
+        jwt: Option<String>,
+    ) -> LemmyResult<ReportResolutionSummary> {
+        let mut summary = ReportResolutionSummary::default();
+        let mut first = true;
+        for report in self.unresolved_reports(jwt.clone()).await? {
+            if !predicate(&report) {
+                summary.skipped += 1;
+                continue;
+            }
+            if !first {
+                sleep(request_delay).await;
+            }
+            first = false;
+
+            let result = match &report {
+                Report::Post(view) => {
+                    let form = ResolvePostReport {
+                        report_id: view.post_report.id,
+                        resolved: true,
+                    };
+                    self.client
+                        .resolve_post_report(
+                            LemmyRequest::with_jwt(form, jwt.clone()),
+                            &self.headers,
+                        )
+                        .await
+                        .map(|_| summary.resolved_posts.push(view.post_report.id))
+                }
+                Report::Comment(view) => {
+                    let form = ResolveCommentReport {
+                        report_id: view.comment_report.id,
+                        resolved: true,
+                    };
+                    self.client
+                        .resolve_comment_report(
+                            LemmyRequest::with_jwt(form, jwt.clone()),
+                            &self.headers,
+                        )
+                        .await
+                        .map(|_| summary.resolved_comments.push(view.comment_report.id))
+                }
+            };
+            if let Err(e) = result {
+                summary.failed.push((report, e));
+            }
+        }
+
+        Ok(summary)
+    }
+}
@@ -6,11 +6,36 @@ use lemmy_api_common::{
 use serde::Deserialize;
 
 #[cfg(feature = "leptos")]
+/// A type that a response can be deserialized into.
+///
+/// Implemented for every response in [`lemmy_api_common`]. Implement it for your own types to receive them with [`LemmyClient::send_custom`](crate::LemmyClient::send_custom), or with the `_as` method of an endpoint, such as [`LemmyClient::get_site_as`](crate::LemmyClient::get_site_as). With the `leptos` feature enabled, the type must also implement [`leptos::Serializable`].
 pub trait LemmyResponse: leptos::Serializable + for<'de> Deserialize<'de> {}
 
 #[cfg(not(feature = "leptos"))]
+/// A type that a response can be deserialized into.
+///
+/// Implemented for every response in [`lemmy_api_common`]. Implement it for your own types to receive them with [`LemmyClient::send_custom`](crate::LemmyClient::send_custom), or with the `_as` method of an endpoint, which keeps its path and authentication. This helps with instances whose responses differ from the version of [`lemmy_api_common`] this crate uses:
+///
+/// ```no_run
+/// use lemmy_client::{LemmyClient, LemmyResponse};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct MyGetSiteResponse {
+///     version: String,
+/// }
+///
+/// impl LemmyResponse for MyGetSiteResponse {}
+///
+/// # async fn example(client: LemmyClient) -> Result<(), lemmy_client::Error> {
+/// let site = client.get_site_as::<MyGetSiteResponse>().await?;
+/// println!("Running Lemmy {}", site.version);
+/// # Ok(())
+/// # }
+/// ```
 pub trait LemmyResponse: for<'de> Deserialize<'de> {}
 
+/// The result of a request made with a [`LemmyClient`](crate::LemmyClient).
 pub type LemmyResult<R> = Result<R, Error>;
 
 impl_marker_trait!(
@@ -0,0 +1,91 @@
+use crate::{ApiFuture, Error, ErrorKind, LemmyClient};
+use http::Method;
+use std::{sync::Arc, time::Duration};
+
+/// Decides whether a failed write may be sent again, e.g. by searching for the post a failed [`LemmyClient::create_post`] would have created. Set it with [`RetryPolicy::write_check`].
+///
+/// Writes can fail after the instance has already applied them, for example when the connection drops before the response arrives. Sending them again would then create a duplicate.
+pub trait WriteRetryCheck: Send + Sync {
+    /// Called before a write to `endpoint` that failed with `error` is sent again. Resolving to `false`, or to an error, stops retrying and returns `error`.
+    fn should_resend<'a>(&'a self, endpoint: &'a str, error: &'a Error) -> ApiFuture<'a, bool>;
+}
+
+#[derive(Clone)]
+/// When and how often a client sends failed requests again. Set it with [`LemmyClient::set_retry_policy`].
+///
+/// Only requests that failed because of the network, a rate limit, or a server error returned by a proxy in front of the instance are retried, waiting longer before each attempt.
+///
+/// `GET` requests are always safe to send again, so they are the only ones retried by default. Writes are only retried if they are opted into with [`RetryPolicy::retry_writes`] or [`RetryPolicy::retried_writes`], and the [`RetryPolicy::write_check`] allows it.
+///
+/// ```
+/// use lemmy_client::{LemmyClient, ClientOptions, RetryPolicy};
+///
+/// let mut client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// client.set_retry_policy(Some(RetryPolicy {
+///     // Votes are idempotent, so sending them twice is harmless.
+///     retried_writes: vec!["like_post", "like_comment"],
+///     ..Default::default()
+/// }));
+/// ```
+pub struct RetryPolicy {
+    /// How many times a request is sent again at most.
+    pub max_retries: u32,
+    /// How long to wait before the first retry. The wait doubles with each retry.
+    pub base_delay: Duration,
+    /// The longest wait between two attempts.
+    pub max_delay: Duration,
+    /// Retry every `POST` and `PUT` request.
+    pub retry_writes: bool,
+    /// Names of the endpoints whose `POST` and `PUT` requests are retried, such as `like_post`. See [`ENDPOINTS`](crate::ENDPOINTS).
+    pub retried_writes: Vec<&'static str>,
+    /// Checked before a write is sent again.
+    pub write_check: Option<Arc<dyn WriteRetryCheck>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retry_writes: false,
+            retried_writes: Vec::new(),
+            write_check: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether requests to `endpoint` with `method` may be retried at all.
+    pub(crate) fn allows(&self, method: &Method, endpoint: &str) -> bool {
+        method == Method::GET || self.retry_writes || self.retried_writes.contains(&endpoint)
+    }
+
+    /// How long to wait before retry number `attempt`, counting from zero.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .checked_mul(2_u32.saturating_pow(attempt))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// Whether a request that failed with `error` might succeed if sent again.
+pub(crate) fn is_transient(error: &Error) -> bool {
+    match error.kind() {
+        ErrorKind::Network => true,
+        ErrorKind::Api => {
+            error.message() == "rate_limit_error" || error.message().starts_with("http_status_5")
+        }
+        _ => false,
+    }
+}
+
+impl LemmyClient {
+    /// Sets when failed requests are sent again. [`None`] disables retries, which is the default. See [`RetryPolicy`].
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.client.hooks.retry = policy;
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{
+    endpoints::with_endpoints, lemmy_client_trait::LemmyClientInternal, Error, LemmyClient,
+    LemmyForm, LemmyRequest, LemmyResult,
+};
+use lemmy_api_common::{
+    comment::*, community::*, custom_emoji::*, lemmy_db_schema::newtypes::CommunityId, person::*,
+    post::*, private_message::*, site::*, SuccessResponse,
+};
+
+#[derive(Clone, Copy)]
+/// The endpoints only admins can call, for a user that was checked to be an admin. Get one with [`LemmyClient::admin`].
+///
+/// Admins can also do everything moderators can, in every community.
+///
+/// ```no_run
+/// # use lemmy_client::LemmyClient;
+/// # use lemmy_client::lemmy_api_common::site::PurgePerson;
+/// # async fn example(client: LemmyClient, jwt: String, form: PurgePerson) -> Result<(), lemmy_client::Error> {
+/// // Fails with `not_an_admin` for other users, before anything is purged.
+/// let admin = client.admin(&jwt).await?;
+/// admin.purge_person(form).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AdminClient<'a> {
+    client: &'a LemmyClient,
+    jwt: &'a str,
+}
+
+#[derive(Clone, Copy)]
+/// The endpoints only moderators can call, for a user that was checked to moderate a community. Get one with [`LemmyClient::moderator`] or [`AdminClient::moderator`].
+///
+/// The instance still checks that every request is about the community the user moderates.
+pub struct ModClient<'a> {
+    client: &'a LemmyClient,
+    jwt: &'a str,
+    community_id: CommunityId,
+}
+
+impl LemmyClient {
+    /// Checks that the user of `jwt` is an admin, failing with `not_an_admin` if they are not.
+    ///
+    /// HTTP GET /site
+    pub async fn admin<'a>(&'a self, jwt: &'a str) -> LemmyResult<AdminClient<'a>> {
+        let my_user = self.my_user(Some(jwt.to_owned())).await?;
+        if !my_user.local_user_view.local_user.admin {
+            return Err(Error::new("not_an_admin"));
+        }
+
+        Ok(AdminClient { client: self, jwt })
+    }
+
+    /// Checks that the user of `jwt` moderates `community_id`, or is an admin, failing with `not_a_mod_or_admin` otherwise.
+    ///
+    /// HTTP GET /site
+    pub async fn moderator<'a>(
+        &'a self,
+        community_id: CommunityId,
+        jwt: &'a str,
+    ) -> LemmyResult<ModClient<'a>> {
+        let my_user = self.my_user(Some(jwt.to_owned())).await?;
+        let moderates = my_user
+            .moderates
+            .iter()
+            .any(|moderator_view| moderator_view.community.id == community_id);
+        if !moderates && !my_user.local_user_view.local_user.admin {
+            return Err(Error::new("not_a_mod_or_admin"));
+        }
+
+        Ok(ModClient {
+            client: self,
+            jwt,
+            community_id,
+        })
+    }
+}
+
+impl<'a> AdminClient<'a> {
+    /// The moderator endpoints for `community_id`. Admins can moderate every community, so this is not checked.
+    pub fn moderator(&self, community_id: CommunityId) -> ModClient<'a> {
+        ModClient {
+            client: self.client,
+            jwt: self.jwt,
+            community_id,
+        }
+    }
+
+    /// The client the view was made from.
+    pub fn client(&self) -> &'a LemmyClient {
+        self.client
+    }
+}
+
+impl<'a> ModClient<'a> {
+    /// The community the user was checked to moderate.
+    pub fn community_id(&self) -> CommunityId {
+        self.community_id
+    }
+
+    /// The client the view was made from.
+    pub fn client(&self) -> &'a LemmyClient {
+        self.client
+    }
+}
+
+macro_rules! role_fn {
+    ($name:ident, (), $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
+        pub async fn $name(&self) -> LemmyResult<$response> {
+            self.client
+                .client
+                .$name(LemmyRequest::from_jwt(Some(self.jwt.to_owned())), &self.client.headers)
+                .await
+        }
+    };
+    ($name:ident, $form:ty, $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
+        pub async fn $name(&self, form: $form) -> LemmyResult<$response> {
+            if self.client.validate_forms {
+                LemmyForm::validate(&form)?;
+            }
+
+            self.client
+                .client
+                .$name(
+                    LemmyRequest::with_jwt(form, Some(self.jwt.to_owned())),
+                    &self.client.headers,
+                )
+                .await
+        }
+    };
+}
+
+/// Generates the method of an endpoint for admins, if the endpoint needs the user to be an admin or a moderator.
+macro_rules! admin_fn {
+    (Admin, $($rest:tt)*) => { role_fn!($($rest)*); };
+    (Moderator, $($rest:tt)*) => { role_fn!($($rest)*); };
+    ($auth:ident, $($rest:tt)*) => {};
+}
+
+/// Generates the method of an endpoint for moderators, if the endpoint needs the user to be a moderator.
+macro_rules! mod_fn {
+    (Moderator, $($rest:tt)*) => { role_fn!($($rest)*); };
+    ($auth:ident, $($rest:tt)*) => {};
+}
+
+macro_rules! role_fns {
+    ($(
+        $(#[$doc:meta])*
+        $name:ident($variant:ident):
+        $method:ident $path:literal, $auth:ident, ($major:literal, $minor:literal, $patch:literal):
+        $form:tt => $response:ty;
+    )+) => {
+        impl AdminClient<'_> {
+            $( admin_fn!($auth, $name, $form, $response, $method, $path, $(#[$doc])*); )+
+        }
+
+        impl ModClient<'_> {
+            $( mod_fn!($auth, $name, $form, $response, $method, $path, $(#[$doc])*); )+
+        }
+    };
+}
+
+with_endpoints!(role_fns);
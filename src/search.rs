@@ -0,0 +1,216 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::paged_stream, Error,
+    LemmyClient, LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lemmy_api_common::{
+    lemmy_db_schema::{
+        newtypes::{CommunityId, PersonId},
+        ListingType, SearchType, SortType,
+    },
+    lemmy_db_views::structs::{CommentView, PostView},
+    lemmy_db_views_actor::structs::{CommunityView, PersonView},
+    site::{Search, SearchResponse},
+};
+
+#[derive(Debug, Clone)]
+/// A single search result of any type.
+pub enum SearchItem {
+    /// A post.
+    Post(PostView),
+    /// A comment.
+    Comment(CommentView),
+    /// A community.
+    Community(CommunityView),
+    /// A user.
+    Person(PersonView),
+}
+
+impl SearchItem {
+    /// When the post, comment, community, or user was published.
+    pub fn published(&self) -> DateTime<Utc> {
+        match self {
+            Self::Post(post_view) => post_view.post.published,
+            Self::Comment(comment_view) => comment_view.comment.published,
+            Self::Community(community_view) => community_view.community.published,
+            Self::Person(person_view) => person_view.person.published,
+        }
+    }
+}
+
+/// Merges the separate lists of a [`SearchResponse`] into one list.
+///
+/// When `sort` is [`SortType::New`] or [`SortType::Old`], the results are ordered by when they were published. Otherwise the lists are interleaved, so the order the API ranked each type of result in is kept.
+pub fn merge_search_results(response: SearchResponse, sort: Option<SortType>) -> Vec<SearchItem> {
+    let mut lists = [
+        response
+            .posts
+            .into_iter()
+            .map(SearchItem::Post)
+            .collect::<Vec<_>>(),
+        response
+            .comments
+            .into_iter()
+            .map(SearchItem::Comment)
+            .collect(),
+        response
+            .communities
+            .into_iter()
+            .map(SearchItem::Community)
+            .collect(),
+        response.users.into_iter().map(SearchItem::Person).collect(),
+    ]
+    .map(|list| list.into_iter());
+
+    let mut items = Vec::new();
+    loop {
+        let len = items.len();
+        items.extend(lists.iter_mut().filter_map(Iterator::next));
+        if items.len() == len {
+            break;
+        }
+    }
+
+    match sort {
+        Some(SortType::New) => items.sort_by_key(|item| std::cmp::Reverse(item.published())),
+        Some(SortType::Old) => items.sort_by_key(SearchItem::published),
+        _ => {}
+    }
+
+    items
+}
+
+/// The most results Lemmy returns per page.
+const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone)]
+/// Builds a [`Search`] form.
+/// ```
+/// use lemmy_client::{
+///     lemmy_api_common::lemmy_db_schema::{newtypes::CommunityId, SearchType, SortType},
+///     SearchBuilder,
+/// };
+///
+/// let form = SearchBuilder::new("rust")
+///     .in_community(CommunityId(3))
+///     .type_(SearchType::Posts)
+///     .sort(SortType::TopMonth)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct SearchBuilder {
+    form: Search,
+}
+
+impl SearchBuilder {
+    /// Starts a search for `query` across all types of content on all instances, sorted by top of all time.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            form: Search {
+                q: query.into(),
+                type_: Some(SearchType::All),
+                sort: Some(SortType::TopAll),
+                listing_type: Some(ListingType::All),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Only searches a community, identified by its ID.
+    pub fn in_community(mut self, community_id: CommunityId) -> Self {
+        self.form.community_id = Some(community_id);
+        self
+    }
+
+    /// Only searches a community, identified by its name, e.g. `rust@lemmy.ml`.
+    pub fn in_community_named(mut self, community_name: impl Into<String>) -> Self {
+        self.form.community_name = Some(community_name.into());
+        self
+    }
+
+    /// Only searches content created by a user.
+    pub fn creator(mut self, creator_id: PersonId) -> Self {
+        self.form.creator_id = Some(creator_id);
+        self
+    }
+
+    /// Sets the type of content to search for.
+    pub fn type_(mut self, type_: SearchType) -> Self {
+        self.form.type_ = Some(type_);
+        self
+    }
+
+    /// Sets how results are sorted.
+    pub fn sort(mut self, sort: SortType) -> Self {
+        self.form.sort = Some(sort);
+        self
+    }
+
+    /// Sets which instances' content is searched.
+    pub fn listing_type(mut self, listing_type: ListingType) -> Self {
+        self.form.listing_type = Some(listing_type);
+        self
+    }
+
+    /// Sets the page of results to get, starting at 1.
+    pub fn page(mut self, page: i64) -> Self {
+        self.form.page = Some(page);
+        self
+    }
+
+    /// Sets how many results of each type to get per page, at most 50.
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.form.limit = Some(limit);
+        self
+    }
+
+    /// Checks the options and builds the form.
+    ///
+    /// Fails with `empty_search_query` if the query is blank, `conflicting_search_community` if the community is set both by ID and by name, `invalid_page` if the page is below 1, or `invalid_limit` if the limit is not between 1 and 50.
+    pub fn build(self) -> Result<Search, Error> {
+        let form = self.form;
+        if form.q.trim().is_empty() {
+            return Err(Error::new("empty_search_query"));
+        }
+        if form.community_id.is_some() && form.community_name.is_some() {
+            return Err(Error::new("conflicting_search_community"));
+        }
+        if form.page.is_some_and(|page| page < 1) {
+            return Err(Error::new("invalid_page"));
+        }
+        if form
+            .limit
+            .is_some_and(|limit| !(1..=MAX_SEARCH_LIMIT).contains(&limit))
+        {
+            return Err(Error::new("invalid_limit"));
+        }
+
+        Ok(form)
+    }
+}
+
+impl LemmyClient {
+    /// Streams the merged results of a search, fetching pages as needed. See [`merge_search_results`].
+    ///
+    /// Starts at `form.page` if it is set. The stream ends at the first empty page.
+    pub fn search_stream(
+        &self,
+        form: Search,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<SearchItem>> + '_ {
+        let sort = form.sort;
+
+        paged_stream(form.page.unwrap_or(1), move |page| {
+            let form = Search {
+                page: Some(page),
+                ..form.clone()
+            };
+            let request = LemmyRequest::with_jwt(form, jwt.clone());
+            async move {
+                let response = self.client.search(request, &self.headers).await?;
+                Ok(merge_search_results(response, sort))
+            }
+        })
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{utils::sleep, Error, LemmyClient, LemmyResult};
+use event_listener::Event;
+use futures::future::{select, Either};
+use std::{
+    pin::pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[derive(Debug, Default)]
+/// Tracks whether a client still accepts requests, and how many of its requests are in flight.
+pub(crate) struct Lifecycle {
+    closed: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Event,
+}
+
+/// Marks a request as in flight until dropped.
+pub(crate) struct InFlight(Arc<Lifecycle>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify(usize::MAX);
+        }
+    }
+}
+
+impl Lifecycle {
+    /// Registers a new request, failing if the client was shut down.
+    pub(crate) fn start_request(self: &Arc<Self>) -> LemmyResult<InFlight> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        // Created before checking so that a rejected request is unregistered again when dropped.
+        let in_flight = InFlight(self.clone());
+        if self.is_shut_down() {
+            return Err(Error::new("client_shut_down"));
+        }
+
+        Ok(in_flight)
+    }
+
+    /// Whether [`LemmyClient::shutdown`] was called. Long-running tasks started by the client stop when this is set.
+    pub(crate) fn is_shut_down(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    async fn drained(&self) {
+        while self.in_flight.load(Ordering::SeqCst) > 0 {
+            let listener = self.idle.listen();
+            if self.in_flight.load(Ordering::SeqCst) > 0 {
+                listener.await;
+            }
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Shuts the client down, for bots and services that need to exit cleanly, e.g. on `SIGTERM`.
+    ///
+    /// Once called, every new request fails with a `client_shut_down` error, and the streams and other long-running tasks started by the client end, rather than streaming that error. Requests already in flight are given up to `timeout` to finish, and the client's caches are cleared.
+    ///
+    /// Returns `true` if every request in flight finished before the timeout. Clients sharing a [`DispatchQueue`](crate::DispatchQueue) are not affected.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        let lifecycle = &self.client.hooks.lifecycle;
+        lifecycle.closed.store(true, Ordering::SeqCst);
+        self.clear_site_metadata_cache();
+
+        let drained = pin!(lifecycle.drained());
+        let timed_out = pin!(sleep(timeout));
+        matches!(select(drained, timed_out).await, Either::Left(_))
+    }
+
+    /// Whether [`LemmyClient::shutdown`] was called on this client.
+    pub fn is_shut_down(&self) -> bool {
+        self.client.hooks.lifecycle.is_shut_down()
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    lemmy_client_trait::private_trait::LemmyClientInternal, response::LemmyResult, Error,
+    LemmyClient, PollEvent, Report,
+};
+use futures::{Stream, StreamExt};
+use http::Method;
+use lemmy_api_common::lemmy_db_views::structs::{CommentView, PostView, PrivateMessageView};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How much of a post, comment, or message is put in a [`SinkMessage`], in characters.
+const MAX_BODY_CHARS: usize = 1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+/// An event formatted for an [`EventSink`]. Posts, comments, private messages, and reports, as streamed by pollers such as [`LemmyClient::poll_reports`], convert into it.
+pub struct SinkMessage {
+    /// A one line summary.
+    pub title: String,
+    /// The text of the event, shortened if it is long.
+    pub body: String,
+    /// A link to the content, if it has one.
+    pub link: Option<String>,
+}
+
+/// Shortens text to `max` characters, ending it with an ellipsis if anything was cut.
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_owned(),
+    }
+}
+
+impl From<&PostView> for SinkMessage {
+    fn from(post_view: &PostView) -> Self {
+        Self {
+            title: format!(
+                "New post in {}: {}",
+                post_view.community.name, post_view.post.name
+            ),
+            body: truncate(
+                post_view.post.body.as_deref().unwrap_or_default(),
+                MAX_BODY_CHARS,
+            ),
+            link: Some(post_view.post.ap_id.to_string()),
+        }
+    }
+}
+
+impl From<&CommentView> for SinkMessage {
+    fn from(comment_view: &CommentView) -> Self {
+        Self {
+            title: format!(
+                "New comment by {} on {}",
+                comment_view.creator.name, comment_view.post.name
+            ),
+            body: truncate(&comment_view.comment.content, MAX_BODY_CHARS),
+            link: Some(comment_view.comment.ap_id.to_string()),
+        }
+    }
+}
+
+impl From<&PrivateMessageView> for SinkMessage {
+    fn from(message_view: &PrivateMessageView) -> Self {
+        Self {
+            title: format!(
+                "Message from {} to {}",
+                message_view.creator.name, message_view.recipient.name
+            ),
+            body: truncate(&message_view.private_message.content, MAX_BODY_CHARS),
+            link: None,
+        }
+    }
+}
+
+impl From<&Report> for SinkMessage {
+    fn from(report: &Report) -> Self {
+        let (kind, link) = match report {
+            Report::Post(view) => ("post", view.post.ap_id.to_string()),
+            Report::Comment(view) => ("comment", view.comment.ap_id.to_string()),
+        };
+        Self {
+            title: format!(
+                "{} reported a {kind} by {} in {}: {}",
+                report.reporter().name,
+                report.reported().name,
+                report.community().name,
+                report.reason()
+            ),
+            body: truncate(&report.reported_content(), MAX_BODY_CHARS),
+            link: Some(link),
+        }
+    }
+}
+
+/// Converts poll events of items that convert into a [`SinkMessage`], marking edits in the title.
+macro_rules! impl_from_poll_event {
+    ($($item:ty),+) => {
+        $(
+            impl From<&PollEvent<$item>> for SinkMessage {
+                fn from(event: &PollEvent<$item>) -> Self {
+                    let message = Self::from(event.item());
+                    match event {
+                        PollEvent::New(_) => message,
+                        PollEvent::Updated(_) => Self {
+                            title: format!("Edited: {}", message.title),
+                            ..message
+                        },
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_from_poll_event!(PostView, CommentView, PrivateMessageView, Report);
+
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+/// Where [`LemmyClient::forward_events`] sends events, e.g. a moderators' chat.
+///
+/// The [`Debug`] output leaves out credentials: the values of webhook headers, the URL of a Discord webhook, and the Matrix access token.
+pub enum EventSink {
+    /// POSTs each [`SinkMessage`] as JSON, with `title`, `body`, and `link` fields, to a URL.
+    Webhook {
+        /// The URL to send events to.
+        url: String,
+        /// Headers to send with each event, e.g. to authenticate.
+        headers: HashMap<String, String>,
+    },
+    #[cfg(feature = "discord")]
+    /// Posts each event as an embed through a Discord webhook.
+    Discord {
+        /// The URL of the webhook, from the integration settings of the channel.
+        webhook_url: String,
+    },
+    #[cfg(feature = "matrix")]
+    /// Sends each event as a notice to a Matrix room.
+    Matrix {
+        /// The URL of the homeserver, such as `https://matrix.org`.
+        homeserver: String,
+        /// The id of the room, such as `!abc123:matrix.org`. The account has to be in it.
+        room_id: String,
+        /// The access token of the account that sends the events.
+        access_token: String,
+    },
+}
+
+/// Stands in for a credential in [`Debug`] output.
+struct Redacted;
+
+impl std::fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl std::fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Webhook { url, headers } => f
+                .debug_struct("Webhook")
+                .field("url", url)
+                .field(
+                    "headers",
+                    &headers
+                        .keys()
+                        .map(|name| (name, Redacted))
+                        .collect::<HashMap<_, _>>(),
+                )
+                .finish(),
+            #[cfg(feature = "discord")]
+            Self::Discord { .. } => f
+                .debug_struct("Discord")
+                .field("webhook_url", &Redacted)
+                .finish(),
+            #[cfg(feature = "matrix")]
+            Self::Matrix {
+                homeserver,
+                room_id,
+                ..
+            } => f
+                .debug_struct("Matrix")
+                .field("homeserver", homeserver)
+                .field("room_id", room_id)
+                .field("access_token", &Redacted)
+                .finish(),
+        }
+    }
+}
+
+impl EventSink {
+    /// Creates a [`EventSink::Webhook`] without extra headers.
+    pub fn webhook(url: impl Into<String>) -> Self {
+        Self::Webhook {
+            url: url.into(),
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Sends one message through the sink, using the connections of `client`. The dispatch queue, concurrency and bandwidth limits, and metrics of `client` are for its instance, so they do not apply.
+    pub async fn send(&self, client: &LemmyClient, message: &SinkMessage) -> LemmyResult<()> {
+        match self {
+            Self::Webhook { url, headers } => {
+                let body = serde_json::to_value(message).map_err(|e| Error::new(e.to_string()))?;
+                client
+                    .client
+                    .send_json(Method::POST, url, &body, headers)
+                    .await
+            }
+            #[cfg(feature = "discord")]
+            Self::Discord { webhook_url } => {
+                client
+                    .client
+                    .send_json(
+                        Method::POST,
+                        webhook_url,
+                        &discord_body(message),
+                        &HashMap::new(),
+                    )
+                    .await
+            }
+            #[cfg(feature = "matrix")]
+            Self::Matrix {
+                homeserver,
+                room_id,
+                access_token,
+            } => {
+                let url = matrix_send_url(homeserver, room_id)?;
+                let headers =
+                    HashMap::from([("Authorization".to_owned(), format!("Bearer {access_token}"))]);
+                client
+                    .client
+                    .send_json(Method::PUT, &url, &matrix_body(message), &headers)
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(feature = "discord")]
+/// Formats a message as a Discord webhook body with one embed, within Discord's length limits.
+fn discord_body(message: &SinkMessage) -> serde_json::Value {
+    serde_json::json!({
+        "embeds": [{
+            "title": truncate(&message.title, 255),
+            "description": truncate(&message.body, 4095),
+            "url": message.link,
+        }],
+        "allowed_mentions": { "parse": [] },
+    })
+}
+
+#[cfg(feature = "matrix")]
+/// The URL to send a message event to a room with, under a new transaction id.
+fn matrix_send_url(homeserver: &str, room_id: &str) -> LemmyResult<String> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static TRANSACTIONS: AtomicU64 = AtomicU64::new(0);
+
+    let transaction_id = format!(
+        "lemmy-client-{}-{}",
+        crate::now().timestamp_millis(),
+        TRANSACTIONS.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut url =
+        url::Url::parse(homeserver).map_err(|e| Error::new(format!("invalid_url: {e}")))?;
+    url.path_segments_mut()
+        .map_err(|()| Error::new("invalid_url: homeserver cannot be a base"))?
+        .pop_if_empty()
+        .extend([
+            "_matrix",
+            "client",
+            "v3",
+            "rooms",
+            room_id,
+            "send",
+            "m.room.message",
+            &transaction_id,
+        ]);
+
+    Ok(url.into())
+}
+
+#[cfg(feature = "matrix")]
+/// Formats a message as a Matrix notice, with an HTML version for clients that render it.
+fn matrix_body(message: &SinkMessage) -> serde_json::Value {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let mut plain = format!("{}\n{}", message.title, message.body);
+    let mut html = format!(
+        "<strong>{}</strong><br>{}",
+        escape(&message.title),
+        escape(&message.body).replace('\n', "<br>")
+    );
+    if let Some(link) = &message.link {
+        plain.push_str(&format!("\n{link}"));
+        html.push_str(&format!("<br><a href=\"{0}\">{0}</a>", escape(link)));
+    }
+
+    serde_json::json!({
+        "msgtype": "m.notice",
+        "body": plain,
+        "format": "org.matrix.custom.html",
+        "formatted_body": html,
+    })
+}
+
+impl LemmyClient {
+    /// Sends every event of a stream, such as [`LemmyClient::poll_reports`], through `sink`, until the stream ends or the client is [shut down](LemmyClient::shutdown). Errors of the stream and of sending are passed to `on_error`, and forwarding continues.
+    ///
+    /// ```no_run
+    /// use lemmy_client::{EventSink, LemmyClient, MemorySeenStore, PollOptions};
+    /// use std::sync::Arc;
+    ///
+    /// # async fn example(client: LemmyClient, jwt: String) {
+    /// let reports = client.poll_reports(
+    ///     Arc::new(MemorySeenStore::default()),
+    ///     PollOptions::default(),
+    ///     Some(jwt),
+    /// );
+    /// let sink = EventSink::webhook("https://chat.example.com/hooks/mods");
+    /// client
+    ///     .forward_events(reports, &sink, |e| eprintln!("Could not forward a report: {e}"))
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn forward_events<S, T>(
+        &self,
+        events: S,
+        sink: &EventSink,
+        mut on_error: impl FnMut(Error),
+    ) where
+        S: Stream<Item = LemmyResult<T>>,
+        for<'a> SinkMessage: From<&'a T>,
+    {
+        let mut events = std::pin::pin!(events);
+        while let Some(event) = events.next().await {
+            if self.is_shut_down() {
+                break;
+            }
+            let result = match event {
+                Ok(event) => sink.send(self, &SinkMessage::from(&event)).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(_) if self.is_shut_down() => break,
+                Err(e) => on_error(e),
+                Ok(()) => {}
+            }
+        }
+    }
+}
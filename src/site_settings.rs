@@ -0,0 +1,100 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::site::{EditSite, SiteResponse};
+
+impl LemmyClient {
+    /// Adds a tagline to the front page of the instance, if it does not have it yet. Only admins can do this.
+    ///
+    /// The taglines are sent with the current ones, and only the taglines are sent, so other site settings are left untouched.
+    pub async fn add_tagline(
+        &self,
+        tagline: impl Into<String>,
+        jwt: Option<String>,
+    ) -> LemmyResult<SiteResponse> {
+        let tagline = tagline.into();
+        self.update_taglines(
+            |taglines| {
+                if !taglines.contains(&tagline) {
+                    taglines.push(tagline);
+                }
+            },
+            jwt,
+        )
+        .await
+    }
+
+    /// Removes every tagline with the given text from the front page of the instance. Only admins can do this.
+    ///
+    /// Only the taglines are sent, so other site settings are left untouched.
+    pub async fn remove_tagline(
+        &self,
+        tagline: &str,
+        jwt: Option<String>,
+    ) -> LemmyResult<SiteResponse> {
+        self.update_taglines(
+            |taglines| taglines.retain(|content| content != tagline),
+            jwt,
+        )
+        .await
+    }
+
+    /// Sets the legal information of the instance, such as its privacy policy, in markdown. Only admins can do this.
+    ///
+    /// Only the legal information is sent, so other site settings are left untouched.
+    pub async fn set_legal_info(
+        &self,
+        legal_information: impl Into<String>,
+        jwt: Option<String>,
+    ) -> LemmyResult<SiteResponse> {
+        let form = EditSite {
+            legal_information: Some(legal_information.into()),
+            ..Default::default()
+        };
+        self.client
+            .edit_site(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+
+    /// Sets the sidebar of the instance, in markdown. Only admins can do this.
+    ///
+    /// Only the sidebar is sent, so other site settings are left untouched.
+    pub async fn set_sidebar(
+        &self,
+        sidebar: impl Into<String>,
+        jwt: Option<String>,
+    ) -> LemmyResult<SiteResponse> {
+        let form = EditSite {
+            sidebar: Some(sidebar.into()),
+            ..Default::default()
+        };
+        self.client
+            .edit_site(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+
+    /// Fetches the current taglines, changes them with `update`, and sends them back.
+    async fn update_taglines(
+        &self,
+        update: impl FnOnce(&mut Vec<String>),
+        jwt: Option<String>,
+    ) -> LemmyResult<SiteResponse> {
+        let mut taglines = self
+            .client
+            .get_site(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+            .await?
+            .taglines
+            .into_iter()
+            .map(|tagline| tagline.content)
+            .collect::<Vec<_>>();
+        update(&mut taglines);
+
+        let form = EditSite {
+            taglines: Some(taglines),
+            ..Default::default()
+        };
+        self.client
+            .edit_site(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+}
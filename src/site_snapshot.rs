@@ -0,0 +1,307 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use lemmy_api_common::{
+    lemmy_db_schema::source::{
+        local_site::LocalSite, local_site_rate_limit::LocalSiteRateLimit, site::Site,
+    },
+    site::{GetSiteResponse, InstanceWithFederationState},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt};
+
+/// Fields that change without anyone changing the configuration, so they are left out of diffs.
+const IGNORED_FIELDS: &[&str] = &[
+    "id",
+    "site_id",
+    "local_site_id",
+    "instance_id",
+    "published",
+    "updated",
+    "last_refreshed_at",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The configuration of an instance at one point in time, as taken by [`LemmyClient::site_snapshot`]. Save snapshots with [`SiteSnapshot::to_json`], and compare them with [`SiteDiff::between`].
+pub struct SiteSnapshot {
+    /// When the snapshot was taken.
+    pub taken_at: DateTime<Utc>,
+    /// The public settings of the site, such as its name and sidebar.
+    pub site: Site,
+    /// The settings of the instance, such as its registration mode.
+    pub local_site: LocalSite,
+    /// The rate limits of the instance.
+    pub rate_limits: LocalSiteRateLimit,
+    /// ActivityPub IDs of the admins.
+    pub admins: Vec<String>,
+    /// The taglines shown at the top of the front page.
+    pub taglines: Vec<String>,
+    /// Shortcodes of the custom emojis.
+    pub custom_emojis: Vec<String>,
+    /// Domains of the instances on the allowlist.
+    pub allowed_instances: Vec<String>,
+    /// Domains of the instances on the blocklist.
+    pub blocked_instances: Vec<String>,
+}
+
+impl SiteSnapshot {
+    /// Takes a snapshot from a site response, without federation lists. See [`LemmyClient::site_snapshot`] for one with them.
+    pub fn from_site(site: GetSiteResponse) -> Self {
+        let mut snapshot = Self {
+            taken_at: crate::now(),
+            site: site.site_view.site,
+            local_site: site.site_view.local_site,
+            rate_limits: site.site_view.local_site_rate_limit,
+            admins: site
+                .admins
+                .into_iter()
+                .map(|admin| admin.person.actor_id.to_string())
+                .collect(),
+            taglines: site
+                .taglines
+                .into_iter()
+                .map(|tagline| tagline.content)
+                .collect(),
+            custom_emojis: site
+                .custom_emojis
+                .into_iter()
+                .map(|emoji| emoji.custom_emoji.shortcode)
+                .collect(),
+            allowed_instances: Vec::new(),
+            blocked_instances: Vec::new(),
+        };
+        snapshot.admins.sort();
+        snapshot.custom_emojis.sort();
+
+        snapshot
+    }
+
+    /// The snapshot as pretty-printed JSON, e.g. to save it to a file or commit it to a repository.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Snapshots always serialize")
+    }
+
+    /// Reads a snapshot saved with [`SiteSnapshot::to_json`].
+    pub fn from_json(json: &str) -> LemmyResult<Self> {
+        serde_json::from_str(json).map_err(|e| Error::new(format!("invalid_snapshot: {e}")))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The differences between two [`SiteSnapshot`]s, from [`SiteDiff::between`]. Displays as a readable report, with one change per line.
+///
+/// ```
+/// # use lemmy_client::{SiteDiff, SiteSnapshot};
+/// # fn example(yesterday: &str, today: &str) -> Result<(), lemmy_client::Error> {
+/// let diff = SiteDiff::between(&SiteSnapshot::from_json(yesterday)?, &SiteSnapshot::from_json(today)?);
+/// if !diff.is_empty() {
+///     println!("The configuration changed:\n{diff}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SiteDiff {
+    /// Settings whose value changed, such as `local_site.registration_mode`, with the old and new values as JSON.
+    pub settings_changed: Vec<(String, String, String)>,
+    /// ActivityPub IDs of people who became admins.
+    pub admins_added: Vec<String>,
+    /// ActivityPub IDs of people who stopped being admins.
+    pub admins_removed: Vec<String>,
+    /// Taglines that were added.
+    pub taglines_added: Vec<String>,
+    /// Taglines that were removed.
+    pub taglines_removed: Vec<String>,
+    /// Shortcodes of custom emojis that were added.
+    pub custom_emojis_added: Vec<String>,
+    /// Shortcodes of custom emojis that were removed.
+    pub custom_emojis_removed: Vec<String>,
+    /// Domains added to the allowlist.
+    pub allowed_instances_added: Vec<String>,
+    /// Domains removed from the allowlist.
+    pub allowed_instances_removed: Vec<String>,
+    /// Domains added to the blocklist.
+    pub blocked_instances_added: Vec<String>,
+    /// Domains removed from the blocklist.
+    pub blocked_instances_removed: Vec<String>,
+}
+
+/// Returns the entries only in `new`, and those only in `old`, both sorted.
+fn list_changes(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+    let (old, new) = (
+        old.iter().collect::<BTreeSet<_>>(),
+        new.iter().collect::<BTreeSet<_>>(),
+    );
+    (
+        new.difference(&old)
+            .map(|entry| entry.to_string())
+            .collect(),
+        old.difference(&new)
+            .map(|entry| entry.to_string())
+            .collect(),
+    )
+}
+
+/// The fields of a settings section, as JSON.
+fn settings_fields(settings: &impl Serialize) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(settings) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    }
+}
+
+/// Adds the fields of `section` that differ between `old` and `new` to `changes`, in alphabetical order.
+fn settings_changes<T: Serialize>(
+    section: &str,
+    old: &T,
+    new: &T,
+    changes: &mut Vec<(String, String, String)>,
+) {
+    let (old, new) = (settings_fields(old), settings_fields(new));
+    let fields = old
+        .keys()
+        .chain(new.keys())
+        .filter(|field| !IGNORED_FIELDS.contains(&field.as_str()))
+        .collect::<BTreeSet<_>>();
+    for field in fields {
+        let (old_value, new_value) = (
+            old.get(field).unwrap_or(&serde_json::Value::Null),
+            new.get(field).unwrap_or(&serde_json::Value::Null),
+        );
+        if old_value != new_value {
+            changes.push((
+                format!("{section}.{field}"),
+                old_value.to_string(),
+                new_value.to_string(),
+            ));
+        }
+    }
+}
+
+impl SiteDiff {
+    /// Compares an older and a newer snapshot. Timestamps and ids are ignored, since they change without the configuration changing.
+    pub fn between(old: &SiteSnapshot, new: &SiteSnapshot) -> Self {
+        let mut settings_changed = Vec::new();
+        settings_changes("site", &old.site, &new.site, &mut settings_changed);
+        settings_changes(
+            "local_site",
+            &old.local_site,
+            &new.local_site,
+            &mut settings_changed,
+        );
+        settings_changes(
+            "rate_limits",
+            &old.rate_limits,
+            &new.rate_limits,
+            &mut settings_changed,
+        );
+
+        let (admins_added, admins_removed) = list_changes(&old.admins, &new.admins);
+        let (taglines_added, taglines_removed) = list_changes(&old.taglines, &new.taglines);
+        let (custom_emojis_added, custom_emojis_removed) =
+            list_changes(&old.custom_emojis, &new.custom_emojis);
+        let (allowed_instances_added, allowed_instances_removed) =
+            list_changes(&old.allowed_instances, &new.allowed_instances);
+        let (blocked_instances_added, blocked_instances_removed) =
+            list_changes(&old.blocked_instances, &new.blocked_instances);
+
+        Self {
+            settings_changed,
+            admins_added,
+            admins_removed,
+            taglines_added,
+            taglines_removed,
+            custom_emojis_added,
+            custom_emojis_removed,
+            allowed_instances_added,
+            allowed_instances_removed,
+            blocked_instances_added,
+            blocked_instances_removed,
+        }
+    }
+
+    /// Returns true if the snapshots had no differences.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for SiteDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (setting, old, new) in &self.settings_changed {
+            writeln!(f, "~ {setting}: {old} → {new}")?;
+        }
+        let lists = [
+            ("admin", &self.admins_added, &self.admins_removed),
+            ("tagline", &self.taglines_added, &self.taglines_removed),
+            (
+                "custom emoji",
+                &self.custom_emojis_added,
+                &self.custom_emojis_removed,
+            ),
+            (
+                "allowed instance",
+                &self.allowed_instances_added,
+                &self.allowed_instances_removed,
+            ),
+            (
+                "blocked instance",
+                &self.blocked_instances_added,
+                &self.blocked_instances_removed,
+            ),
+        ];
+        for (list, added, removed) in lists {
+            for entry in added {
+                writeln!(f, "+ {list}: {entry}")?;
+            }
+            for entry in removed {
+                writeln!(f, "- {list}: {entry}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LemmyClient {
+    /// Takes a snapshot of the configuration of the instance, including its federation lists.
+    ///
+    /// ```no_run
+    /// # use lemmy_client::LemmyClient;
+    /// # async fn example(client: LemmyClient, jwt: String) -> Result<(), lemmy_client::Error> {
+    /// let snapshot = client.site_snapshot(Some(jwt)).await?;
+    /// std::fs::write("site.json", snapshot.to_json()).unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// HTTP GET /site, HTTP GET /federated_instances
+    pub async fn site_snapshot(&self, jwt: Option<String>) -> LemmyResult<SiteSnapshot> {
+        let site = self
+            .client
+            .get_site(LemmyRequest::from_jwt(jwt.clone()), &self.headers)
+            .await?;
+        let federated_instances = self
+            .client
+            .get_federated_instances(LemmyRequest::from_jwt(jwt), &self.headers)
+            .await?
+            .federated_instances;
+
+        let mut snapshot = SiteSnapshot::from_site(site);
+        if let Some(lists) = federated_instances {
+            let domains = |instances: Vec<InstanceWithFederationState>| {
+                let mut domains = instances
+                    .into_iter()
+                    .map(|instance| instance.instance.domain)
+                    .collect::<Vec<_>>();
+                domains.sort();
+                domains
+            };
+            snapshot.allowed_instances = domains(lists.allowed);
+            snapshot.blocked_instances = domains(lists.blocked);
+        }
+
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, timestamps::now, utils::sleep,
+    LemmyClient, LemmyRequest,
+};
+use chrono::{DateTime, Utc};
+use futures::{future, stream, Stream, StreamExt};
+use lemmy_api_common::{community::GetCommunity, lemmy_db_schema::newtypes::CommunityId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// What a [`StatsSnapshot`] counts.
+pub enum StatsTarget {
+    /// The whole instance.
+    Instance,
+    /// A community.
+    Community(CommunityId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The counts of an instance or community at one point in time, taken by [`LemmyClient::stats_snapshot`].
+pub struct StatsSnapshot {
+    /// What was counted.
+    pub target: StatsTarget,
+    /// When the counts were fetched.
+    pub taken_at: DateTime<Utc>,
+    /// The number of users of an instance, or the number of subscribers of a community.
+    pub members: i64,
+    /// The number of posts.
+    pub posts: i64,
+    /// The number of comments.
+    pub comments: i64,
+    /// The number of users with any activity in the last day.
+    pub active_day: i64,
+    /// The number of users with any activity in the last week.
+    pub active_week: i64,
+    /// The number of users with any activity in the last month.
+    pub active_month: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How fast the counts grew between two snapshots, as returned by [`growth_series`]. Rates are per day, and negative if counts shrank, e.g. because content was purged.
+pub struct GrowthPoint {
+    /// When the later snapshot was taken.
+    pub at: DateTime<Utc>,
+    /// New members per day.
+    pub members_per_day: f64,
+    /// New posts per day.
+    pub posts_per_day: f64,
+    /// New comments per day.
+    pub comments_per_day: f64,
+    /// Users with any activity in the last day, at the later snapshot.
+    pub active_day: i64,
+}
+
+/// Keeps the snapshots taken by [`LemmyClient::track_stats`], so that the history survives restarts. Implement it to persist them, e.g. in a file or database. [`MemoryStatsStore`] keeps them in memory.
+pub trait StatsStore: Send + Sync {
+    /// Stores a snapshot that was just taken.
+    fn save(&self, snapshot: &StatsSnapshot);
+
+    /// The snapshots stored for `target`, oldest first.
+    fn load(&self, target: StatsTarget) -> Vec<StatsSnapshot>;
+}
+
+#[derive(Debug, Default)]
+/// A [`StatsStore`] that keeps snapshots in memory, up to a limit, so they are lost when the process exits.
+pub struct MemoryStatsStore {
+    max_snapshots: Option<usize>,
+    snapshots: Mutex<VecDeque<StatsSnapshot>>,
+}
+
+impl MemoryStatsStore {
+    /// Creates a store that keeps at most `max_snapshots` snapshots, dropping the oldest ones first.
+    pub fn with_limit(max_snapshots: usize) -> Self {
+        Self {
+            max_snapshots: Some(max_snapshots),
+            snapshots: Mutex::default(),
+        }
+    }
+}
+
+impl StatsStore for MemoryStatsStore {
+    fn save(&self, snapshot: &StatsSnapshot) {
+        let mut snapshots = self
+            .snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        snapshots.push_back(snapshot.clone());
+        if let Some(max_snapshots) = self.max_snapshots {
+            while snapshots.len() > max_snapshots {
+                snapshots.pop_front();
+            }
+        }
+    }
+
+    fn load(&self, target: StatsTarget) -> Vec<StatsSnapshot> {
+        self.snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|snapshot| snapshot.target == target)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Computes the growth between each pair of consecutive snapshots, which should be of the same target and sorted oldest first. Pairs taken at the same time are skipped.
+pub fn growth_series(snapshots: &[StatsSnapshot]) -> Vec<GrowthPoint> {
+    snapshots
+        .windows(2)
+        .filter_map(|pair| {
+            let (earlier, later) = (&pair[0], &pair[1]);
+            let days = (later.taken_at - earlier.taken_at).num_seconds() as f64 / 86_400.0;
+            if days <= 0.0 {
+                return None;
+            }
+            let per_day = |earlier: i64, later: i64| (later - earlier) as f64 / days;
+
+            Some(GrowthPoint {
+                at: later.taken_at,
+                members_per_day: per_day(earlier.members, later.members),
+                posts_per_day: per_day(earlier.posts, later.posts),
+                comments_per_day: per_day(earlier.comments, later.comments),
+                active_day: later.active_day,
+            })
+        })
+        .collect()
+}
+
+impl LemmyClient {
+    /// Fetches the current counts of the instance or a community.
+    pub async fn stats_snapshot(
+        &self,
+        target: StatsTarget,
+        jwt: Option<String>,
+    ) -> LemmyResult<StatsSnapshot> {
+        let taken_at = now();
+        match target {
+            StatsTarget::Instance => {
+                let counts = self
+                    .client
+                    .get_site(LemmyRequest::from_jwt(jwt), &self.headers)
+                    .await?
+                    .site_view
+                    .counts;
+                Ok(StatsSnapshot {
+                    target,
+                    taken_at,
+                    members: counts.users,
+                    posts: counts.posts,
+                    comments: counts.comments,
+                    active_day: counts.users_active_day,
+                    active_week: counts.users_active_week,
+                    active_month: counts.users_active_month,
+                })
+            }
+            StatsTarget::Community(community_id) => {
+                let form = GetCommunity {
+                    id: Some(community_id),
+                    name: None,
+                };
+                let counts = self
+                    .client
+                    .get_community(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                    .await?
+                    .community_view
+                    .counts;
+                Ok(StatsSnapshot {
+                    target,
+                    taken_at,
+                    members: counts.subscribers,
+                    posts: counts.posts,
+                    comments: counts.comments,
+                    active_day: counts.users_active_day,
+                    active_week: counts.users_active_week,
+                    active_month: counts.users_active_month,
+                })
+            }
+        }
+    }
+
+    /// Takes a snapshot of each target every `interval`, saving it to `store` and streaming it. A failed snapshot streams its error, and tracking continues until the client is [shut down](LemmyClient::shutdown).
+    ///
+    /// Pass the stored snapshots of a target to [`growth_series`] to chart its growth.
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use lemmy_client::{growth_series, LemmyClient, MemoryStatsStore, StatsStore, StatsTarget};
+    /// use std::{sync::Arc, time::Duration};
+    ///
+    /// # async fn example(client: LemmyClient) {
+    /// let store = Arc::new(MemoryStatsStore::default());
+    /// let tracking = client.track_stats(
+    ///     vec![StatsTarget::Instance],
+    ///     store.clone(),
+    ///     Duration::from_secs(60 * 60),
+    ///     None,
+    /// );
+    /// let mut tracking = std::pin::pin!(tracking.take(24));
+    /// while tracking.next().await.is_some() {}
+    ///
+    /// for point in growth_series(&store.load(StatsTarget::Instance)) {
+    ///     println!("{}: {:.1} posts per day", point.at, point.posts_per_day);
+    /// }
+    /// # }
+    /// ```
+    pub fn track_stats(
+        &self,
+        targets: Vec<StatsTarget>,
+        store: Arc<dyn StatsStore>,
+        interval: Duration,
+        jwt: Option<String>,
+    ) -> impl Stream<Item = LemmyResult<StatsSnapshot>> + '_ {
+        let rounds = stream::unfold(true, move |first| async move {
+            if !first {
+                sleep(interval).await;
+            }
+            (!self.is_shut_down()).then_some(((), false))
+        });
+
+        rounds
+            .flat_map(move |()| {
+                let store = store.clone();
+                let jwt = jwt.clone();
+                stream::iter(targets.clone()).then(move |target| {
+                    let store = store.clone();
+                    let jwt = jwt.clone();
+                    async move {
+                        let snapshot = self.stats_snapshot(target, jwt).await?;
+                        store.save(&snapshot);
+                        Ok(snapshot)
+                    }
+                })
+            })
+            .take_while(move |_| future::ready(!self.is_shut_down()))
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, Error, LemmyClient,
+    LemmyRequest,
+};
+use lemmy_api_common::{
+    community::{FollowCommunity, GetCommunity},
+    lemmy_db_schema::{newtypes::CommunityId, source::community::Community},
+};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A way of referring to a community.
+pub enum CommunityHandle {
+    /// The ID of the community on the instance the client sends requests to.
+    Id(CommunityId),
+    /// The name of the community, e.g. `rust` for a local community or `rust@lemmy.ml` for a remote one.
+    Name(String),
+}
+
+impl From<CommunityId> for CommunityHandle {
+    fn from(id: CommunityId) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl From<&str> for CommunityHandle {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_owned())
+    }
+}
+
+impl From<String> for CommunityHandle {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The changes made by [`LemmyClient::sync_subscriptions`].
+pub struct SubscriptionDiff {
+    /// Communities that were followed.
+    pub followed: Vec<CommunityId>,
+    /// Communities that were unfollowed.
+    pub unfollowed: Vec<CommunityId>,
+    /// Communities that were already followed and are in the target list.
+    pub unchanged: Vec<CommunityId>,
+    /// Communities for which the follow or unfollow request failed.
+    pub failed: Vec<(CommunityId, Error)>,
+}
+
+impl LemmyClient {
+    /// Follows and unfollows communities so the account's subscriptions match `target`.
+    ///
+    /// Every handle is resolved before any change is made, so an unknown community aborts the sync without touching the account's follows.
+    pub async fn sync_subscriptions(
+        &self,
+        target: &[CommunityHandle],
+        jwt: Option<String>,
+    ) -> LemmyResult<SubscriptionDiff> {
+        let current = self
+            .my_follows(jwt.clone())
+            .await?
+            .into_iter()
+            .map(|community| community.id)
+            .collect::<HashSet<_>>();
+
+        let mut wanted = HashSet::with_capacity(target.len());
+        for handle in target {
+            wanted.insert(self.resolve_community_id(handle, jwt.clone()).await?);
+        }
+
+        let mut diff = SubscriptionDiff::default();
+        for &community_id in &wanted {
+            if current.contains(&community_id) {
+                diff.unchanged.push(community_id);
+            } else {
+                match self.set_follow(community_id, true, jwt.clone()).await {
+                    Ok(()) => diff.followed.push(community_id),
+                    Err(e) => diff.failed.push((community_id, e)),
+                }
+            }
+        }
+        for &community_id in current.difference(&wanted) {
+            match self.set_follow(community_id, false, jwt.clone()).await {
+                Ok(()) => diff.unfollowed.push(community_id),
+                Err(e) => diff.failed.push((community_id, e)),
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Gets the communities the logged in user follows.
+    pub(crate) async fn my_follows(&self, jwt: Option<String>) -> LemmyResult<Vec<Community>> {
+        Ok(self
+            .my_user(jwt)
+            .await?
+            .follows
+            .into_iter()
+            .map(|follow| follow.community)
+            .collect())
+    }
+
+    /// Gets the ID a community has on the instance the client sends requests to.
+    pub(crate) async fn resolve_community_id(
+        &self,
+        handle: &CommunityHandle,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommunityId> {
+        match handle {
+            CommunityHandle::Id(id) => Ok(*id),
+            CommunityHandle::Name(name) => {
+                let form = GetCommunity {
+                    id: None,
+                    name: Some(name.clone()),
+                };
+                Ok(self
+                    .client
+                    .get_community(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                    .await?
+                    .community_view
+                    .community
+                    .id)
+            }
+        }
+    }
+
+    pub(crate) async fn set_follow(
+        &self,
+        community_id: CommunityId,
+        follow: bool,
+        jwt: Option<String>,
+    ) -> LemmyResult<()> {
+        let form = FollowCommunity {
+            community_id,
+            follow,
+        };
+        self.client
+            .follow_community(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal,
+    mirror::{MirrorItem, SyncReport},
+    response::LemmyResult,
+    utils::sleep,
+    LemmyClient, LemmyRequest, Mirror,
+};
+use lemmy_api_common::{
+    comment::GetComments,
+    lemmy_db_schema::{CommentSortType, SortType},
+    post::GetPosts,
+};
+use std::{collections::HashSet, future::Future, time::Duration};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Options for [`LemmyClient::sync_posts`] and [`LemmyClient::sync_comments`].
+pub struct SyncOptions {
+    /// How far before the checkpoint to keep fetching, so that objects published around the previous sync are not missed. Only objects in this window are refetched, so it is also how long edits and deletions of synced objects are picked up for.
+    pub overlap: Duration,
+    /// How long to wait before each request, to avoid hitting the instance's rate limits.
+    pub request_delay: Duration,
+    /// Stops after this many pages, even if the checkpoint was not reached. Useful for the first sync of a large feed.
+    ///
+    /// A sync that stops early keeps the previous checkpoint, and the next sync of the feed carries on from the page it stopped before, until the checkpoint or the end of the feed is reached.
+    pub max_pages: Option<i64>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            overlap: Duration::from_secs(60 * 60),
+            request_delay: Duration::from_millis(500),
+            max_pages: None,
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Fetches the posts of a feed that were published since its last sync, and stores them in `mirror`.
+    ///
+    /// `feed` names the checkpoint, and should be unique for each `form`, for example `"community:rust@programming.dev"`. The first sync of a feed fetches every post, up to [`SyncOptions::max_pages`]. The sort of `form` is always [`SortType::New`].
+    ///
+    /// Each sync refetches the posts published in the [`SyncOptions::overlap`] before the checkpoint, along with the newer ones. Refetched posts that were edited replace the stored copy. Posts in that range that were deleted or removed, or are no longer listed, which is how listings leave out deleted posts for most users, are dropped from the mirror along with their comments. Older posts are not refetched, so later edits and deletions of them are not picked up; use a longer overlap, or [`Mirror::reset_checkpoint`], to refresh them.
+    pub async fn sync_posts(
+        &self,
+        mirror: &Mirror,
+        feed: &str,
+        form: GetPosts,
+        options: &SyncOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<SyncReport> {
+        self.sync_pages(mirror, feed, options, |page| {
+            let form = GetPosts {
+                sort: Some(SortType::New),
+                page: Some(page),
+                page_cursor: None,
+                ..form.clone()
+            };
+            let jwt = jwt.clone();
+            async move {
+                self.client
+                    .list_posts(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                    .await
+                    .map(|response| response.posts)
+            }
+        })
+        .await
+    }
+
+    /// Fetches the comments of a feed that were published since its last sync, and stores them in `mirror`. Works like [`LemmyClient::sync_posts`].
+    pub async fn sync_comments(
+        &self,
+        mirror: &Mirror,
+        feed: &str,
+        form: GetComments,
+        options: &SyncOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<SyncReport> {
+        self.sync_pages(mirror, feed, options, |page| {
+            let form = GetComments {
+                sort: Some(CommentSortType::New),
+                page: Some(page),
+                max_depth: None,
+                ..form.clone()
+            };
+            let jwt = jwt.clone();
+            async move {
+                self.client
+                    .list_comments(LemmyRequest::with_jwt(form, jwt), &self.headers)
+                    .await
+                    .map(|response| response.comments)
+            }
+        })
+        .await
+    }
+
+    async fn sync_pages<T, F, Fut>(
+        &self,
+        mirror: &Mirror,
+        feed: &str,
+        options: &SyncOptions,
+        mut fetch_page: F,
+    ) -> LemmyResult<SyncReport>
+    where
+        T: MirrorItem,
+        F: FnMut(i64) -> Fut,
+        Fut: Future<Output = LemmyResult<Vec<T>>>,
+    {
+        let checkpoint = mirror.checkpoint(feed)?;
+        let overlap = i64::try_from(options.overlap.as_micros()).unwrap_or(i64::MAX);
+        let stop_before = checkpoint.map(|checkpoint| checkpoint.saturating_sub(overlap));
+        // Objects published since an unfinished sync push the remaining ones to later pages, and the newer ones are fetched by the next sync. Objects deleted since pull them to earlier pages, so the page before the one it stopped at is fetched again.
+        let (first_page, mut newest) = match mirror.resume_point(feed)? {
+            Some((page, newest)) => ((page - 1).max(1), newest.max(checkpoint)),
+            None => (1, checkpoint),
+        };
+        let mut report = SyncReport::default();
+        let mut seen = HashSet::new();
+        let mut fetched_range: Option<(i64, i64)> = None;
+        let mut finished = true;
+
+        for page in first_page.. {
+            if options
+                .max_pages
+                .is_some_and(|max_pages| page - first_page >= max_pages)
+            {
+                mirror.set_resume_point(feed, page, newest)?;
+                finished = false;
+                break;
+            }
+
+            sleep(options.request_delay).await;
+            let items = fetch_page(page).await?;
+            if items.is_empty() {
+                break;
+            }
+
+            for item in &items {
+                let published = item.published();
+                fetched_range = Some(match fetched_range {
+                    Some((oldest, newest)) => (oldest.min(published), newest.max(published)),
+                    None => (published, published),
+                });
+                seen.insert(item.ap_id());
+            }
+            newest = items.iter().map(T::published).chain(newest).max();
+            report += mirror.apply(feed, &items)?;
+            if stop_before
+                .is_some_and(|stop_before| items.iter().any(|item| item.published() < stop_before))
+            {
+                break;
+            }
+        }
+
+        // Every object of the feed published between the oldest and newest fetched ones was listed, as was every newer one if the first page was fetched.
+        if let Some((oldest, newest)) = fetched_range {
+            let before = (first_page > 1).then_some(newest);
+            report.deleted += mirror.drop_missing::<T>(feed, &seen, oldest, before)?;
+        }
+        if finished {
+            mirror.finish_sync(feed, newest)?;
+        }
+
+        Ok(report)
+    }
+}
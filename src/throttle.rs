@@ -0,0 +1,92 @@
+use crate::{utils::sleep, LemmyClient};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use web_time::Instant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Caps how much of the network a client uses, so long running jobs such as archiving or mirroring stay polite to slow connections and small instances. Set it with [`LemmyClient::set_bandwidth_limit`].
+///
+/// ```
+/// use lemmy_client::{BandwidthLimit, ClientOptions, LemmyClient};
+/// use std::time::Duration;
+///
+/// let mut client = LemmyClient::new(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// client.set_bandwidth_limit(Some(BandwidthLimit {
+///     bytes_per_second: Some(256 * 1024),
+///     min_request_interval: Some(Duration::from_millis(500)),
+/// }));
+/// ```
+pub struct BandwidthLimit {
+    /// How many bytes of responses can be downloaded per second on average. Counts the bodies of endpoint responses and image downloads.
+    pub bytes_per_second: Option<u64>,
+    /// How long to wait at least between the starts of two requests.
+    pub min_request_interval: Option<Duration>,
+}
+
+/// When the next request may start, and when the downloaded bytes are paid off.
+struct ThrottleState {
+    next_request: Instant,
+    next_download: Instant,
+}
+
+/// A bandwidth limit set on a client, shared by its copies.
+pub(crate) struct Throttle {
+    limit: BandwidthLimit,
+    state: Mutex<ThrottleState>,
+}
+
+impl Throttle {
+    fn new(limit: BandwidthLimit) -> Self {
+        let now = Instant::now();
+        Self {
+            limit,
+            state: Mutex::new(ThrottleState {
+                next_request: now,
+                next_download: now,
+            }),
+        }
+    }
+
+    /// Waits until a request may start, and reserves the interval after it.
+    pub(crate) async fn pace(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            let start = state.next_request.max(state.next_download).max(now);
+            if let Some(interval) = self.limit.min_request_interval {
+                state.next_request = start + interval;
+            }
+            start - now
+        };
+        sleep(wait).await;
+    }
+
+    /// Counts `bytes` that were downloaded, waiting for as long as downloading them should have taken.
+    pub(crate) async fn consume(&self, bytes: usize) {
+        let Some(rate) = self.limit.bytes_per_second.filter(|rate| *rate > 0) else {
+            return;
+        };
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            state.next_download =
+                state.next_download.max(now) + Duration::from_secs_f64(bytes as f64 / rate as f64);
+            state.next_download - now
+        };
+        sleep(wait).await;
+    }
+}
+
+impl LemmyClient {
+    /// Limits the download rate of this client and how often it starts requests. Copies of the client, such as those made by [`LemmyClient::customize`], share the limit. [`None`] removes the limit, which is the default. See [`BandwidthLimit`].
+    ///
+    /// Requests over the limit wait before they are sent. Responses are read chunk by chunk, waiting after each chunk for as long as downloading it should have taken, so bursts are no larger than a chunk. On wasm, where `fetch` reads a response at once, the wait comes after the whole response, so only the average rate is limited.
+    pub fn set_bandwidth_limit(&mut self, limit: Option<BandwidthLimit>) {
+        self.client.hooks.throttle = limit.map(|limit| Arc::new(Throttle::new(limit)));
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Returns the current time. Works on WASM, where [`std::time::SystemTime`] is unavailable.
+pub fn now() -> DateTime<Utc> {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    DateTime::from_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .unwrap_or_default()
+}
+
+/// Describes how long before `now` the `timestamp` was, the way Lemmy's web UI does, such as "5 minutes ago" or "in 2 days".
+///
+/// ```
+/// use chrono::{DateTime, TimeDelta};
+/// use lemmy_client::humanize_relative;
+///
+/// let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+/// assert_eq!(humanize_relative(now - TimeDelta::seconds(20), now), "just now");
+/// assert_eq!(humanize_relative(now - TimeDelta::minutes(1), now), "1 minute ago");
+/// assert_eq!(humanize_relative(now - TimeDelta::hours(5), now), "5 hours ago");
+/// assert_eq!(humanize_relative(now + TimeDelta::days(3), now), "in 3 days");
+/// ```
+pub fn humanize_relative(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (365 * 24 * 60 * 60, "year"),
+        (30 * 24 * 60 * 60, "month"),
+        (7 * 24 * 60 * 60, "week"),
+        (24 * 60 * 60, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+
+    let seconds = (now - timestamp).num_seconds();
+    let Some((count, unit)) = UNITS
+        .iter()
+        .find(|(unit_seconds, _)| seconds.abs() >= *unit_seconds)
+        .map(|(unit_seconds, unit)| (seconds.abs() / unit_seconds, unit))
+    else {
+        return "just now".to_owned();
+    };
+
+    let plural = if count == 1 { "" } else { "s" };
+    if seconds < 0 {
+        format!("in {count} {unit}{plural}")
+    } else {
+        format!("{count} {unit}{plural} ago")
+    }
+}
+
+/// Describes how long ago `timestamp` was relative to the current time. See [`humanize_relative`].
+pub fn time_ago(timestamp: DateTime<Utc>) -> String {
+    humanize_relative(timestamp, now())
+}
+
+/// Returns the value for the `expires` field of [`BanPerson`](lemmy_api_common::person::BanPerson) or [`BanFromCommunity`](lemmy_api_common::community::BanFromCommunity) that ends the ban after `duration`.
+///
+/// Lemmy turns bans longer than ten years into permanent bans. Durations too large to represent return [`None`], which is also a permanent ban.
+pub fn ban_for(duration: Duration) -> Option<i64> {
+    TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| now().checked_add_signed(duration))
+        .and_then(ban_until)
+}
+
+/// Returns the value for the `expires` field of a ban form that ends the ban at `expires`.
+///
+/// Lemmy rejects bans that expire in the past.
+pub fn ban_until(expires: DateTime<Utc>) -> Option<i64> {
+    Some(expires.timestamp())
+}
+
+#[cfg(feature = "time")]
+/// Converts a timestamp from Lemmy's API into a [`time::OffsetDateTime`].
+pub fn to_offset_date_time(timestamp: DateTime<Utc>) -> time::OffsetDateTime {
+    time::OffsetDateTime::from_unix_timestamp_nanos(
+        i128::from(timestamp.timestamp()) * 1_000_000_000
+            + i128::from(timestamp.timestamp_subsec_nanos()),
+    )
+    .expect("Lemmy timestamps are within the range supported by time")
+}
+
+#[cfg(feature = "time")]
+/// Converts a [`time::OffsetDateTime`] into the timestamp type used by Lemmy's API.
+pub fn from_offset_date_time(timestamp: time::OffsetDateTime) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp.unix_timestamp(), timestamp.nanosecond()).unwrap_or_default()
+}
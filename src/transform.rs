@@ -0,0 +1,186 @@
+use crate::LemmyClient;
+use lemmy_api_common::lemmy_db_schema::source::{
+    comment::Comment, community::Community, local_user::LocalUser, person::Person, post::Post,
+    private_message::PrivateMessage, site::Site,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+
+/// Fields of Lemmy's views that hold a person.
+const PERSON_FIELDS: &[&str] = &[
+    "admin",
+    "banned_person",
+    "commenter",
+    "creator",
+    "moderator",
+    "other_person",
+    "person",
+    "recipient",
+    "target",
+];
+
+/// Rewrites responses as they pass through a client, e.g. to route image URLs through a privacy proxy, strip tracking parameters from links, or redact email addresses. Add one with [`LemmyClient::add_response_transform`].
+///
+/// Each method is called for every object of its type in a response, wherever it is nested, so one transform covers every endpoint. [`ResponseTransform::transform_json`] sees the whole response first. Every method defaults to doing nothing.
+///
+/// ```
+/// use lemmy_client::{
+///     lemmy_api_common::lemmy_db_schema::source::{local_user::LocalUser, post::Post},
+///     ResponseTransform,
+/// };
+///
+/// struct Privacy;
+///
+/// impl ResponseTransform for Privacy {
+///     fn transform_post(&self, post: &mut Post) {
+///         if let Some(url) = &mut post.url {
+///             let mut clean = url.inner().clone();
+///             let kept = url
+///                 .query_pairs()
+///                 .filter(|(name, _)| !name.starts_with("utm_"))
+///                 .map(|(name, value)| (name.into_owned(), value.into_owned()))
+///                 .collect::<Vec<_>>();
+///             clean.query_pairs_mut().clear().extend_pairs(kept);
+///             if clean.query() == Some("") {
+///                 clean.set_query(None);
+///             }
+///             *url = clean.into();
+///         }
+///     }
+///
+///     fn transform_local_user(&self, local_user: &mut LocalUser) {
+///         local_user.email = None;
+///     }
+/// }
+/// ```
+pub trait ResponseTransform: Send + Sync {
+    /// Changes the whole successful response from the endpoint at `path`, relative to `/api/v3`, before the typed methods run.
+    fn transform_json(&self, path: &str, response: &mut serde_json::Value) {
+        let _ = (path, response);
+    }
+
+    /// Changes a post.
+    fn transform_post(&self, post: &mut Post) {
+        let _ = post;
+    }
+
+    /// Changes a comment.
+    fn transform_comment(&self, comment: &mut Comment) {
+        let _ = comment;
+    }
+
+    /// Changes a person, such as the creator of a post.
+    fn transform_person(&self, person: &mut Person) {
+        let _ = person;
+    }
+
+    /// Changes a community.
+    fn transform_community(&self, community: &mut Community) {
+        let _ = community;
+    }
+
+    /// Changes a private message.
+    fn transform_private_message(&self, private_message: &mut PrivateMessage) {
+        let _ = private_message;
+    }
+
+    /// Changes the settings of a local user, which include their email address.
+    fn transform_local_user(&self, local_user: &mut LocalUser) {
+        let _ = local_user;
+    }
+
+    /// Changes a site, such as the instance's own.
+    fn transform_site(&self, site: &mut Site) {
+        let _ = site;
+    }
+}
+
+/// Passes the object in `value` through `transform` as a `T`, if it is one. Fields `T` does not know are kept.
+fn transform_as<T: Serialize + DeserializeOwned>(
+    value: &mut serde_json::Value,
+    transform: impl FnOnce(&mut T),
+) {
+    let Ok(mut typed) = T::deserialize(&*value) else {
+        return;
+    };
+    let to_fields = |typed: &T| match serde_json::to_value(typed) {
+        Ok(serde_json::Value::Object(fields)) => Some(fields),
+        _ => None,
+    };
+    let (Some(before), Some(object)) = (to_fields(&typed), value.as_object_mut()) else {
+        return;
+    };
+    transform(&mut typed);
+    let Some(after) = to_fields(&typed) else {
+        return;
+    };
+
+    // Fields that became `None` are left out when serializing, so they have to be removed.
+    for field in before.keys().filter(|field| !after.contains_key(*field)) {
+        object.remove(field);
+    }
+    object.extend(after);
+}
+
+/// Runs the typed methods of `transform` on every object in `value` they apply to.
+fn transform_objects(transform: &dyn ResponseTransform, value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (field, value) in object.iter_mut() {
+                if value.is_object() {
+                    match field.as_str() {
+                        "post" => transform_as(value, |post| transform.transform_post(post)),
+                        "comment" => {
+                            transform_as(value, |comment| transform.transform_comment(comment))
+                        }
+                        "community" => transform_as(value, |community| {
+                            transform.transform_community(community)
+                        }),
+                        "private_message" => transform_as(value, |private_message| {
+                            transform.transform_private_message(private_message)
+                        }),
+                        "local_user" => transform_as(value, |local_user| {
+                            transform.transform_local_user(local_user)
+                        }),
+                        "site" => transform_as(value, |site| transform.transform_site(site)),
+                        field if PERSON_FIELDS.contains(&field) => {
+                            transform_as(value, |person| transform.transform_person(person))
+                        }
+                        _ => {}
+                    }
+                }
+                transform_objects(transform, value);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                transform_objects(transform, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Runs every transform on a response, in the order they were added.
+pub(crate) fn apply_transforms(
+    transforms: &[Arc<dyn ResponseTransform>],
+    path: &str,
+    response: &mut serde_json::Value,
+) {
+    for transform in transforms {
+        transform.transform_json(path, response);
+        transform_objects(transform.as_ref(), response);
+    }
+}
+
+impl LemmyClient {
+    /// Adds a transform that rewrites every successful endpoint response before it is returned. Transforms run in the order they were added, after the [server adapter](LemmyClient::set_server_adapter) and before the [content filter](LemmyClient::set_content_filter). See [`ResponseTransform`].
+    pub fn add_response_transform(&mut self, transform: Arc<dyn ResponseTransform>) {
+        self.client.hooks.transforms.push(transform);
+    }
+
+    /// Removes every transform added with [`LemmyClient::add_response_transform`].
+    pub fn clear_response_transforms(&mut self) {
+        self.client.hooks.transforms.clear();
+    }
+}
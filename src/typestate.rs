@@ -0,0 +1,189 @@
+use crate::{
+    endpoints::with_endpoints, lemmy_client_trait::LemmyClientInternal, ClientOptions, LemmyClient,
+    LemmyForm, LemmyRequest, LemmyResult,
+};
+use lemmy_api_common::{
+    comment::*, community::*, custom_emoji::*, lemmy_db_schema::source::login_token::LoginToken,
+    person::*, post::*, private_message::*, sensitive::Sensitive, site::*, SuccessResponse,
+};
+
+use crate::newer_api::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The default mode of a [`LemmyClient`], where the JWT, if any, is passed with each request and checked by the instance.
+pub struct Dynamic;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The mode of a [`LemmyClient`] that is not logged in. Only endpoints that work without logging in can be called, so calling the others is a compile error rather than a `not_logged_in` error.
+///
+/// ```compile_fail
+/// # use lemmy_client::{LemmyClient, ClientOptions};
+/// # async fn example(form: lemmy_client::lemmy_api_common::post::CreatePost) {
+/// let client = LemmyClient::anonymous(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// // Creating posts requires logging in.
+/// client.create_post(form).await;
+/// # }
+/// ```
+pub struct Anonymous;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The mode of a logged in [`LemmyClient`]. Every endpoint can be called, and the JWT is sent with each request.
+///
+/// ```no_run
+/// use lemmy_client::{ClientOptions, LemmyClient};
+/// use lemmy_client::lemmy_api_common::person::Login;
+///
+/// # async fn example(login: Login) -> Result<(), lemmy_client::Error> {
+/// let client = LemmyClient::anonymous(ClientOptions {
+///     domain: String::from("lemmy.ml"),
+///     secure: true,
+/// });
+/// let site = client.get_site().await?;
+///
+/// let jwt = client.login(login).await?.jwt.expect("Login was approved");
+/// let client = client.authenticated(jwt.into_inner());
+/// let unread = client.unread_count().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Authenticated {
+    jwt: Sensitive<String>,
+}
+
+impl<State> LemmyClient<State> {
+    fn with_state<NewState>(self, state: NewState) -> LemmyClient<NewState> {
+        LemmyClient {
+            state,
+            headers: self.headers,
+            site_metadata_cache: self.site_metadata_cache,
+            validate_forms: self.validate_forms,
+            image_proxy: self.image_proxy,
+            client: self.client,
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Creates a client that is not logged in. See [`Anonymous`].
+    pub fn anonymous(options: ClientOptions) -> LemmyClient<Anonymous> {
+        Self::new(options).with_state(Anonymous)
+    }
+
+    /// Turns the client into one that is not logged in, keeping its settings. See [`Anonymous`].
+    pub fn into_anonymous(self) -> LemmyClient<Anonymous> {
+        self.with_state(Anonymous)
+    }
+
+    /// Turns the client into one logged in with `jwt`, keeping its settings. See [`Authenticated`].
+    ///
+    /// The JWT is not checked. Use [`LemmyClient::validate_jwt`] first when it may have expired.
+    pub fn into_authenticated(self, jwt: impl Into<String>) -> LemmyClient<Authenticated> {
+        self.with_state(Authenticated {
+            jwt: Sensitive::new(jwt.into()),
+        })
+    }
+}
+
+impl LemmyClient<Anonymous> {
+    /// Logs the client in with `jwt`, e.g. one returned by [`LemmyClient::<Anonymous>::login`]. The JWT is not checked.
+    pub fn authenticated(self, jwt: impl Into<String>) -> LemmyClient<Authenticated> {
+        self.with_state(Authenticated {
+            jwt: Sensitive::new(jwt.into()),
+        })
+    }
+
+    /// Turns the client back into a [`Dynamic`] one, e.g. to use the helpers only available in that mode.
+    pub fn into_dynamic(self) -> LemmyClient {
+        self.with_state(Dynamic)
+    }
+}
+
+impl LemmyClient<Authenticated> {
+    /// The JWT sent with each request.
+    pub fn jwt(&self) -> &str {
+        &self.state.jwt
+    }
+
+    /// Forgets the JWT, without logging it out on the instance. Use [`LemmyClient::<Authenticated>::logout`] first to invalidate it.
+    pub fn into_anonymous(self) -> LemmyClient<Anonymous> {
+        self.with_state(Anonymous)
+    }
+
+    /// Turns the client back into a [`Dynamic`] one, e.g. to use the helpers only available in that mode. Returns the JWT, since dynamic clients take it with each request.
+    pub fn into_dynamic(self) -> (LemmyClient, String) {
+        let jwt = self.state.jwt.clone().into_inner();
+        (self.with_state(Dynamic), jwt)
+    }
+}
+
+macro_rules! typed_fn {
+    ($name:ident, (), $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
+        pub async fn $name(&self) -> LemmyResult<$response> {
+            let response = self
+                .client
+                .$name(LemmyRequest::from_jwt(self.state.jwt()), &self.headers)
+                .await?;
+            self.filter_response($path, response)
+        }
+    };
+    ($name:ident, $form:ty, $response:ty, $method:ident, $path:literal, $(#[$doc:meta])*) => {
+        $(#[$doc])*
+        #[doc = ""]
+        #[doc = concat!("HTTP ", stringify!($method), " /", $path)]
+        pub async fn $name(&self, form: $form) -> LemmyResult<$response> {
+            if self.validate_forms {
+                LemmyForm::validate(&form)?;
+            }
+
+            let response = self
+                .client
+                .$name(LemmyRequest::with_jwt(form, self.state.jwt()), &self.headers)
+                .await?;
+            self.filter_response($path, response)
+        }
+    };
+}
+
+/// Generates the method of an endpoint for anonymous clients, if the endpoint can be called without logging in.
+macro_rules! anonymous_fn {
+    (None, $($rest:tt)*) => { typed_fn!($($rest)*); };
+    (Optional, $($rest:tt)*) => { typed_fn!($($rest)*); };
+    ($auth:ident, $($rest:tt)*) => {};
+}
+
+macro_rules! typed_fns {
+    ($(
+        $(#[$doc:meta])*
+        $name:ident($variant:ident):
+        $method:ident $path:literal, $auth:ident, ($major:literal, $minor:literal, $patch:literal):
+        $form:tt => $response:ty;
+    )+) => {
+        impl LemmyClient<Anonymous> {
+            $( anonymous_fn!($auth, $name, $form, $response, $method, $path, $(#[$doc])*); )+
+        }
+
+        impl LemmyClient<Authenticated> {
+            $( typed_fn!($name, $form, $response, $method, $path, $(#[$doc])*); )+
+        }
+    };
+}
+
+with_endpoints!(typed_fns);
+
+impl Anonymous {
+    fn jwt(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Authenticated {
+    fn jwt(&self) -> Option<String> {
+        Some(self.jwt.to_string())
+    }
+}
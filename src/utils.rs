@@ -1,3 +1,7 @@
+use crate::response::LemmyResult;
+use futures::{stream, Stream, TryStreamExt};
+use std::future::Future;
+
 macro_rules! impl_marker_trait {
     ($trait_name:ty, [$( $impler:ty ),+$(,)?]) => {
         $(
@@ -30,3 +34,43 @@ pub struct ClientOptions {
     /// If true, use HTTPS. If false, use HTTP
     pub secure: bool,
 }
+
+/// Streams the items of numbered pages, starting at `first_page` and ending at the first empty page.
+pub(crate) fn paged_stream<'a, T, F, Fut>(
+    first_page: i64,
+    mut fetch_page: F,
+) -> impl Stream<Item = LemmyResult<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(i64) -> Fut + 'a,
+    Fut: Future<Output = LemmyResult<Vec<T>>> + 'a,
+{
+    stream::try_unfold(Some(first_page), move |page| {
+        let items = page.map(&mut fetch_page);
+        async move {
+            let (Some(page), Some(items)) = (page, items) else {
+                return LemmyResult::Ok(None);
+            };
+            let items = items.await?;
+            let next_page = (!items.is_empty()).then_some(page + 1);
+
+            Ok(Some((
+                stream::iter(items.into_iter().map(LemmyResult::Ok)),
+                next_page,
+            )))
+        }
+    })
+    .try_flatten()
+}
+
+/// Waits for `duration` without blocking the thread. Works on both native and WASM.
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    if duration.is_zero() {
+        return;
+    }
+
+    #[cfg(target_family = "wasm")]
+    gloo_timers::future::sleep(duration).await;
+    #[cfg(not(target_family = "wasm"))]
+    futures_timer::Delay::new(duration).await;
+}
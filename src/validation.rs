@@ -0,0 +1,276 @@
+use crate::{form::LemmyForm, Error, LemmyClient};
+use lemmy_api_common::{
+    comment::{CreateComment, EditComment},
+    community::{CreateCommunity, EditCommunity},
+    post::{CreatePost, EditPost},
+    private_message::{CreatePrivateMessage, EditPrivateMessage},
+};
+use thiserror::Error as ThisError;
+use url::Url;
+
+/// Shortest post title Lemmy accepts.
+const POST_TITLE_MIN_LENGTH: usize = 3;
+/// Longest post title Lemmy accepts.
+const POST_TITLE_MAX_LENGTH: usize = 200;
+/// Longest post body Lemmy accepts.
+const POST_BODY_MAX_LENGTH: usize = 50_000;
+/// Longest comment, private message, or community description Lemmy accepts.
+const BODY_MAX_LENGTH: usize = 10_000;
+/// Longest URL Lemmy accepts.
+const URL_MAX_LENGTH: usize = 2000;
+/// Shortest community name Lemmy accepts.
+const ACTOR_NAME_MIN_LENGTH: usize = 3;
+/// Longest community name Lemmy accepts by default. Admins can change this in the site settings.
+const ACTOR_NAME_MAX_LENGTH: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+/// A problem with a form that would make Lemmy reject it.
+pub enum ValidationError {
+    /// The field is too short or too long.
+    #[error("{field} must be between {min} and {max} characters long")]
+    Length {
+        /// Name of the field.
+        field: &'static str,
+        /// Minimum number of characters.
+        min: usize,
+        /// Maximum number of characters.
+        max: usize,
+    },
+    /// The field contains a line break.
+    #[error("{field} must not contain line breaks")]
+    ContainsNewline {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// The field is empty or only whitespace.
+    #[error("{field} must not be empty")]
+    Empty {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// The field is not an HTTP(S) URL, or is too long.
+    #[error("{field} must be an http or https URL of at most {URL_MAX_LENGTH} characters")]
+    InvalidUrl {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// The field contains characters other than letters, numbers, and underscores.
+    #[error("{field} may only contain letters, numbers, and underscores")]
+    InvalidName {
+        /// Name of the field.
+        field: &'static str,
+    },
+}
+
+/// Turns the problems found with a form into an [`ErrorKind::Validation`](crate::ErrorKind::Validation) error with the `invalid_form` code, which keeps them in [`Error::validation_errors`].
+///
+/// ```
+/// use lemmy_client::{Error, ErrorKind, ValidationError};
+///
+/// let error = Error::from(vec![ValidationError::Empty { field: "name" }]);
+/// assert_eq!(error.kind(), ErrorKind::Validation);
+/// assert_eq!(error.message(), "invalid_form: name must not be empty");
+/// assert_eq!(error.validation_errors(), [ValidationError::Empty { field: "name" }]);
+/// ```
+impl From<Vec<ValidationError>> for Error {
+    fn from(errors: Vec<ValidationError>) -> Self {
+        let message = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        Error::with_validation_errors(format!("invalid_form: {message}"), errors)
+    }
+}
+
+/// Checks a form against the limits Lemmy enforces, without sending it.
+///
+/// Limits that admins can configure, such as the maximum community name length, are checked against Lemmy's defaults.
+pub trait Validate {
+    /// Returns every problem found with the form.
+    fn validate(&self) -> Result<(), Vec<ValidationError>>;
+}
+
+#[derive(Default)]
+struct Checks(Vec<ValidationError>);
+
+impl Checks {
+    fn length(&mut self, field: &'static str, value: &str, min: usize, max: usize) {
+        if !(min..=max).contains(&value.trim().chars().count()) {
+            self.0.push(ValidationError::Length { field, min, max });
+        }
+    }
+
+    fn max_length(&mut self, field: &'static str, value: Option<&str>, max: usize) {
+        if let Some(value) = value {
+            self.length(field, value, 0, max);
+        }
+    }
+
+    fn single_line(&mut self, field: &'static str, value: &str) {
+        if value.contains(['\n', '\r']) {
+            self.0.push(ValidationError::ContainsNewline { field });
+        }
+    }
+
+    fn not_empty(&mut self, field: &'static str, value: &str) {
+        if value.trim().is_empty() {
+            self.0.push(ValidationError::Empty { field });
+        }
+    }
+
+    fn url(&mut self, field: &'static str, url: Option<&Url>) {
+        if let Some(url) = url {
+            if !matches!(url.scheme(), "http" | "https") || url.as_str().len() > URL_MAX_LENGTH {
+                self.0.push(ValidationError::InvalidUrl { field });
+            }
+        }
+    }
+
+    fn url_str(&mut self, field: &'static str, url: Option<&str>) {
+        match url.filter(|url| !url.is_empty()).map(Url::parse) {
+            Some(Ok(url)) => self.url(field, Some(&url)),
+            Some(Err(_)) => self.0.push(ValidationError::InvalidUrl { field }),
+            None => {}
+        }
+    }
+
+    fn actor_name(&mut self, field: &'static str, name: &str) {
+        self.length(field, name, ACTOR_NAME_MIN_LENGTH, ACTOR_NAME_MAX_LENGTH);
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            self.0.push(ValidationError::InvalidName { field });
+        }
+    }
+
+    fn finish(self) -> Result<(), Vec<ValidationError>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+impl Validate for CreatePost {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        checks.length(
+            "name",
+            &self.name,
+            POST_TITLE_MIN_LENGTH,
+            POST_TITLE_MAX_LENGTH,
+        );
+        checks.single_line("name", &self.name);
+        checks.max_length("body", self.body.as_deref(), POST_BODY_MAX_LENGTH);
+        checks.url("url", self.url.as_ref());
+        checks.finish()
+    }
+}
+
+impl Validate for EditPost {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        if let Some(name) = &self.name {
+            checks.length("name", name, POST_TITLE_MIN_LENGTH, POST_TITLE_MAX_LENGTH);
+            checks.single_line("name", name);
+        }
+        checks.max_length("body", self.body.as_deref(), POST_BODY_MAX_LENGTH);
+        checks.url("url", self.url.as_ref());
+        checks.finish()
+    }
+}
+
+impl Validate for CreateComment {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        checks.not_empty("content", &self.content);
+        checks.max_length("content", Some(&self.content), BODY_MAX_LENGTH);
+        checks.finish()
+    }
+}
+
+impl Validate for EditComment {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        if let Some(content) = &self.content {
+            checks.not_empty("content", content);
+            checks.max_length("content", Some(content), BODY_MAX_LENGTH);
+        }
+        checks.finish()
+    }
+}
+
+impl Validate for CreateCommunity {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        checks.actor_name("name", &self.name);
+        checks.not_empty("title", &self.title);
+        checks.single_line("title", &self.title);
+        checks.max_length("description", self.description.as_deref(), BODY_MAX_LENGTH);
+        checks.url_str("icon", self.icon.as_deref());
+        checks.url_str("banner", self.banner.as_deref());
+        checks.finish()
+    }
+}
+
+impl Validate for EditCommunity {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        if let Some(title) = &self.title {
+            checks.not_empty("title", title);
+            checks.single_line("title", title);
+        }
+        checks.max_length("description", self.description.as_deref(), BODY_MAX_LENGTH);
+        checks.url_str("icon", self.icon.as_deref());
+        checks.url_str("banner", self.banner.as_deref());
+        checks.finish()
+    }
+}
+
+impl Validate for CreatePrivateMessage {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        checks.not_empty("content", &self.content);
+        checks.max_length("content", Some(&self.content), BODY_MAX_LENGTH);
+        checks.finish()
+    }
+}
+
+impl Validate for EditPrivateMessage {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut checks = Checks::default();
+        checks.not_empty("content", &self.content);
+        checks.max_length("content", Some(&self.content), BODY_MAX_LENGTH);
+        checks.finish()
+    }
+}
+
+macro_rules! impl_validated_form {
+    ($( $form:ty ),+$(,)?) => {
+        $(
+            impl LemmyForm for $form {
+                fn validate(&self) -> Result<(), Vec<ValidationError>> {
+                    Validate::validate(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_validated_form!(
+    CreatePost,
+    EditPost,
+    CreateComment,
+    EditComment,
+    CreateCommunity,
+    EditCommunity,
+    CreatePrivateMessage,
+    EditPrivateMessage,
+);
+
+impl LemmyClient {
+    /// Sets whether forms are checked with [`Validate`] before they are sent, returning an error without sending the request if they are invalid. Disabled by default.
+    pub fn set_validate_forms(&mut self, validate_forms: bool) {
+        self.validate_forms = validate_forms;
+    }
+}
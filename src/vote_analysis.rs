@@ -0,0 +1,264 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, utils::paged_stream, Error,
+    LemmyClient, LemmyRequest,
+};
+use futures::TryStreamExt;
+use lemmy_api_common::{
+    comment::ListCommentLikes,
+    lemmy_db_schema::{newtypes::PersonId, source::person::Person, SortType},
+    lemmy_db_views::structs::VoteView,
+    person::GetPersonDetails,
+    post::ListPostLikes,
+};
+use std::{collections::HashMap, time::Duration};
+
+/// How many votes are fetched at a time.
+const VOTE_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Options for [`LemmyClient::analyze_votes`].
+pub struct VoteAnalysisOptions {
+    /// How many of the newest posts of the user to analyze.
+    pub max_posts: i64,
+    /// How many of the newest comments of the user to analyze.
+    pub max_comments: i64,
+    /// Accounts younger than this, when the analysis runs, count as new.
+    pub new_account_age: Duration,
+    /// How many of the most active voters count as the small set that [`VoteFlag::ConcentratedVoters`] is about.
+    pub top_voters: usize,
+    /// The share of votes from the most active voters above which [`VoteFlag::ConcentratedVoters`] is raised, from 0 to 1.
+    pub concentration_threshold: f64,
+    /// The share of votes from new accounts above which [`VoteFlag::NewAccounts`] is raised, from 0 to 1.
+    pub new_account_threshold: f64,
+    /// How many votes the content needs in total before any flag is raised, since a few votes always look concentrated.
+    pub min_votes: usize,
+}
+
+impl Default for VoteAnalysisOptions {
+    fn default() -> Self {
+        Self {
+            max_posts: 20,
+            max_comments: 50,
+            new_account_age: Duration::from_secs(7 * 24 * 60 * 60),
+            top_voters: 5,
+            concentration_threshold: 0.5,
+            new_account_threshold: 0.5,
+            min_votes: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// How one account voted on the analyzed content.
+pub struct VoterSummary {
+    /// The account.
+    pub person: Person,
+    /// How many of the analyzed posts and comments it upvoted.
+    pub upvotes: usize,
+    /// How many of the analyzed posts and comments it downvoted.
+    pub downvotes: usize,
+    /// How old the account was when the analysis ran.
+    pub account_age: Duration,
+}
+
+impl VoterSummary {
+    /// How many of the analyzed posts and comments the account voted on.
+    pub fn votes(&self) -> usize {
+        self.upvotes + self.downvotes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A suspicious pattern found by [`LemmyClient::analyze_votes`].
+pub enum VoteFlag {
+    /// A small set of accounts cast much of the votes. Holds the accounts and their share of the votes.
+    ConcentratedVoters {
+        /// The most active voters.
+        voters: Vec<PersonId>,
+        /// Their share of all votes, from 0 to 1.
+        share: f64,
+    },
+    /// Much of the votes came from new accounts. See [`VoteAnalysisOptions::new_account_age`].
+    NewAccounts {
+        /// How many new accounts voted.
+        accounts: usize,
+        /// Their share of all votes, from 0 to 1.
+        share: f64,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// The votes on the recent content of a user, as returned by [`LemmyClient::analyze_votes`]. A starting point for investigating vote manipulation, not proof of it: popular users have regular fans too.
+pub struct VoteAnalysis {
+    /// How many posts were analyzed.
+    pub posts: usize,
+    /// How many comments were analyzed.
+    pub comments: usize,
+    /// How many votes the analyzed content got in total, not counting the user's own.
+    pub total_votes: usize,
+    /// Every account that voted, most votes first.
+    pub voters: Vec<VoterSummary>,
+    /// The suspicious patterns found.
+    pub flags: Vec<VoteFlag>,
+    /// Posts and comments whose votes could not be listed, by ActivityPub ID.
+    pub failed: Vec<(String, Error)>,
+}
+
+impl VoteAnalysis {
+    /// Summarizes votes and raises flags, once every vote was added to `voters`.
+    fn finish(&mut self, me: PersonId, options: &VoteAnalysisOptions) {
+        self.voters.retain(|voter| voter.person.id != me);
+        self.voters.sort_by(|a, b| {
+            b.votes()
+                .cmp(&a.votes())
+                .then_with(|| a.person.name.cmp(&b.person.name))
+        });
+        self.total_votes = self.voters.iter().map(VoterSummary::votes).sum();
+        if self.total_votes == 0 || self.total_votes < options.min_votes {
+            return;
+        }
+
+        let share = |votes: usize| votes as f64 / self.total_votes as f64;
+        let top = &self.voters[..options.top_voters.min(self.voters.len())];
+        let top_share = share(top.iter().map(VoterSummary::votes).sum());
+        if top_share > options.concentration_threshold {
+            self.flags.push(VoteFlag::ConcentratedVoters {
+                voters: top.iter().map(|voter| voter.person.id).collect(),
+                share: top_share,
+            });
+        }
+
+        let new_accounts = self
+            .voters
+            .iter()
+            .filter(|voter| voter.account_age < options.new_account_age)
+            .collect::<Vec<_>>();
+        let new_share = share(new_accounts.iter().map(|voter| voter.votes()).sum());
+        if new_share > options.new_account_threshold {
+            self.flags.push(VoteFlag::NewAccounts {
+                accounts: new_accounts.len(),
+                share: new_share,
+            });
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Lists the votes on the newest posts and comments of a user, and looks for suspicious patterns: votes from a small set of accounts, or from new accounts. Only moderators of the communities and admins can list votes.
+    ///
+    /// A post or comment whose votes could not be listed is skipped and reported in [`VoteAnalysis::failed`].
+    ///
+    /// ```no_run
+    /// # use lemmy_client::{lemmy_api_common::lemmy_db_schema::newtypes::PersonId, LemmyClient, VoteAnalysisOptions};
+    /// # async fn example(client: LemmyClient, suspect: PersonId, jwt: String) -> Result<(), lemmy_client::Error> {
+    /// let analysis = client
+    ///     .analyze_votes(suspect, &VoteAnalysisOptions::default(), Some(jwt))
+    ///     .await?;
+    /// for flag in &analysis.flags {
+    ///     println!("{flag:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// HTTP GET /user, HTTP GET /post/like/list, HTTP GET /comment/like/list
+    pub async fn analyze_votes(
+        &self,
+        person_id: PersonId,
+        options: &VoteAnalysisOptions,
+        jwt: Option<String>,
+    ) -> LemmyResult<VoteAnalysis> {
+        let details = |limit| {
+            let form = GetPersonDetails {
+                person_id: Some(person_id),
+                sort: Some(SortType::New),
+                limit: Some(limit),
+                ..Default::default()
+            };
+            self.client
+                .get_person(LemmyRequest::with_jwt(form, jwt.clone()), &self.headers)
+        };
+        let mut posts = details(options.max_posts).await?.posts;
+        posts.truncate(options.max_posts.max(0) as usize);
+        let mut comments = details(options.max_comments).await?.comments;
+        comments.truncate(options.max_comments.max(0) as usize);
+
+        let mut analysis = VoteAnalysis {
+            posts: posts.len(),
+            comments: comments.len(),
+            ..Default::default()
+        };
+        let mut voters = HashMap::<PersonId, VoterSummary>::new();
+        let mut count = |votes: Vec<VoteView>| {
+            let now = crate::now();
+            for vote in votes {
+                let voter = voters
+                    .entry(vote.creator.id)
+                    .or_insert_with(|| VoterSummary {
+                        account_age: (now - vote.creator.published).to_std().unwrap_or_default(),
+                        person: vote.creator,
+                        upvotes: 0,
+                        downvotes: 0,
+                    });
+                if vote.score > 0 {
+                    voter.upvotes += 1;
+                } else if vote.score < 0 {
+                    voter.downvotes += 1;
+                }
+            }
+        };
+
+        for post_view in &posts {
+            let post_id = post_view.post.id;
+            let votes = paged_stream(1, |page| {
+                let form = ListPostLikes {
+                    post_id,
+                    page: Some(page),
+                    limit: Some(VOTE_PAGE_SIZE),
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    self.client
+                        .list_post_likes(request, &self.headers)
+                        .await
+                        .map(|response| response.post_likes)
+                }
+            })
+            .try_collect()
+            .await;
+            match votes {
+                Ok(votes) => count(votes),
+                Err(e) => analysis.failed.push((post_view.post.ap_id.to_string(), e)),
+            }
+        }
+        for comment_view in &comments {
+            let comment_id = comment_view.comment.id;
+            let votes = paged_stream(1, |page| {
+                let form = ListCommentLikes {
+                    comment_id,
+                    page: Some(page),
+                    limit: Some(VOTE_PAGE_SIZE),
+                };
+                let request = LemmyRequest::with_jwt(form, jwt.clone());
+                async move {
+                    self.client
+                        .list_comment_likes(request, &self.headers)
+                        .await
+                        .map(|response| response.comment_likes)
+                }
+            })
+            .try_collect()
+            .await;
+            match votes {
+                Ok(votes) => count(votes),
+                Err(e) => analysis
+                    .failed
+                    .push((comment_view.comment.ap_id.to_string(), e)),
+            }
+        }
+
+        analysis.voters = voters.into_values().collect();
+        analysis.finish(person_id, options);
+        Ok(analysis)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    lemmy_client_trait::LemmyClientInternal, response::LemmyResult, LemmyClient, LemmyRequest,
+};
+use lemmy_api_common::{
+    comment::{CommentResponse, CreateCommentLike},
+    lemmy_db_schema::newtypes::{CommentId, PostId},
+    post::{CreatePostLike, PostResponse},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Mutex, PoisonError},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A vote on a post or comment.
+pub enum Vote {
+    /// An upvote.
+    Up,
+    /// A downvote.
+    Down,
+    /// No vote.
+    #[default]
+    Neutral,
+}
+
+impl Vote {
+    /// The score Lemmy uses for the vote.
+    pub fn score(self) -> i16 {
+        match self {
+            Self::Up => 1,
+            Self::Down => -1,
+            Self::Neutral => 0,
+        }
+    }
+
+    /// Gets the vote from a score, such as the `my_vote` field of a post or comment.
+    pub fn from_score(score: Option<i16>) -> Self {
+        match score {
+            Some(score) if score > 0 => Self::Up,
+            Some(score) if score < 0 => Self::Down,
+            _ => Self::Neutral,
+        }
+    }
+
+    /// The vote that results from pressing the button for `pressed` when `self` is the current vote, i.e. pressing the same button again removes the vote.
+    pub fn toggle(self, pressed: Vote) -> Self {
+        if self == pressed {
+            Self::Neutral
+        } else {
+            pressed
+        }
+    }
+}
+
+impl LemmyClient {
+    /// Votes on a post.
+    pub async fn vote_post(
+        &self,
+        post_id: PostId,
+        vote: Vote,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostResponse> {
+        let form = CreatePostLike {
+            post_id,
+            score: vote.score(),
+        };
+        self.client
+            .like_post(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+
+    /// Upvotes a post.
+    pub async fn upvote_post(
+        &self,
+        post_id: PostId,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostResponse> {
+        self.vote_post(post_id, Vote::Up, jwt).await
+    }
+
+    /// Downvotes a post.
+    pub async fn downvote_post(
+        &self,
+        post_id: PostId,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostResponse> {
+        self.vote_post(post_id, Vote::Down, jwt).await
+    }
+
+    /// Removes your vote from a post.
+    pub async fn unvote_post(
+        &self,
+        post_id: PostId,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostResponse> {
+        self.vote_post(post_id, Vote::Neutral, jwt).await
+    }
+
+    /// Votes on a comment.
+    pub async fn vote_comment(
+        &self,
+        comment_id: CommentId,
+        vote: Vote,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommentResponse> {
+        let form = CreateCommentLike {
+            comment_id,
+            score: vote.score(),
+        };
+        self.client
+            .like_comment(LemmyRequest::with_jwt(form, jwt), &self.headers)
+            .await
+    }
+
+    /// Upvotes a comment.
+    pub async fn upvote_comment(
+        &self,
+        comment_id: CommentId,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommentResponse> {
+        self.vote_comment(comment_id, Vote::Up, jwt).await
+    }
+
+    /// Downvotes a comment.
+    pub async fn downvote_comment(
+        &self,
+        comment_id: CommentId,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommentResponse> {
+        self.vote_comment(comment_id, Vote::Down, jwt).await
+    }
+
+    /// Removes your vote from a comment.
+    pub async fn unvote_comment(
+        &self,
+        comment_id: CommentId,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommentResponse> {
+        self.vote_comment(comment_id, Vote::Neutral, jwt).await
+    }
+}
+
+#[derive(Debug, Default)]
+/// Local record of the user's votes for optimistic UI updates.
+///
+/// The toggle methods update the cache before the request is sent, so a UI reading from the cache reflects the vote immediately. If the request fails, the previous vote is restored.
+pub struct VoteCache {
+    posts: Mutex<HashMap<PostId, Vote>>,
+    comments: Mutex<HashMap<CommentId, Vote>>,
+}
+
+fn get_vote<K: Eq + Hash>(votes: &Mutex<HashMap<K, Vote>>, id: &K) -> Vote {
+    votes
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(id)
+        .copied()
+        .unwrap_or_default()
+}
+
+fn set_vote<K: Eq + Hash>(votes: &Mutex<HashMap<K, Vote>>, id: K, vote: Vote) {
+    votes
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(id, vote);
+}
+
+impl VoteCache {
+    /// The vote the user has on a post, or [`Vote::Neutral`] if the post is not in the cache.
+    pub fn post_vote(&self, post_id: PostId) -> Vote {
+        get_vote(&self.posts, &post_id)
+    }
+
+    /// The vote the user has on a comment, or [`Vote::Neutral`] if the comment is not in the cache.
+    pub fn comment_vote(&self, comment_id: CommentId) -> Vote {
+        get_vote(&self.comments, &comment_id)
+    }
+
+    /// Records a vote on a post, e.g. from the `my_vote` field of a fetched post.
+    pub fn set_post_vote(&self, post_id: PostId, vote: Vote) {
+        set_vote(&self.posts, post_id, vote);
+    }
+
+    /// Records a vote on a comment, e.g. from the `my_vote` field of a fetched comment.
+    pub fn set_comment_vote(&self, comment_id: CommentId, vote: Vote) {
+        set_vote(&self.comments, comment_id, vote);
+    }
+
+    /// Presses the vote button for `pressed` on a post. See [`Vote::toggle`].
+    pub async fn toggle_post_vote(
+        &self,
+        client: &LemmyClient,
+        post_id: PostId,
+        pressed: Vote,
+        jwt: Option<String>,
+    ) -> LemmyResult<PostResponse> {
+        let previous = self.post_vote(post_id);
+        self.set_post_vote(post_id, previous.toggle(pressed));
+
+        let result = client
+            .vote_post(post_id, previous.toggle(pressed), jwt)
+            .await;
+        match &result {
+            Ok(response) => {
+                self.set_post_vote(post_id, Vote::from_score(response.post_view.my_vote))
+            }
+            Err(_) => self.set_post_vote(post_id, previous),
+        }
+
+        result
+    }
+
+    /// Presses the vote button for `pressed` on a comment. See [`Vote::toggle`].
+    pub async fn toggle_comment_vote(
+        &self,
+        client: &LemmyClient,
+        comment_id: CommentId,
+        pressed: Vote,
+        jwt: Option<String>,
+    ) -> LemmyResult<CommentResponse> {
+        let previous = self.comment_vote(comment_id);
+        self.set_comment_vote(comment_id, previous.toggle(pressed));
+
+        let result = client
+            .vote_comment(comment_id, previous.toggle(pressed), jwt)
+            .await;
+        match &result {
+            Ok(response) => {
+                self.set_comment_vote(comment_id, Vote::from_score(response.comment_view.my_vote))
+            }
+            Err(_) => self.set_comment_vote(comment_id, previous),
+        }
+
+        result
+    }
+}
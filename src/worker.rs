@@ -0,0 +1,194 @@
+use crate::{
+    response::LemmyResult, Error, ErrorKind, LemmyClient, LemmyForm, LemmyRequest, LemmyResponse,
+    Method,
+};
+use lemmy_api_common::sensitive::Sensitive;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+/// A form or response passed between threads as JSON.
+pub(crate) struct RawBody(pub(crate) serde_json::Value);
+
+impl LemmyForm for RawBody {}
+impl LemmyResponse for RawBody {}
+
+/// Reads a message posted by [`WorkerRequest::to_bytes`] or [`WorkerResponse::to_bytes`].
+fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> LemmyResult<T> {
+    serde_json::from_slice(bytes).map_err(|e| Error::new(format!("invalid_worker_message: {e}")))
+}
+
+#[cfg(target_family = "wasm")]
+/// Copies a message into an `ArrayBuffer`, returning it with the transfer list to post it with.
+fn to_transferable(bytes: Vec<u8>) -> (js_sys::ArrayBuffer, js_sys::Array) {
+    let buffer = js_sys::Uint8Array::from(bytes.as_slice()).buffer();
+    let transfer = js_sys::Array::of1(&buffer);
+    (buffer, transfer)
+}
+
+#[cfg(target_family = "wasm")]
+/// Reads the bytes of an `ArrayBuffer` or typed array received in a message event.
+fn from_transferable(data: &web_sys::wasm_bindgen::JsValue) -> Vec<u8> {
+    js_sys::Uint8Array::new(data).to_vec()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A request to an endpoint, posted from the main thread to a [`LemmyClient`] running in a Web Worker, which sends it with [`LemmyClient::handle_worker_request`]. This keeps heavy work, such as polling or archiving, off the main thread of browser apps.
+///
+/// Messages are JSON, so they can be posted as strings, or as `ArrayBuffer`s that are moved to the other thread rather than copied. On wasm, see `WorkerRequest::to_transferable`.
+///
+/// ```
+/// # use lemmy_client::{lemmy_api_common::post::GetPosts, Method, WorkerRequest};
+/// # fn example() -> Result<(), lemmy_client::Error> {
+/// let request = WorkerRequest::new(1, Method::GET, "post/list", GetPosts::default())?;
+/// let bytes = request.to_bytes();
+/// // Post the bytes to the worker, which reads them back.
+/// assert_eq!(WorkerRequest::from_bytes(&bytes)?, request);
+/// # Ok(())
+/// # }
+/// ```
+pub struct WorkerRequest {
+    /// Chosen by the sender to tell which request a [`WorkerResponse`] answers.
+    pub id: u64,
+    /// The HTTP method, such as `GET`.
+    pub method: String,
+    /// The path of the endpoint relative to `/api/v3`, such as `post/list`.
+    pub path: String,
+    /// The form, as JSON.
+    pub body: serde_json::Value,
+    /// The JWT to send the request with. Left out of the `Debug` output.
+    pub jwt: Option<Sensitive<String>>,
+}
+
+impl WorkerRequest {
+    /// Builds a request to the endpoint at `path` from a form, the way [`LemmyClient::send_custom`] sends it.
+    pub fn new<Form: LemmyForm>(
+        id: u64,
+        method: Method,
+        path: impl Into<String>,
+        request: impl Into<LemmyRequest<Form>>,
+    ) -> LemmyResult<Self> {
+        let LemmyRequest { body, jwt } = request.into();
+        let body = serde_json::to_value(body).map_err(|e| Error::new(e.to_string()))?;
+
+        Ok(Self {
+            id,
+            method: method.to_string(),
+            path: path.into(),
+            body,
+            jwt: jwt.map(Sensitive::new),
+        })
+    }
+
+    /// The request as a message to post.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("Worker requests always serialize")
+    }
+
+    /// Reads a request posted with [`WorkerRequest::to_bytes`]. Fails with `invalid_worker_message` if the message is not one.
+    pub fn from_bytes(bytes: &[u8]) -> LemmyResult<Self> {
+        from_bytes(bytes)
+    }
+
+    #[cfg(target_family = "wasm")]
+    /// The request as an `ArrayBuffer`, with the transfer list to pass to `postMessage` so the buffer is moved to the worker instead of copied.
+    ///
+    /// Only available on wasm.
+    pub fn to_transferable(&self) -> (js_sys::ArrayBuffer, js_sys::Array) {
+        to_transferable(self.to_bytes())
+    }
+
+    #[cfg(target_family = "wasm")]
+    /// Reads a request from the data of a message event, posted with [`WorkerRequest::to_transferable`] or as a typed array.
+    ///
+    /// Only available on wasm.
+    pub fn from_transferable(data: &web_sys::wasm_bindgen::JsValue) -> LemmyResult<Self> {
+        from_bytes(&from_transferable(data))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The response to a [`WorkerRequest`], posted back from the worker to the main thread.
+pub struct WorkerResponse {
+    /// The id of the request this answers.
+    pub id: u64,
+    /// The response as JSON, or the error the request failed with.
+    pub result: Result<serde_json::Value, Error>,
+}
+
+impl WorkerResponse {
+    /// Parses the response into the type the endpoint returns, or returns the error the request failed with.
+    pub fn parse<Response: LemmyResponse>(self) -> LemmyResult<Response> {
+        serde_json::from_value(self.result?)
+            .map_err(|e| Error::with_kind(ErrorKind::Decode, e.to_string()))
+    }
+
+    /// The response as a message to post.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("Worker responses always serialize")
+    }
+
+    /// Reads a response posted with [`WorkerResponse::to_bytes`]. Fails with `invalid_worker_message` if the message is not one.
+    pub fn from_bytes(bytes: &[u8]) -> LemmyResult<Self> {
+        from_bytes(bytes)
+    }
+
+    #[cfg(target_family = "wasm")]
+    /// The response as an `ArrayBuffer`, with the transfer list to pass to `postMessage` so the buffer is moved to the main thread instead of copied.
+    ///
+    /// Only available on wasm.
+    pub fn to_transferable(&self) -> (js_sys::ArrayBuffer, js_sys::Array) {
+        to_transferable(self.to_bytes())
+    }
+
+    #[cfg(target_family = "wasm")]
+    /// Reads a response from the data of a message event, posted with [`WorkerResponse::to_transferable`] or as a typed array.
+    ///
+    /// Only available on wasm.
+    pub fn from_transferable(data: &web_sys::wasm_bindgen::JsValue) -> LemmyResult<Self> {
+        from_bytes(&from_transferable(data))
+    }
+}
+
+impl LemmyClient {
+    /// Sends a request posted from another thread, returning the response to post back. Requests with a method other than `GET`, `POST`, or `PUT` fail with `invalid_worker_message`.
+    ///
+    /// The client works the same in a dedicated Web Worker as on the main thread, so a worker can run one and answer the requests of the page:
+    ///
+    /// ```no_run
+    /// # use lemmy_client::{LemmyClient, WorkerRequest};
+    /// # async fn example(client: LemmyClient, message: Vec<u8>) -> Result<Vec<u8>, lemmy_client::Error> {
+    /// let request = WorkerRequest::from_bytes(&message)?;
+    /// let response = client.handle_worker_request(request).await;
+    /// // Post the bytes back to the main thread.
+    /// Ok(response.to_bytes())
+    /// # }
+    /// ```
+    pub async fn handle_worker_request(&self, request: WorkerRequest) -> WorkerResponse {
+        let WorkerRequest {
+            id,
+            method,
+            path,
+            body,
+            jwt,
+        } = request;
+        let result = match method.parse::<Method>() {
+            Ok(method @ (Method::GET | Method::POST | Method::PUT)) => self
+                .send_custom::<RawBody, RawBody>(
+                    method,
+                    &path,
+                    LemmyRequest {
+                        body: RawBody(body),
+                        jwt: jwt.map(Sensitive::into_inner),
+                    },
+                )
+                .await
+                .map(|RawBody(response)| response),
+            _ => Err(Error::new(format!(
+                "invalid_worker_message: unsupported method {method}"
+            ))),
+        };
+
+        WorkerResponse { id, result }
+    }
+}